@@ -31,6 +31,7 @@ pub struct TestApp {
     pub checked: bool,
     popup: Option<DialogHandle<PopUp>>,
     popups: Vec<DialogHandle<PopUp>>,
+    popup_count: u32,
 }
 
 impl Default for TestApp {
@@ -41,6 +42,7 @@ impl Default for TestApp {
             checked: false,
             popup: None,
             popups: Vec::new(),
+            popup_count: 0,
         }
     }
 }
@@ -58,6 +60,18 @@ impl Dialog for TestApp {
     }
 
     fn show(&mut self, ctx: &egui::Context, windower: &mut Windower) {
+        egui::TopBottomPanel::top("menu").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                ui.menu_button("Windows", |ui| {
+                    for (window_id, title) in windower.open_windows() {
+                        if ui.button(title).clicked() {
+                            windower.focus_window(window_id);
+                            ui.close_menu();
+                        }
+                    }
+                });
+            });
+        });
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("My egui Application");
             ui.horizontal(|ui| {
@@ -71,7 +85,11 @@ impl Dialog for TestApp {
                 info!("Button clicked, {}", self.age);
             }
             if ui.button("Open a new window").clicked() {
-                let new_window = windower.new_window(PopUp { value: 12 });
+                self.popup_count += 1;
+                let new_window = windower.new_window(PopUp {
+                    number: self.popup_count,
+                    value: 12,
+                });
                 if let Some(old_window) = self.popup.take() {
                     self.popups.push(old_window);
                 }
@@ -116,6 +134,7 @@ impl Dialog for TestApp {
 }
 
 struct PopUp {
+    number: u32,
     value: i32,
 }
 
@@ -142,6 +161,7 @@ impl Dialog for PopUp {
 
     fn get_window_options(&self) -> WindowOptions {
         WindowOptions {
+            title: format!("Popup {}", self.number),
             enabled_buttons: WindowButtons::CLOSE,
             resizeable: true,
             size: Some(Size {
@@ -99,6 +99,7 @@ impl<T: Dialog + ?Sized> WeakDialogHandle<T> {
 /// Allows the creating of windows from inside a egui context.
 pub struct Windower<'a> {
     events: &'a mut Vec<DialogEvent>,
+    open_windows: &'a [(WindowId, String)],
 }
 
 impl<'a> Windower<'a> {
@@ -108,6 +109,20 @@ impl<'a> Windower<'a> {
             .push(DialogEvent::CreateWindow(dialog_handle.clone().to_dyn()));
         dialog_handle
     }
+
+    /// Return the id and the title of all open windows, sorted by their title.
+    ///
+    /// The list is taken before the dialog is shown. Windows that are created or closed
+    /// while the dialog is shown appear in the list the next time the dialog is shown.
+    pub fn open_windows(&self) -> Vec<(WindowId, String)> {
+        self.open_windows.to_vec()
+    }
+
+    /// Bring a window to the front and give it focus.
+    /// Does nothing if the window is no longer open.
+    pub fn focus_window(&mut self, window_id: WindowId) {
+        self.events.push(DialogEvent::FocusWindow(window_id));
+    }
 }
 
 /// Wrapps around a specific dialog object and collects events that
@@ -135,9 +150,10 @@ impl<T: Dialog + ?Sized> DialogContainer<T> {
     }
 
     /// Show this dialog.
-    fn show(&mut self, egui_ctx: &Context) {
+    fn show(&mut self, egui_ctx: &Context, open_windows: &[(WindowId, String)]) {
         let mut windower = Windower {
             events: &mut self.events,
+            open_windows,
         };
         self.dialog.show(egui_ctx, &mut windower);
     }
@@ -245,11 +261,13 @@ pub(crate) enum DialogEvent {
     CreateWindow(DialogHandle<dyn Dialog>),
     RequestRedraw,
     Close,
+    FocusWindow(WindowId),
 }
 
 /// An os window that can display a dialog.
 pub(crate) struct DialogWindow {
     dialog: WeakDialogHandle<dyn Dialog>,
+    title: String,
     redraw_time: Option<Instant>,
     modal: Option<WindowId>,
     backend: Backend,
@@ -257,14 +275,19 @@ pub(crate) struct DialogWindow {
 
 impl DialogWindow {
     /// Create a new os window backend.
-    pub fn new(dialog: DialogHandle<dyn Dialog>, backend: Backend) -> Self {
+    pub fn new(
+        dialog: DialogHandle<dyn Dialog>,
+        backend: Backend,
+        open_windows: &[(WindowId, String)],
+    ) -> Self {
         let mut dialog_window = DialogWindow {
+            title: dialog.borrow_dialog().get_window_options().title,
             dialog: dialog.as_weak(),
             redraw_time: None,
             modal: None,
             backend,
         };
-        dialog_window.run_and_paint();
+        dialog_window.run_and_paint(open_windows);
         dialog_window.backend.window.set_visible(true);
         dialog_window
     }
@@ -299,14 +322,14 @@ impl DialogWindow {
     }
 
     /// Run the dialog on this window.
-    pub fn run_and_paint(&mut self) {
+    pub fn run_and_paint(&mut self, open_windows: &[(WindowId, String)]) {
         let dialog = match self.dialog.upgrade() {
             Some(dialog) => dialog,
             // If the dialog has been dropped then this window should be destroyed aswell.
             None => return,
         };
 
-        let repaint_after = self.backend.run_and_paint(dialog);
+        let repaint_after = self.backend.run_and_paint(dialog, open_windows);
 
         if repaint_after.is_zero() {
             // We want to redraw on the next frame so we create a request for right now.
@@ -338,6 +361,16 @@ impl DialogWindow {
         self.backend.window.id()
     }
 
+    /// Return the title of the os window.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Bring the os window to the front and give it focus.
+    pub fn focus(&self) {
+        self.backend.window.focus_window();
+    }
+
     /// Set this window to be modal to another window.
     ///
     /// If given `Some(WindowId)`, this window will be disabled and receive no window events
@@ -448,7 +481,11 @@ impl Backend {
     }
 
     /// Run the egui dialog on this window.
-    pub fn run_and_paint(&mut self, dialog: DialogHandle<dyn Dialog>) -> Duration {
+    pub fn run_and_paint(
+        &mut self,
+        dialog: DialogHandle<dyn Dialog>,
+        open_windows: &[(WindowId, String)],
+    ) -> Duration {
         // Gather input (mouse, touches, keyboard, screen size, etc):
         let raw_input: egui::RawInput = self.state.take_egui_input(&self.window);
 
@@ -458,7 +495,7 @@ impl Backend {
             textures_delta,
             shapes,
         } = self.context.run(raw_input, |egui_ctx| {
-            dialog.borrow_dialog_mut().show(egui_ctx);
+            dialog.borrow_dialog_mut().show(egui_ctx, open_windows);
         });
 
         self.state
@@ -52,6 +52,20 @@ impl WindowTree {
         self.tree.is_empty()
     }
 
+    /// Return the id and title of all open windows, sorted by their title.
+    fn open_windows(&self) -> Vec<(WindowId, String)> {
+        let mut open_windows: Vec<(WindowId, String)> = self
+            .tree
+            .values()
+            .map(|dialog_window| {
+                let dialog_window = dialog_window.borrow();
+                (dialog_window.window_id(), dialog_window.title().to_owned())
+            })
+            .collect();
+        open_windows.sort_by(|(_, t1), (_, t2)| t1.cmp(t2));
+        open_windows
+    }
+
     /// Return the dialog window for a given window id.
     fn get(&self, window_id: WindowId) -> Option<&RefCell<DialogWindow>> {
         self.tree.get(&window_id)
@@ -75,7 +89,11 @@ impl WindowTree {
         };
 
         let backend = Backend::new(window_target, &window_options, owner);
-        let dialog_window = RefCell::new(DialogWindow::new(dialog_handle, backend));
+        let dialog_window = RefCell::new(DialogWindow::new(
+            dialog_handle,
+            backend,
+            &self.open_windows(),
+        ));
 
         // add window to tree
         let id = dialog_window.borrow().window_id();
@@ -101,7 +119,11 @@ impl WindowTree {
             &dialog_handle.borrow_dialog().get_window_options(),
             None,
         );
-        let dialog_window = RefCell::new(DialogWindow::new(dialog_handle, backend));
+        let dialog_window = RefCell::new(DialogWindow::new(
+            dialog_handle,
+            backend,
+            &self.open_windows(),
+        ));
         let id = dialog_window.borrow().window_id();
         self.tree.add_node(id, dialog_window);
     }
@@ -168,8 +190,9 @@ pub fn run_event_loop<T: Dialog + 'static>(dialog: T) {
 
             // Redraw the requested window.
             Event::RedrawRequested(window_id) => {
+                let open_windows = window_tree.open_windows();
                 if let Some(app_state) = window_tree.get(window_id) {
-                    app_state.borrow_mut().run_and_paint();
+                    app_state.borrow_mut().run_and_paint(&open_windows);
                 }
             }
 
@@ -192,6 +215,11 @@ pub fn run_event_loop<T: Dialog + 'static>(dialog: T) {
                         dialog::DialogEvent::Close => {
                             window_tree.close_window(src_window_id);
                         }
+                        dialog::DialogEvent::FocusWindow(window_id) => {
+                            if let Some(dialog_window) = window_tree.get(window_id) {
+                                dialog_window.borrow().focus();
+                            }
+                        }
                     }
                 }
 
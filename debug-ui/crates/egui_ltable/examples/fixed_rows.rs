@@ -46,6 +46,7 @@ fn show_table(ui: &mut Ui) {
         .column(Column::fill(3.0).min_width(300.0))
         .scroll(true, true)
         .striped(true)
+        .reorder_columns(true)
         .show(ui, |table| {
             let r = table.row(
                 Row::new()
@@ -74,6 +75,8 @@ fn show_table(ui: &mut Ui) {
                         .height(40.0)
                         .hover_highlight(true)
                         .highlight(i == 5)
+                        .selectable(true)
+                        .id(egui::Id::new(i))
                         .sense(Sense::click()),
                     |row| {
                         let r = row.cell_sense(Sense::click(), |ui| {
@@ -1,12 +1,18 @@
-use std::{collections::VecDeque, ops::RangeInclusive};
+use std::{
+    collections::{HashSet, VecDeque},
+    ops::RangeInclusive,
+};
 
 use egui::{
-    pos2, vec2, Color32, Id, Layout, NumExt, Painter, Pos2, Rect, Response, Sense, Stroke, Ui, Vec2,
+    epaint::Mesh, pos2, vec2, Color32, Id, Layout, Modifiers, NumExt, Painter, Pos2, Rect,
+    Response, Sense, Shape, Stroke, Ui, Vec2,
 };
 
 #[derive(Default, Debug, Clone)]
 struct TableState {
     columns: Vec<ColumnState>,
+    /// The combined height of the pinned rows in the last frame.
+    pinned_height: f32,
 }
 
 impl TableState {
@@ -30,6 +36,9 @@ impl TableState {
 struct ColumnState {
     width: f32,
     pos: i32,
+    content_version: u64,
+    /// If the column should be measured again the next time it is layed out.
+    measure: bool,
 }
 
 //  -------------------------------------------------------------------------------------
@@ -40,12 +49,18 @@ pub struct Row {
     height: f32,
     /// If the row is fixed to the visible area.
     fixed: bool,
+    /// If the row is pinned to the top of the body.
+    pinned: bool,
     /// The interaction sense of this row.
     sense: Sense,
     /// If the row should be highlighted if hovered.
     hover_highlight: bool,
     /// If the row should be highlighted.
     highlight: bool,
+    /// If the row can be selected by clicking it.
+    selectable: bool,
+    /// The id of the row in the selection.
+    id: Option<Id>,
 }
 
 impl Row {
@@ -53,9 +68,12 @@ impl Row {
         Self {
             height: 40.0,
             fixed: false,
+            pinned: false,
             sense: Sense::hover(),
             hover_highlight: false,
             highlight: false,
+            selectable: false,
+            id: None,
         }
     }
 
@@ -68,13 +86,32 @@ impl Row {
     /// Set the row to be fixed. This will make sure that this row is always
     /// visible in the viewport when the table is scrolled.
     ///
-    /// If vertical scrolling is disabled, this setting will have no effect
-    /// since all rows are always visible.
+    /// If vertical scrolling is disabled, the row is fixed to the visible
+    /// clip of the parent ui instead.
     pub fn fixed(mut self, fixed: bool) -> Self {
         self.fixed = fixed;
         self
     }
 
+    /// Pin the row to the top of the body. Pinned rows are shown above all other
+    /// rows that scroll, regardless of the order in which they are added.
+    /// Like fixed rows they stay visible when the table is scrolled.
+    ///
+    /// Pinned rows are placed below the fixed rows that are added before the first
+    /// row that is not fixed, which is usually the header. Multiple pinned rows keep
+    /// the order in which they are added.
+    ///
+    /// Stripes and group boundaries use the order in which the rows are added to the body,
+    /// not the order in which they are shown. The stripes of the other rows do not change
+    /// when a row is pinned but the pinned rows may have the same stripe next to each other.
+    ///
+    /// The space for the pinned rows is measured in the previous frame. When the height
+    /// of the pinned rows changes the table takes one frame to adjust.
+    pub fn pin_to_top(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
     /// Set the sense level the row should respond to.
     ///
     /// A sense other than `Sense::hover()` will stop the
@@ -97,6 +134,112 @@ impl Row {
         self.highlight = highlight;
         self
     }
+
+    /// Set if the row can be selected by clicking it.
+    ///
+    /// A click selects only this row. With the command key held the row is added to or
+    /// removed from the selection. With shift held every row from the last clicked row
+    /// to this row is selected. A selectable row needs an [`Row::id`] to be selected.
+    ///
+    /// Default is `false`.
+    pub fn selectable(mut self, selectable: bool) -> Self {
+        self.selectable = selectable;
+        self
+    }
+
+    /// Set the id of the row. The selection of the table refers to rows by this id.
+    /// The id must be unique within the table and stay the same between frames.
+    pub fn id(mut self, id: impl Into<Id>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+}
+
+/// The rows that are selected in a table.
+///
+/// The selection is kept between frames. Ids of rows that are no longer shown stay selected
+/// until they are deselected or the selection is cleared.
+#[derive(Default, Debug, Clone)]
+pub struct Selection {
+    /// The ids of the selected rows.
+    selected: HashSet<Id>,
+    /// The row that was clicked last. A shift click selects the rows from here.
+    anchor: Option<Id>,
+}
+
+impl Selection {
+    fn load(ui: &Ui, selection_id: Id) -> Self {
+        ui.data_mut(|d| d.get_temp::<Self>(selection_id))
+            .unwrap_or_default()
+    }
+
+    fn store(self, ui: &Ui, selection_id: Id) {
+        ui.data_mut(|d| d.insert_temp(selection_id, self));
+    }
+
+    /// If the row with this id is selected.
+    pub fn is_selected(&self, id: impl Into<Id>) -> bool {
+        self.selected.contains(&id.into())
+    }
+
+    /// Add a row to the selection.
+    pub fn select(&mut self, id: impl Into<Id>) {
+        let id = id.into();
+        self.selected.insert(id);
+        self.anchor = Some(id);
+    }
+
+    /// Remove a row from the selection.
+    pub fn deselect(&mut self, id: impl Into<Id>) {
+        self.selected.remove(&id.into());
+    }
+
+    /// Select only the row with this id.
+    pub fn select_only(&mut self, id: impl Into<Id>) {
+        self.selected.clear();
+        self.select(id);
+    }
+
+    /// Deselect all rows.
+    pub fn clear(&mut self) {
+        self.selected.clear();
+        self.anchor = None;
+    }
+
+    /// If no row is selected.
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    /// The ids of the selected rows in no particular order.
+    pub fn selected(&self) -> impl Iterator<Item = Id> + '_ {
+        self.selected.iter().copied()
+    }
+
+    /// Change the selection for a click on the row `id`.
+    /// `rows` are the ids of the selectable rows in the order they were added.
+    fn click(&mut self, id: Id, modifiers: Modifiers, rows: &[Id]) {
+        let anchor_index = self
+            .anchor
+            .and_then(|anchor| rows.iter().position(|row| *row == anchor));
+        let index = rows.iter().position(|row| *row == id);
+        match (anchor_index, index) {
+            (Some(anchor_index), Some(index)) if modifiers.shift => {
+                if !modifiers.command {
+                    self.selected.clear();
+                }
+                let range = anchor_index.min(index)..=anchor_index.max(index);
+                self.selected.extend(&rows[range]);
+            }
+            _ if modifiers.command => {
+                if !self.selected.remove(&id) {
+                    self.selected.insert(id);
+                }
+                self.anchor = Some(id);
+            }
+            _ => self.select_only(id),
+        }
+    }
 }
 
 /// Configure a table column.
@@ -116,6 +259,8 @@ pub struct Column {
     fixed: bool,
     /// The layout to use for this column.
     layout: Layout,
+    /// The version of the content of this column.
+    content_version: u64,
 }
 
 impl Column {
@@ -136,6 +281,7 @@ impl Column {
             resizeable: false,
             fixed: false,
             layout: Layout::left_to_right(egui::Align::Min).with_main_wrap(false),
+            content_version: 0,
         }
     }
 
@@ -211,8 +357,8 @@ impl Column {
     }
 
     /// Set the column to be fixed. A fixed column is always visible
-    /// in the viewport. Has no effect if horizonzal scrolling is disabled since
-    /// all columns are always visible.
+    /// in the viewport. If horizontal scrolling is disabled, the column is
+    /// fixed to the visible clip of the parent ui instead.
     pub fn fixed(mut self, fixed: bool) -> Self {
         self.fixed = fixed;
         self
@@ -224,6 +370,22 @@ impl Column {
         self
     }
 
+    /// Set the version of the content of this column.
+    ///
+    /// An auto sized column is only sized to its content the first time it is displayed.
+    /// Changing the version makes the column measure its content again for one frame.
+    /// Use this when the content of the column changes, for example when the column
+    /// shows a different value after a change in configuration.
+    ///
+    /// Only the width of this column is recalculated. If this column was resized manually,
+    /// that resize is replaced by the new content width. Other columns keep their width.
+    ///
+    /// Default is 0.
+    pub fn content_version(mut self, version: u64) -> Self {
+        self.content_version = version;
+        self
+    }
+
     fn is_auto_sized(&self) -> bool {
         self.fill_share.is_none() && self.initial_width.is_none()
     }
@@ -265,6 +427,8 @@ struct ColumnLayout {
     fixed: ColumnFixed,
     /// If this is the first time the column is layed out.
     first_time: bool,
+    /// If the width of the column should be reset to its initial width.
+    reset: bool,
 }
 
 #[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
@@ -275,7 +439,7 @@ enum ColumnFixed {
     Right,
 }
 
-pub struct Table {
+pub struct Table<'a> {
     /// The list of defined columns.
     columns: Vec<Column>,
     /// If horizontal scroll is enabled.
@@ -284,22 +448,43 @@ pub struct Table {
     v_scroll: bool,
     /// If every odd row should be highlighted.
     striped: bool,
+    /// Row indices where a new group of rows starts.
+    group_boundaries: Vec<usize>,
     /// If lines seperating the columns are enabled.
     column_lines: bool,
+    /// The index of the column that takes up the remaining width of the table.
+    fill_remaining: Option<usize>,
+    /// If the layout of the columns should be reset.
+    reset_layout: bool,
     /// If resizing of rows is possible for the entire height of the
     /// table or only for the header row.
     resize_full_height: bool,
+    /// If shadows are drawn along fixed rows and columns when content is scrolled under them.
+    scroll_shadows: bool,
+    /// If the columns can be reordered by dragging their header.
+    reorder_columns: bool,
+    /// Called when the user finishes resizing a column.
+    on_resize: Option<Box<dyn FnMut(usize, f32) + 'a>>,
+    /// Receives the text of the visible cells.
+    visible_text: Option<&'a mut Vec<Vec<String>>>,
 }
 
-impl Table {
+impl<'a> Table<'a> {
     pub fn new() -> Self {
         Self {
             columns: Vec::new(),
             h_scroll: false,
             v_scroll: false,
             striped: false,
+            group_boundaries: Vec::new(),
             column_lines: false,
+            fill_remaining: None,
+            reset_layout: false,
+            reorder_columns: false,
             resize_full_height: true,
+            scroll_shadows: false,
+            on_resize: None,
+            visible_text: None,
         }
     }
 
@@ -310,6 +495,11 @@ impl Table {
     }
 
     /// Set the scrollig behavior of the table.
+    ///
+    /// If both directions are disabled the table does not create a scroll area
+    /// of its own and is allocated directly in the parent ui. This allows the table
+    /// to be placed inside of a scroll area of the parent. Fixed rows and columns are then
+    /// kept inside the visible clip rect of the parent.
     pub fn scroll(mut self, h_scroll: bool, v_scroll: bool) -> Self {
         self.h_scroll = h_scroll;
         self.v_scroll = v_scroll;
@@ -322,12 +512,59 @@ impl Table {
         self
     }
 
+    /// Set the row indices where a new group of rows starts.
+    ///
+    /// A heavier separator line is drawn above the first row of each group and the
+    /// stripe parity restarts so that the first row of every group is not highlighted.
+    /// The indices count every row added to the body, including header rows.
+    pub fn group_boundaries(mut self, boundaries: &[usize]) -> Self {
+        self.group_boundaries = boundaries.to_vec();
+        self
+    }
+
     /// Whether to draw lines seperating the columns or not.
     pub fn column_lines(mut self, lines: bool) -> Self {
         self.column_lines = lines;
         self
     }
 
+    /// Give the remaining width of the table to the column at `column_index`.
+    ///
+    /// After all other columns have taken their width, the chosen column is set to
+    /// the leftover width every frame, respecting its min and max width.
+    /// This keeps the column stretched when the available width changes.
+    ///
+    /// Since the width is recalculated every frame, resizing this column manually
+    /// has no lasting effect. Resizing any other column takes the width from this column
+    /// instead. It is best to make this column not resizeable.
+    /// Fill columns already take up all of the available width, so when they are used
+    /// together with this option the chosen column is left at its min width.
+    pub fn fill_remaining_into(mut self, column_index: usize) -> Self {
+        self.fill_remaining = Some(column_index);
+        self
+    }
+
+    /// Reset the width and position of all columns to their initial values.
+    ///
+    /// The layout of resizeable columns can also be reset by the user with the context menu
+    /// of a column seperator.
+    pub fn reset_layout(mut self, reset: bool) -> Self {
+        self.reset_layout = reset;
+        self
+    }
+
+    /// Whether or not the columns can be reordered by dragging their header.
+    ///
+    /// The header are the fixed rows at the top of the table. The position of a column
+    /// is stored with its width. The cells of a row are still added in the order the
+    /// columns were defined in, only the position where they are shown changes.
+    ///
+    /// Default is `false`.
+    pub fn reorder_columns(mut self, reorder: bool) -> Self {
+        self.reorder_columns = reorder;
+        self
+    }
+
     /// Whether or not the resizing of rows can be done along the entire
     /// height of the table or only on the header rows.
     pub fn resize_full_height(mut self, full_height: bool) -> Self {
@@ -335,7 +572,60 @@ impl Table {
         self
     }
 
+    /// Whether or not to draw a shadow along the inner edge of fixed rows and columns
+    /// when content is scrolled underneath them.
+    ///
+    /// A shadow below the fixed rows at the top and right of the fixed columns on the left is
+    /// drawn when the table is scrolled away from its origin. A shadow above the fixed rows
+    /// at the bottom and left of the fixed columns on the right is drawn when there is more
+    /// content hidden behind them.
+    pub fn scroll_shadows(mut self, scroll_shadows: bool) -> Self {
+        self.scroll_shadows = scroll_shadows;
+        self
+    }
+
+    /// Set a callback that is called when the user finishes resizing a column.
+    ///
+    /// The callback receives the index of the column in the order the columns were added
+    /// and the width of the column after the resize. This can be used to store the width
+    /// of the columns outside of the egui memory.
+    pub fn on_resize(mut self, on_resize: impl FnMut(usize, f32) + 'a) -> Self {
+        self.on_resize = Some(Box::new(on_resize));
+        self
+    }
+
+    /// Collect the text of the visible cells into `cells`.
+    ///
+    /// Only cells added with [`RowUi::cell_text`] have a text. After the table is shown, `cells`
+    /// contains an entry for every visible row that has a visible cell with text. Each entry
+    /// has the text of every visible cell of the row in order. Cells without text are empty.
+    /// This can be used to copy the visible part of the table to the clipboard.
+    pub fn visible_cells_text(mut self, cells: &'a mut Vec<Vec<String>>) -> Self {
+        self.visible_text = Some(cells);
+        self
+    }
+
+    /// Clear the stored layout of the table that is shown in this `ui`.
+    ///
+    /// The columns return to their initial widths and positions the next time the table
+    /// is shown. Unlike [`Table::reset_layout`] this can be called from anywhere the `ui`
+    /// of the table is available, for example from a button next to the table.
+    pub fn reset_state(ui: &Ui) {
+        let state_id = ui.id().with("_table_state");
+        ui.data_mut(|d| d.remove::<TableState>(state_id));
+    }
+
     pub fn show(mut self, ui: &mut Ui, add_body_content: impl FnOnce(&mut Body)) {
+        // The state is stored with the id of the parent ui so that it can be found
+        // without knowing the ids of the scroll area.
+        let table_id = ui.id();
+        if !self.h_scroll && !self.v_scroll {
+            // Without a scroll area the clip of the parent is the visible area of the table.
+            let clip = ui.clip_rect();
+            self.show_body(table_id, clip, ui, add_body_content);
+            return;
+        }
+
         let mut child_ui = ui.child_ui(ui.available_rect_before_wrap(), *ui.layout());
         child_ui.style_mut().spacing.scroll_bar_inner_margin = 0.0;
 
@@ -344,7 +634,7 @@ impl Table {
             (true, true) => egui::ScrollArea::both(),
             (true, false) => egui::ScrollArea::horizontal(),
             (false, true) => egui::ScrollArea::vertical(),
-            (false, false) => egui::ScrollArea::neither(),
+            (false, false) => unreachable!("A table without scrolling has no scroll area"),
         }
         .auto_shrink([true, true])
         .show(&mut child_ui, |ui| {
@@ -356,19 +646,27 @@ impl Table {
                     (true, true) => vec2(width, height),
                     (true, false) => vec2(width, f32::INFINITY),
                     (false, true) => vec2(f32::INFINITY, height),
-                    (false, false) => vec2(f32::INFINITY, f32::INFINITY),
+                    (false, false) => unreachable!("A table without scrolling has no scroll area"),
                 },
             );
 
-            self.show_body(clip, ui, add_body_content);
+            self.show_body(table_id, clip, ui, add_body_content);
         });
 
         ui.allocate_rect(child_ui.min_rect(), Sense::hover());
     }
 
-    fn show_body(&mut self, clip: Rect, ui: &mut Ui, add_body_content: impl FnOnce(&mut Body)) {
-        let state_id = ui.id().with("_table_state");
+    fn show_body(
+        &mut self,
+        table_id: Id,
+        clip: Rect,
+        ui: &mut Ui,
+        add_body_content: impl FnOnce(&mut Body),
+    ) {
+        let state_id = table_id.with("_table_state");
         let table_state = TableState::load(ui, state_id);
+        let selection_id = table_id.with("_table_selection");
+        let selection = Selection::load(ui, selection_id);
 
         let table_layout = self.layout_columns(
             &table_state,
@@ -378,20 +676,49 @@ impl Table {
             clip,
         );
 
+        if let Some(cells) = &mut self.visible_text {
+            cells.clear();
+        }
         let mut table_body = Body {
+            visible_text: self.visible_text.as_deref_mut(),
             table_layout,
             cursor: ui.cursor().min,
             ui,
             row_count: 0,
             striped: self.striped,
+            group_boundaries: &self.group_boundaries,
+            group_start: 0,
+            pinned_block: None,
+            pinned_reserved: table_state.pinned_height,
+            pinned_height: 0.0,
+            selection,
+            selectable_rows: Vec::new(),
+            clicked_row: None,
         };
         add_body_content(&mut table_body);
         let Body {
             mut table_layout,
             cursor,
+            pinned_height,
+            mut selection,
+            selectable_rows,
+            clicked_row,
             ..
         } = table_body;
 
+        // A click is applied once all rows are known so that a shift click can select
+        // rows that are added after the clicked row.
+        if let Some((id, modifiers)) = clicked_row {
+            selection.click(id, modifiers, &selectable_rows);
+            ui.ctx().request_repaint();
+        }
+        selection.store(ui, selection_id);
+
+        if pinned_height != table_state.pinned_height {
+            // The space for the pinned rows was wrong, show it again with the correct height.
+            ui.ctx().request_repaint();
+        }
+
         // Allocate space for the table.
         table_layout.rect.set_bottom(cursor.y);
         ui.allocate_rect(table_layout.rect, Sense::hover());
@@ -400,11 +727,20 @@ impl Table {
         table_layout.free_viewport =
             constrain_top_bottom(table_layout.free_viewport, table_layout.rect);
 
+        if self.scroll_shadows {
+            paint_scroll_shadows(ui, &table_layout);
+        }
+
         // The rectangle of the table that is visible.
         self.resize_columns(ui, &mut table_layout);
+        if self.reorder_columns {
+            // The resize handles are allocated first so that they take the drag
+            // where they overlap with the header.
+            drag_columns(ui, table_id.with("_column_drag"), &mut table_layout);
+        }
 
         // Save the column state
-        self.save_column_widths(ui, state_id, &table_layout.columns);
+        self.save_column_widths(ui, state_id, &table_layout.columns, pinned_height);
     }
 
     fn resize_columns(&mut self, ui: &mut Ui, table_layout: &mut TableLayout) {
@@ -453,9 +789,10 @@ impl Table {
             )
         };
 
-        let mut fixed_columns_first: Vec<_> = table_layout.columns.iter_mut().collect();
-        fixed_columns_first.sort_by(|c1, c2| c2.definition.fixed.cmp(&c1.definition.fixed));
-        for column in fixed_columns_first.iter_mut() {
+        let mut fixed_columns_first: Vec<_> = table_layout.columns.iter_mut().enumerate().collect();
+        fixed_columns_first
+            .sort_by(|(_, c1), (_, c2)| c2.definition.fixed.cmp(&c1.definition.fixed));
+        for (column_index, column) in fixed_columns_first.iter_mut() {
             // position of the resize bar and the direction of the drag.
             let (pos, dir, is_fixed) = match column.fixed {
                 ColumnFixed::None => (column.x_pos + column.width, 1.0, false),
@@ -497,7 +834,17 @@ impl Table {
 
             let sense = ui
                 .allocate_rect(sense_rect, Sense::click_and_drag())
-                .on_hover_cursor(egui::CursorIcon::ResizeColumn);
+                .on_hover_cursor(egui::CursorIcon::ResizeColumn)
+                .context_menu(|ui| {
+                    if ui.button("Reset column width").clicked() {
+                        column.reset = true;
+                        ui.close_menu();
+                    }
+                    if ui.button("Reset all columns").clicked() {
+                        self.reset_layout = true;
+                        ui.close_menu();
+                    }
+                });
 
             // highlight the column that is going to be resized if dragged.
             if sense.hovered() || sense.dragged() {
@@ -536,12 +883,62 @@ impl Table {
                     Stroke::new(3.0, ui.visuals().widgets.active.bg_stroke.color),
                 );
             }
+            if sense.drag_released() {
+                if let Some(on_resize) = &mut self.on_resize {
+                    let width = column
+                        .width
+                        .at_least(column.definition.min_width)
+                        .at_most(column.definition.max_width);
+                    on_resize(*column_index, width);
+                }
+            }
         }
     }
 
-    fn save_column_widths(&mut self, ui: &Ui, state_id: Id, column_layout: &Vec<ColumnLayout>) {
-        let mut new_table_state = TableState::default();
+    fn save_column_widths(
+        &mut self,
+        ui: &Ui,
+        state_id: Id,
+        column_layout: &Vec<ColumnLayout>,
+        pinned_height: f32,
+    ) {
+        let mut new_table_state = TableState {
+            pinned_height,
+            ..Default::default()
+        };
+        if self.reset_layout {
+            // Without a stored state every column is layed out for the first time again.
+            TableState::store(new_table_state, ui, state_id);
+            return;
+        }
+
+        // Fill columns store their width in pixels which is used as their fill share.
+        // A reset fill column must get a width in pixels that matches its fill share.
+        let (fill_width, fill_share) = column_layout
+            .iter()
+            .filter_map(|col| col.definition.fill_share.map(|share| (col.width, share)))
+            .fold((0.0, 0.0), |(width, share), (w, s)| (width + w, share + s));
+
         for (i, column) in column_layout.iter().enumerate() {
+            if column.reset {
+                let width = match (
+                    column.definition.fill_share,
+                    column.definition.initial_width,
+                ) {
+                    (Some(share), _) => share * fill_width / fill_share,
+                    (None, Some(initial_width)) => initial_width,
+                    (None, None) => column.definition.min_width,
+                };
+                new_table_state.columns.push(ColumnState {
+                    width: width
+                        .at_least(column.definition.min_width)
+                        .at_most(column.definition.max_width),
+                    pos: column.pos_index,
+                    content_version: column.definition.content_version,
+                    measure: column.definition.is_auto_sized(),
+                });
+                continue;
+            }
             let width = if column.first_time && column.definition.is_auto_sized() {
                 println!(
                     "Save column {} with content width: {}",
@@ -556,6 +953,8 @@ impl Table {
                     .at_least(column.definition.min_width)
                     .at_most(column.definition.max_width),
                 pos: column.pos_index,
+                content_version: column.definition.content_version,
+                measure: false,
             });
         }
         TableState::store(new_table_state, ui, state_id);
@@ -573,7 +972,11 @@ impl Table {
             let (pos_index, width, first_time) = table_state
                 .columns
                 .get(i)
-                .map(|state| (state.pos, state.width, false))
+                .map(|state| {
+                    // A changed content version requires the column to be measured again.
+                    let remeasure = state.measure || state.content_version != col.content_version;
+                    (state.pos, state.width, remeasure)
+                })
                 .unwrap_or((
                     i as i32,
                     col.fill_share.or(col.initial_width).unwrap_or(0.0),
@@ -589,6 +992,21 @@ impl Table {
         }
 
         layout = self.calculate_column_widths(available_width, layout);
+        if let Some(fill_index) = self.fill_remaining {
+            let other_width: f32 = layout
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != fill_index)
+                .map(|(_, col)| col.width)
+                .sum();
+            if let Some(column) = layout.get_mut(fill_index) {
+                column.width = (available_width - other_width)
+                    .at_least(column.definition.min_width)
+                    .at_most(column.definition.max_width);
+                // The width is known, the column does not need to be sized to its content.
+                column.first_time = false;
+            }
+        }
 
         let width: f32 = layout.iter().map(|col| col.width).sum();
         let table_rect = Rect::from_min_size(table_origin, vec2(width, 0.0));
@@ -655,6 +1073,12 @@ impl Table {
         // fixed matters. So we explore every variation until we find one that creates a valid layout.
         // Doing a breadth first search guarantees that we find a layout with the smallest amount of changes
         // necessary to create a valid layout.
+        // If the limits cannot be satisfied within the available width, for example because the minimum
+        // widths add up to more than the available width, every fill column ends up at its limit and
+        // the table is wider than the available width.
+        //
+        // While a run is explored, the width of its active columns is their fill share. Fixed
+        // columns hold their final width.
         #[derive(Default, Clone, Debug)]
         struct LayoutRun {
             layout: Vec<ColumnLayout>,
@@ -685,12 +1109,13 @@ impl Table {
         let mut openlist = VecDeque::new();
         openlist.push_back(first_run);
 
-        while let Some(mut run) = openlist.pop_front().take() {
+        while let Some(run) = openlist.pop_front() {
             // Calculate the widths for all open columns.
+            let mut layout = run.layout.clone();
             let mut all_columns_fit = true;
             for (i, column_index) in run.active_columns.iter().copied().enumerate() {
-                let column = &mut run.layout[column_index];
-                let fill_share = column.width;
+                let column = &mut layout[column_index];
+                let fill_share = run.layout[column_index].width;
                 column.width = run.dynamic_width * fill_share / run.total_fill;
 
                 if column.width < column.definition.min_width
@@ -703,6 +1128,7 @@ impl Table {
                     column.width = width;
 
                     let mut new_run = run.clone();
+                    new_run.layout[column_index].width = width;
                     new_run.dynamic_width -= width;
                     new_run.total_fill -= fill_share;
                     new_run.active_columns.remove(i);
@@ -713,7 +1139,7 @@ impl Table {
 
             // Move widths to the columns and return.
             if all_columns_fit {
-                return run.layout.clone();
+                return layout;
             }
         }
         initial_layout
@@ -726,35 +1152,136 @@ pub struct Body<'a> {
     cursor: Pos2,
     row_count: i32,
     striped: bool,
+    group_boundaries: &'a [usize],
+    /// The index of the first row in the current group.
+    group_start: i32,
+    /// The area reserved for the pinned rows.
+    pinned_block: Option<Rect>,
+    /// The height reserved for the pinned rows.
+    pinned_reserved: f32,
+    /// The combined height of the pinned rows that were added.
+    pinned_height: f32,
+    /// Receives the text of the visible cells.
+    visible_text: Option<&'a mut Vec<Vec<String>>>,
+    /// The selected rows.
+    selection: Selection,
+    /// The ids of the selectable rows in the order they were added.
+    selectable_rows: Vec<Id>,
+    /// The selectable row that was clicked in this frame.
+    clicked_row: Option<(Id, Modifiers)>,
 }
 
 impl<'a> Body<'a> {
+    /// The selected rows of the table.
+    ///
+    /// Changes to the selection are shown from the next row that is added.
+    /// Clicks on selectable rows are applied after all rows have been added.
+    pub fn selection(&mut self) -> &mut Selection {
+        &mut self.selection
+    }
+
+    /// Add a row to the table.
+    ///
+    /// The response covers the visible part of the row and senses with [`Row::sense`].
+    /// It is allocated before the cells of the row. A click anywhere in the row is reported
+    /// by the row, also on top of a cell, so the response can be used with
+    /// [`Response::context_menu`]. A cell that senses clicks itself does not get the clicks
+    /// of a row that senses clicks.
     pub fn row(&mut self, row: Row, add_row_content: impl FnOnce(&mut RowUi)) -> Response {
-        let row_rect = self.get_row_rect(row);
+        if self.pinned_block.is_none() && (row.pinned || !row.fixed) {
+            self.reserve_pinned_block();
+        }
+
+        let (row_rect, cell_viewport) = match self.pinned_block {
+            Some(block) if row.pinned => {
+                let row_rect = Rect::from_min_size(
+                    pos2(self.cursor.x, block.top() + self.pinned_height),
+                    vec2(f32::INFINITY, row.height),
+                );
+                let block_viewport = Rect::from_x_y_ranges(
+                    self.table_layout.free_viewport.x_range(),
+                    block.y_range(),
+                );
+                (row_rect, block_viewport)
+            }
+            _ => (self.get_row_rect(row), self.table_layout.free_viewport),
+        };
+
+        let is_group_start = self.group_boundaries.contains(&(self.row_count as usize));
+        if is_group_start {
+            self.group_start = self.row_count;
+        }
 
         let mut row_viewport = constrain_to(row_rect, self.table_layout.clip);
-        if !row.fixed {
+        if row.pinned {
+            row_viewport = constrain_top_bottom(row_viewport, cell_viewport);
+        } else if !row.fixed {
             row_viewport = constrain_top_bottom(row_viewport, self.table_layout.free_viewport);
         };
 
-        let response = self.ui.allocate_rect(row_viewport, row.sense);
+        let selectable_id = row.id.filter(|_| row.selectable);
+        let sense = if selectable_id.is_some() {
+            row.sense.union(Sense::click())
+        } else {
+            row.sense
+        };
+        let response = self.ui.allocate_rect(row_viewport, sense);
+
+        let mut selected = false;
+        if let Some(id) = selectable_id {
+            self.selectable_rows.push(id);
+            selected = self.selection.is_selected(id);
+            if response.clicked() {
+                let modifiers = self.ui.input(|i| i.modifiers);
+                self.clicked_row = Some((id, modifiers));
+            }
+        }
 
         let mut row_ui = RowUi {
             body: self,
             config: row,
+            selected,
             cell_count: 0,
             rect: row_rect,
+            viewport: cell_viewport,
             cell_was_hovered: false,
+            cell_texts: Vec::new(),
         };
         add_row_content(&mut row_ui);
         let RowUi {
-            cell_was_hovered, ..
+            cell_was_hovered,
+            cell_texts,
+            ..
         } = row_ui;
 
-        if row.fixed {
-            self.adjust_viewport(row.height);
+        if let Some(visible_text) = &mut self.visible_text {
+            if row_viewport.is_positive() && cell_texts.iter().any(Option::is_some) {
+                visible_text.push(
+                    cell_texts
+                        .into_iter()
+                        .map(Option::unwrap_or_default)
+                        .collect(),
+                );
+            }
+        }
+
+        // Draw group seperator
+        if is_group_start && self.row_count > 0 && row_viewport.height() > 0.0 {
+            self.ui.painter().hline(
+                row_viewport.x_range(),
+                row_viewport.top(),
+                Stroke::new(2.0, self.ui.visuals().noninteractive().bg_stroke.color),
+            );
+        }
+
+        if row.pinned {
+            self.pinned_height += row.height;
+        } else {
+            if row.fixed {
+                self.adjust_viewport(row.height);
+            }
+            self.cursor.y += row.height;
         }
-        self.cursor.y += row.height;
         self.row_count += 1;
 
         // Draw highlight
@@ -798,6 +1325,16 @@ impl<'a> Body<'a> {
         row_viewport
     }
 
+    /// Reserve the space for the pinned rows at the current position.
+    /// The block of pinned rows behaves like a single fixed row.
+    fn reserve_pinned_block(&mut self) {
+        let height = self.pinned_reserved;
+        let block = self.get_row_rect(Row::new().height(height).fixed(true));
+        self.adjust_viewport(height);
+        self.cursor.y += height;
+        self.pinned_block = Some(block);
+    }
+
     fn adjust_viewport(&mut self, height: f32) {
         if self.cursor.y <= self.table_layout.free_viewport.top() {
             *self.table_layout.free_viewport.top_mut() += height;
@@ -811,9 +1348,15 @@ impl<'a> Body<'a> {
 pub struct RowUi<'a, 'b> {
     body: &'a mut Body<'b>,
     config: Row,
+    /// If the row is selected.
+    selected: bool,
     cell_count: usize,
     rect: Rect,
+    /// The area the cells of this row are visible in.
+    viewport: Rect,
     cell_was_hovered: bool,
+    /// The text of every visible cell of this row.
+    cell_texts: Vec<Option<String>>,
 }
 
 impl<'a, 'b> RowUi<'a, 'b> {
@@ -822,11 +1365,32 @@ impl<'a, 'b> RowUi<'a, 'b> {
         self.cell_sense(Sense::hover(), add_content)
     }
 
+    /// Add the next cell in this row with a text.
+    ///
+    /// The text is not drawn by the cell, `add_content` has to show it. The text is collected
+    /// for [`Table::visible_cells_text`] if the cell is visible.
+    pub fn cell_text<R>(
+        &mut self,
+        text: &str,
+        add_content: impl FnOnce(&mut Ui) -> R,
+    ) -> Option<Response> {
+        self.add_cell(Sense::hover(), Some(text), add_content)
+    }
+
     /// Add the next cell to this row with sense.
     pub fn cell_sense<R>(
         &mut self,
         sense: Sense,
         add_content: impl FnOnce(&mut Ui) -> R,
+    ) -> Option<Response> {
+        self.add_cell(sense, None, add_content)
+    }
+
+    fn add_cell<R>(
+        &mut self,
+        sense: Sense,
+        text: Option<&str>,
+        add_content: impl FnOnce(&mut Ui) -> R,
     ) -> Option<Response> {
         if self.cell_count >= self.body.table_layout.columns.len() {
             return None;
@@ -837,10 +1401,13 @@ impl<'a, 'b> RowUi<'a, 'b> {
 
         let cell_rect = self.get_cell_rect();
         let clip_rect = if fixed {
-            constrain_top_bottom(cell_rect, self.body.table_layout.free_viewport)
+            constrain_top_bottom(cell_rect, self.viewport)
         } else {
-            constrain_to(cell_rect, self.body.table_layout.free_viewport)
+            constrain_to(cell_rect, self.viewport)
         };
+        if clip_rect.is_positive() {
+            self.cell_texts.push(text.map(str::to_owned));
+        }
 
         // Draw cell background.
         self.body.ui.painter().rect_filled(
@@ -848,13 +1415,20 @@ impl<'a, 'b> RowUi<'a, 'b> {
             0.0,
             self.body.ui.style().visuals.window_fill,
         );
-        if self.body.striped && self.body.row_count % 2 == 1 {
+        if self.body.striped && (self.body.row_count - self.body.group_start) % 2 == 1 {
             self.body.ui.painter().rect_filled(
                 align_to_pixel(clip_rect, self.body.ui.painter()),
                 0.0,
                 self.body.ui.style().visuals.faint_bg_color,
             );
         }
+        if self.selected {
+            self.body.ui.painter().rect_filled(
+                align_to_pixel(clip_rect, self.body.ui.painter()),
+                0.0,
+                self.body.ui.visuals().selection.bg_fill,
+            );
+        }
 
         // Show the cell.
         let ui_rect = cell_rect.expand2(-self.body.ui.spacing().item_spacing);
@@ -903,6 +1477,167 @@ impl<'a, 'b> RowUi<'a, 'b> {
     }
 }
 
+/// Let the user move the columns by dragging their header.
+fn drag_columns(ui: &Ui, drag_id: Id, table_layout: &mut TableLayout) {
+    let table_visible_area = constrain_to(table_layout.rect, table_layout.clip);
+    let mut header_rect = table_visible_area;
+    header_rect.set_bottom(table_layout.free_viewport.top());
+    if !header_rect.is_positive() {
+        return;
+    }
+
+    // The columns in the order they are shown.
+    let mut order: Vec<usize> = (0..table_layout.columns.len()).collect();
+    order.sort_by_key(|i| table_layout.columns[*i].pos_index);
+
+    let mut moved = None;
+    for (column_index, column) in table_layout.columns.iter().enumerate() {
+        let mut column_rect = Rect::from_x_y_ranges(
+            column.x_pos..=column.x_pos + column.width,
+            header_rect.y_range(),
+        );
+        if column.fixed == ColumnFixed::None {
+            column_rect = constrain_left_right(column_rect, table_layout.free_viewport);
+        }
+        // Always interact with the same id, even if the column is not visible.
+        let response = ui.interact(
+            column_rect.intersect(header_rect),
+            drag_id.with(column_index),
+            Sense::drag(),
+        );
+        if !response.dragged() && !response.drag_released() {
+            continue;
+        }
+        let (Some(origin), Some(pointer)) = (
+            ui.input(|i| i.pointer.press_origin()),
+            response
+                .interact_pointer_pos()
+                .or(ui.ctx().pointer_latest_pos()),
+        ) else {
+            continue;
+        };
+
+        // The slot the column is inserted at, between the other columns.
+        let others: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|i| *i != column_index)
+            .collect();
+        let slot = others
+            .iter()
+            .take_while(|i| {
+                let other = &table_layout.columns[**i];
+                other.x_pos + other.width / 2.0 < pointer.x
+            })
+            .count();
+
+        if response.drag_released() {
+            moved = Some((column_index, others, slot));
+            continue;
+        }
+
+        // Draw a ghost of the column that follows the pointer.
+        let ghost = Rect::from_x_y_ranges(
+            column.x_pos + pointer.x - origin.x
+                ..=column.x_pos + column.width + pointer.x - origin.x,
+            table_visible_area.y_range(),
+        );
+        ui.painter().rect(
+            ghost,
+            0.0,
+            ui.visuals().selection.bg_fill.linear_multiply(0.3),
+            ui.visuals().selection.stroke,
+        );
+
+        // Draw the line where the column is inserted.
+        let insert_x = match others.get(slot) {
+            Some(i) => table_layout.columns[*i].x_pos,
+            None => others
+                .last()
+                .map(|i| table_layout.columns[*i].x_pos + table_layout.columns[*i].width)
+                .unwrap_or(column.x_pos),
+        };
+        ui.painter().vline(
+            insert_x,
+            table_visible_area.y_range(),
+            Stroke::new(3.0, ui.visuals().selection.stroke.color),
+        );
+        response.on_hover_cursor(egui::CursorIcon::Grabbing);
+        ui.ctx().request_repaint();
+    }
+
+    if let Some((column_index, mut others, slot)) = moved {
+        others.insert(slot, column_index);
+        for (pos, i) in others.into_iter().enumerate() {
+            table_layout.columns[i].pos_index = pos as i32;
+        }
+        ui.ctx().request_repaint();
+    }
+}
+
+/// The width of the shadow along fixed rows and columns.
+const SCROLL_SHADOW_WIDTH: f32 = 8.0;
+
+/// Paint shadows along the inner edges of the fixed rows and columns where content
+/// is scrolled underneath them.
+fn paint_scroll_shadows(ui: &Ui, table_layout: &TableLayout) {
+    let TableLayout {
+        rect,
+        clip,
+        free_viewport,
+        ..
+    } = *table_layout;
+    let visible = constrain_to(rect, clip);
+    let color = ui.visuals().window_shadow.color;
+
+    // Content scrolled under fixed rows at the top.
+    if clip.top() > rect.top() && free_viewport.top() > clip.top() {
+        let shadow = Rect::from_x_y_ranges(
+            visible.x_range(),
+            free_viewport.top()..=free_viewport.top() + SCROLL_SHADOW_WIDTH,
+        );
+        paint_shadow(ui.painter(), shadow, color, Color32::TRANSPARENT, true);
+    }
+    // Content hidden behind fixed rows at the bottom.
+    if rect.bottom() > clip.bottom() && free_viewport.bottom() < clip.bottom() {
+        let shadow = Rect::from_x_y_ranges(
+            visible.x_range(),
+            free_viewport.bottom() - SCROLL_SHADOW_WIDTH..=free_viewport.bottom(),
+        );
+        paint_shadow(ui.painter(), shadow, Color32::TRANSPARENT, color, true);
+    }
+    // Content scrolled under fixed columns on the left.
+    if clip.left() > rect.left() && free_viewport.left() > clip.left() {
+        let shadow = Rect::from_x_y_ranges(
+            free_viewport.left()..=free_viewport.left() + SCROLL_SHADOW_WIDTH,
+            visible.y_range(),
+        );
+        paint_shadow(ui.painter(), shadow, color, Color32::TRANSPARENT, false);
+    }
+    // Content hidden behind fixed columns on the right.
+    if rect.right() > clip.right() && free_viewport.right() < clip.right() {
+        let shadow = Rect::from_x_y_ranges(
+            free_viewport.right() - SCROLL_SHADOW_WIDTH..=free_viewport.right(),
+            visible.y_range(),
+        );
+        paint_shadow(ui.painter(), shadow, Color32::TRANSPARENT, color, false);
+    }
+}
+
+/// Paint a rectangle with a linear gradient from the `start` color to the `end` color.
+/// The gradient goes from top to bottom if `vertical` is true, otherwise from left to right.
+fn paint_shadow(painter: &Painter, rect: Rect, start: Color32, end: Color32, vertical: bool) {
+    let (top_right, bottom_left) = if vertical { (start, end) } else { (end, start) };
+    let mut mesh = Mesh::default();
+    mesh.colored_vertex(rect.left_top(), start);
+    mesh.colored_vertex(rect.right_top(), top_right);
+    mesh.colored_vertex(rect.right_bottom(), end);
+    mesh.colored_vertex(rect.left_bottom(), bottom_left);
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    painter.add(Shape::mesh(mesh));
+}
+
 fn constrain_to(rect: Rect, constraint: Rect) -> Rect {
     Rect::from_min_max(
         rect.min.clamp(constraint.min, constraint.max),
@@ -956,3 +1691,168 @@ fn was_hoverd_strict(response: &Response) -> bool {
             && pos.y < response.rect.bottom()
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use egui::{pos2, Context, Event, Id, Modifiers, PointerButton, Pos2, RawInput, Rect, Sense};
+
+    use super::{Column, ColumnLayout, Row, Selection, Table};
+
+    /// A column layout as it is layed out the first time, with the fill share as its width.
+    fn fill(share: f32, min_width: f32, max_width: f32) -> ColumnLayout {
+        ColumnLayout {
+            definition: Column::fill(share)
+                .min_width(min_width)
+                .max_width(max_width),
+            width: share,
+            ..Default::default()
+        }
+    }
+
+    fn fixed(width: f32) -> ColumnLayout {
+        ColumnLayout {
+            definition: Column::exact(width),
+            width,
+            ..Default::default()
+        }
+    }
+
+    fn widths(available_width: f32, layout: Vec<ColumnLayout>) -> Vec<f32> {
+        Table::new()
+            .calculate_column_widths(available_width, layout)
+            .iter()
+            .map(|col| col.width)
+            .collect()
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    #[test]
+    fn fill_shares_divide_remaining_width() {
+        let widths = widths(
+            400.0,
+            vec![
+                fixed(100.0),
+                fill(1.0, 0.0, f32::INFINITY),
+                fill(2.0, 0.0, f32::INFINITY),
+            ],
+        );
+        assert_close(widths[0], 100.0);
+        assert_close(widths[1], 100.0);
+        assert_close(widths[2], 200.0);
+    }
+
+    #[test]
+    fn constrained_columns_respect_min_and_max() {
+        let layout = vec![
+            fill(1.0, 150.0, f32::INFINITY),
+            fill(1.0, 0.0, 50.0),
+            fill(1.0, 0.0, f32::INFINITY),
+            fill(1.0, 0.0, f32::INFINITY),
+        ];
+        let definitions: Vec<_> = layout.iter().map(|col| col.definition.clone()).collect();
+        let widths = widths(400.0, layout);
+        for (width, definition) in widths.iter().zip(definitions) {
+            assert!(*width >= definition.min_width - 1e-3);
+            assert!(*width <= definition.max_width + 1e-3);
+        }
+        assert_close(widths[0], 150.0);
+        assert_close(widths[1], 50.0);
+        // Unconstrained columns with the same share are the same width.
+        assert_close(widths[2], widths[3]);
+        assert_close(widths.iter().sum(), 400.0);
+    }
+
+    #[test]
+    fn no_valid_layout_uses_min_widths() {
+        // The minimum widths need more space than is available.
+        let widths = widths(
+            200.0,
+            vec![
+                fill(1.0, 150.0, f32::INFINITY),
+                fill(3.0, 100.0, f32::INFINITY),
+            ],
+        );
+        assert_close(widths[0], 150.0);
+        assert_close(widths[1], 100.0);
+    }
+
+    #[test]
+    fn selection_clicks() {
+        let rows: Vec<Id> = (0..5).map(Id::new).collect();
+        let shift = Modifiers {
+            shift: true,
+            ..Default::default()
+        };
+        let mut selection = Selection::default();
+
+        selection.click(rows[1], Modifiers::NONE, &rows);
+        selection.click(rows[3], Modifiers::COMMAND, &rows);
+        assert!(selection.is_selected(rows[1]) && selection.is_selected(rows[3]));
+
+        // A shift click selects the range from the last clicked row.
+        selection.click(rows[0], shift, &rows);
+        let selected: Vec<bool> = rows.iter().map(|id| selection.is_selected(*id)).collect();
+        assert_eq!(selected, vec![true, true, true, true, false]);
+
+        selection.click(rows[2], Modifiers::COMMAND, &rows);
+        assert!(!selection.is_selected(rows[2]));
+
+        selection.click(rows[4], Modifiers::NONE, &rows);
+        assert_eq!(selection.selected().collect::<Vec<_>>(), vec![rows[4]]);
+    }
+
+    /// Show a table with a fixed header and five rows and return if the rows were
+    /// secondary clicked in the last frame.
+    fn secondary_clicks(ctx: &Context, events: Vec<Event>) -> Vec<bool> {
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(Pos2::ZERO, egui::vec2(400.0, 400.0))),
+            events,
+            ..Default::default()
+        };
+        let mut clicks = Vec::new();
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                Table::new()
+                    .column(Column::exact(100.0))
+                    .column(Column::exact(100.0))
+                    .scroll(true, true)
+                    .show(ui, |table| {
+                        table.row(Row::new().height(20.0).fixed(true), |row| {
+                            row.cell(|ui| ui.label("header"));
+                            row.cell(|ui| ui.label("header"));
+                        });
+                        for _ in 0..5 {
+                            let response =
+                                table.row(Row::new().height(20.0).sense(Sense::click()), |row| {
+                                    row.cell(|ui| ui.label("cell"));
+                                    row.cell_sense(Sense::click(), |ui| ui.label("cell"));
+                                });
+                            clicks.push(response.secondary_clicked());
+                        }
+                    });
+            });
+        });
+        clicks
+    }
+
+    #[test]
+    fn row_reports_secondary_click() {
+        let ctx = Context::default();
+        secondary_clicks(&ctx, Vec::new());
+
+        // Click the third row, inside of the first cell.
+        let pos = pos2(50.0, 75.0);
+        let button = |pressed| Event::PointerButton {
+            pos,
+            button: PointerButton::Secondary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        };
+        secondary_clicks(&ctx, vec![Event::PointerMoved(pos), button(true)]);
+        let clicks = secondary_clicks(&ctx, vec![button(false)]);
+        assert_eq!(clicks, vec![false, false, true, false, false]);
+    }
+}
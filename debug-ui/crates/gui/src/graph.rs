@@ -7,71 +7,210 @@ use std::{
     time::{Duration, Instant},
 };
 
-use egui::plot::{Line, PlotPoints};
+use egui::{
+    plot::{Legend, Line, PlotPoints},
+    Color32,
+};
 use egui_custom::dialog::{Dialog, DialogHandle, Size};
-use unified_sim_model::{model::EntryId, Adapter};
+use unified_sim_model::{
+    model::{Entry, EntryId},
+    Adapter,
+};
+
+/// A channel of data that can be shown in the graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Channel {
+    DistanceDriven,
+    Speed,
+    Throttle,
+}
+
+impl Channel {
+    const ALL: [Channel; 3] = [Channel::DistanceDriven, Channel::Speed, Channel::Throttle];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Channel::DistanceDriven => "Distance driven",
+            Channel::Speed => "Speed",
+            Channel::Throttle => "Throttle",
+        }
+    }
+}
+
+/// A single sample of all channels of an entry.
+struct Sample {
+    time: Duration,
+    distance_driven: f32,
+    speed: f32,
+    /// The inputs are only available for the player car.
+    throttle: Option<f32>,
+}
+
+impl Sample {
+    fn value(&self, channel: Channel) -> Option<f32> {
+        match channel {
+            Channel::DistanceDriven => Some(self.distance_driven),
+            Channel::Speed => Some(self.speed),
+            Channel::Throttle => self.throttle,
+        }
+    }
+}
+
+/// The samples of a single entry.
+struct Series {
+    entry_id: EntryId,
+    name: String,
+    color: Color32,
+    data: Vec<Sample>,
+}
+
+impl Series {
+    fn new(entry: &Entry) -> Self {
+        let name = match entry.drivers.get(&entry.current_driver) {
+            Some(driver) => format!(
+                "{} {} #{}",
+                driver.first_name, driver.last_name, entry.car_number
+            ),
+            None => format!("#{}", entry.car_number),
+        };
+        let color = entry.display_color();
+        Self {
+            entry_id: entry.id,
+            name,
+            color: Color32::from_rgb(color.r, color.g, color.b),
+            data: Vec::new(),
+        }
+    }
+}
 
 struct GraphModel {
-    data: Vec<(Duration, f32)>,
+    series: Vec<Series>,
 }
 
 pub struct Graph {
     pub handle: Option<DialogHandle<Graph>>,
-    driver_name: String,
+    adapter: Adapter,
+    channel: Channel,
     model: Arc<RwLock<GraphModel>>,
     close_channel: Sender<()>,
 }
 
 impl Graph {
     pub fn new(adapter: Adapter, entry_id: EntryId) -> Self {
-        let graph_model = Arc::new(RwLock::new(GraphModel { data: Vec::new() }));
+        let graph_model = Arc::new(RwLock::new(GraphModel { series: Vec::new() }));
         let (tx, rx) = mpsc::channel();
         let thread_model = graph_model.clone();
         let thread_adapter = adapter.clone();
-        thread::spawn(move || graph_thread(thread_adapter, thread_model, rx, entry_id));
+        thread::spawn(move || graph_thread(thread_adapter, thread_model, rx));
 
-        let model = adapter
+        let mut graph = Self {
+            handle: None,
+            adapter,
+            channel: Channel::DistanceDriven,
+            model: graph_model,
+            close_channel: tx,
+        };
+        graph.add_entry(entry_id);
+        graph
+    }
+
+    /// Add an entry to the graph. Does nothing if the entry is already shown.
+    fn add_entry(&mut self, entry_id: EntryId) {
+        let model = self
+            .adapter
             .model
             .read()
             .expect("Model shouldnt become poisoned");
-        let driver_name = model
+        let Some(entry) = model
             .current_session()
             .and_then(|session| session.entries.get(&entry_id))
-            .map_or("N/a".to_owned(), |entry| {
-                let driver = entry.drivers.get(&entry.current_driver);
-                match driver {
-                    Some(driver) => format!(
-                        "{} {} #{}",
-                        driver.first_name, driver.last_name, entry.car_number
-                    ),
-                    None => "N/a".to_owned(),
-                }
-            });
-        Self {
-            handle: None,
-            driver_name,
-            model: graph_model,
-            close_channel: tx,
+        else {
+            return;
+        };
+        let mut graph_model = self.model.write().expect("Model shouldnt become poisoned");
+        if graph_model
+            .series
+            .iter()
+            .all(|series| series.entry_id != entry_id)
+        {
+            graph_model.series.push(Series::new(entry));
         }
     }
+
+    fn show_controls(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_label("Channel")
+                .selected_text(self.channel.name())
+                .show_ui(ui, |ui| {
+                    for channel in Channel::ALL {
+                        ui.selectable_value(&mut self.channel, channel, channel.name());
+                    }
+                });
+
+            let mut compare_with = None;
+            egui::ComboBox::from_label("Compare with")
+                .selected_text("Add entry")
+                .show_ui(ui, |ui| {
+                    let model = self
+                        .adapter
+                        .model
+                        .read()
+                        .expect("Model shouldnt become poisoned");
+                    let Some(session) = model.current_session() else {
+                        return;
+                    };
+                    let entries = session.entries_by_position();
+                    for entry in entries {
+                        if ui
+                            .selectable_label(false, Series::new(entry).name)
+                            .clicked()
+                        {
+                            compare_with = Some(entry.id);
+                        }
+                    }
+                });
+            if let Some(entry_id) = compare_with {
+                self.add_entry(entry_id);
+            }
+        });
+    }
 }
 
 impl Dialog for Graph {
     fn show(&mut self, ctx: &egui::Context, _windower: &mut egui_custom::dialog::Windower) {
         dear_egui::set_theme(ctx, dear_egui::SKY);
         egui::CentralPanel::default().show(ctx, |ui| {
-            ui.horizontal(|ui| {
-                ui.label("Distance driven graph for: ");
-                ui.label(&self.driver_name);
-            });
+            self.show_controls(ui);
             let graph_model = self.model.read().expect("Model shouldnt become poisoned");
-            let data: PlotPoints = graph_model
-                .data
+            // Channels that are only available for the player car produce no data for
+            // other entries. Those entries are left out instead of showing an empty trace.
+            let lines: Vec<Line> = graph_model
+                .series
                 .iter()
-                .map(|(time, value)| [time.as_secs_f64(), *value as f64])
+                .filter_map(|series| {
+                    let data: Vec<[f64; 2]> = series
+                        .data
+                        .iter()
+                        .filter_map(|sample| {
+                            sample
+                                .value(self.channel)
+                                .map(|value| [sample.time.as_secs_f64(), value as f64])
+                        })
+                        .collect();
+                    (!data.is_empty()).then(|| {
+                        Line::new(PlotPoints::from(data))
+                            .name(&series.name)
+                            .color(series.color)
+                    })
+                })
                 .collect();
-            let line = Line::new(data);
-            egui::plot::Plot::new("my_plot").show(ui, |plot_ui| plot_ui.line(line));
+            egui::plot::Plot::new("my_plot")
+                .legend(Legend::default())
+                .show(ui, |plot_ui| {
+                    for line in lines {
+                        plot_ui.line(line);
+                    }
+                });
         });
         ctx.request_repaint();
     }
@@ -96,7 +235,6 @@ fn graph_thread(
     adapter: Adapter,
     graph_model: Arc<RwLock<GraphModel>>,
     close_request: Receiver<()>,
-    entry_id: EntryId,
 ) {
     let time_zero = Instant::now();
     while adapter.wait_for_update().is_ok() {
@@ -108,21 +246,27 @@ fn graph_thread(
             .model
             .read()
             .expect("Game model shouldnt become poisoned");
-        let data = model
-            .current_session()
-            .and_then(|session| session.entries.get(&entry_id));
-        if let Some(entry) = data {
+        let Some(session) = model.current_session() else {
+            continue;
+        };
+        let now = Instant::now();
+
+        let mut graph_model = graph_model
+            .write()
+            .expect("The model shouldnt become poisoded");
+        for series in graph_model.series.iter_mut() {
+            let Some(entry) = session.entries.get(&series.entry_id) else {
+                continue;
+            };
             if !*entry.connected {
                 continue;
             }
-            let now = Instant::now();
-
-            let mut graph_model = graph_model
-                .write()
-                .expect("The model shouldnt become poisoded");
-            graph_model
-                .data
-                .push((now - time_zero, *entry.distance_driven));
+            series.data.push(Sample {
+                time: now - time_zero,
+                distance_driven: *entry.distance_driven,
+                speed: *entry.speed,
+                throttle: entry.inputs.get_available().map(|inputs| inputs.throttle),
+            });
         }
     }
 }
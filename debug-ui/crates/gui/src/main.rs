@@ -1,11 +1,11 @@
-use std::env;
+use std::{env, time::Duration};
 
 use egui::Context;
 use egui_custom::dialog::{Dialog, Size, Windower};
 
-use tracing::{debug, error, info, warn};
+use tracing::{debug, info, warn};
 use tracing_subscriber::EnvFilter;
-use unified_sim_model::{Adapter, AdapterCommand};
+use unified_sim_model::{model::ConnectionState, Adapter, AdapterCommand};
 
 mod graph;
 mod session_table;
@@ -69,12 +69,17 @@ impl Dialog for App {
                             self.adapter = Some(Adapter::new_dummy());
                             ui.close_menu();
                         }
+                        if ui.button("Demo").clicked() {
+                            self.adapter = Some(Adapter::new_demo(Default::default()));
+                            ui.close_menu();
+                        }
                         if ui.button("ACC").clicked() {
-                            self.adapter = Some(Adapter::new_acc());
+                            self.adapter = Some(Adapter::new_acc_reconnecting(Default::default()));
                             ui.close_menu();
                         }
                         if ui.button("iRacing").clicked() {
-                            self.adapter = Some(Adapter::new_iracing());
+                            self.adapter =
+                                Some(Adapter::new_iracing_reconnecting(Default::default()));
                             ui.close_menu();
                         }
                     }
@@ -86,22 +91,38 @@ impl Dialog for App {
             let Some(adapter) = self.adapter.as_ref() else {
                 return;
             };
-            let Ok(model) = adapter.model.read() else {
-                return;
-            };
+            let model = adapter.model.read_recover();
 
+            if model.connection_state == ConnectionState::Connecting {
+                ui.label("Waiting for game...");
+            }
+            ui.label(format!("Game: {}", model.game));
             ui.label(format!("Event name: {}", model.event_name));
             ui.label(format!("Active Camera: {}", *model.active_camera));
+            match model.time_since_update() {
+                Some(age) => ui.label(format!("Data age: {:.1}s", age.as_secs_f32())),
+                None => ui.label("Data age: -"),
+            };
+            let warnings = model.validate();
+            if !warnings.is_empty() {
+                ui.collapsing(format!("Model warnings ({})", warnings.len()), |ui| {
+                    for warning in warnings {
+                        ui.label(warning.to_string());
+                    }
+                });
+            }
             //self.session_table.show(ui, &model, windower, adapter);
             session_table::show_session_tabs(ui, &model, windower, adapter);
         });
 
-        // clear adapter events at the end of the frame.
-        if let Some(ref mut adapter) = self.adapter {
-            if let Err(e) = adapter.clear_events() {
-                error!("Cannot clear events. Model is poisoned: {}", e);
+        if let Some(adapter) = self.adapter.as_ref() {
+            // Repaint right away when new data is available. Otherwise repaint slowly
+            // to keep the data age up to date.
+            if adapter.take_update() {
+                ctx.request_repaint();
+            } else {
+                ctx.request_repaint_after(Duration::from_millis(100));
             }
-            ctx.request_repaint();
         }
     }
 
@@ -127,10 +148,10 @@ impl App {
                 return;
             }
             adapter.send(AdapterCommand::Close);
-            if let Some(Err(e)) = adapter.join() {
-                warn!("Connection closed: {:?}", e);
-            } else {
-                info!("Adapter shut down correctly");
+            match adapter.join_timeout(Duration::from_secs(2)) {
+                Some(Err(e)) => warn!("Connection closed: {:?}", e),
+                Some(Ok(())) => info!("Adapter shut down correctly"),
+                None => warn!("Adapter did not shut down in time"),
             }
         }
     }
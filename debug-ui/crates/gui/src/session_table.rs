@@ -1,11 +1,9 @@
-use std::collections::HashMap;
-
 use egui::{DragValue, RichText, Sense, Ui};
 use egui_custom::dialog::Windower;
 use egui_ltable::{Column, Row, Table};
 use unified_sim_model::{
     games::dummy::DummyCommands,
-    model::{Entry, EntryId, Model, Session},
+    model::{Entry, Model, Session, SessionType},
     Adapter, AdapterCommand, GameAdapterCommand,
 };
 
@@ -13,8 +11,8 @@ use crate::{graph::Graph, tab_panel::TabPanel};
 
 pub fn show_session_tabs(ui: &mut Ui, model: &Model, windower: &mut Windower, adapter: &Adapter) {
     let mut session_tabs = TabPanel::new(ui);
-    for (session_id, session) in model.sessions.iter() {
-        session_tabs.add_tab(*session_id, format!("{:?}", *session.session_type));
+    for (session_id, session_type, _) in model.session_tabs() {
+        session_tabs.add_tab(session_id, format!("{:?}", session_type));
     }
     session_tabs.show(|session_id, ui| {
         let Some(session) = model.sessions.get(session_id) else {
@@ -34,9 +32,7 @@ pub fn show_session_tabs(ui: &mut Ui, model: &Model, windower: &mut Windower, ad
             .with_tab(SessionTabs::Camera, "Camera")
             .with_tab(SessionTabs::Actions, "Actions")
             .show(|id, ui| match id {
-                SessionTabs::Livetiming => {
-                    display_entries_table(ui, &session.entries, windower, adapter)
-                }
+                SessionTabs::Livetiming => display_entries_table(ui, session, windower, adapter),
                 SessionTabs::SessionInfo => display_session_info(ui, session),
                 SessionTabs::Camera => display_cameras(ui, model, adapter),
                 SessionTabs::Actions => display_actions(ui, model, adapter),
@@ -131,225 +127,295 @@ fn display_session_info(ui: &mut Ui, session: &Session) {
     });
 }
 
+/// A column of the entries table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnSpec {
+    Status,
+    Position,
+    CarNumber,
+    TeamName,
+    Driver,
+    Car,
+    SplinePos,
+    Laps,
+    BestLap,
+    LastLap,
+    Lap,
+    Delta,
+    ToLeader,
+    Interval,
+    PitStops,
+    Stint,
+}
+
+impl ColumnSpec {
+    /// The definition of the column in the table.
+    fn column(&self) -> Column {
+        let center = egui::Layout::centered_and_justified(egui::Direction::LeftToRight);
+        let right = egui::Layout::right_to_left(egui::Align::Min);
+        match self {
+            ColumnSpec::Status => Column::exact(25.0).layout(center),
+            ColumnSpec::Position | ColumnSpec::CarNumber => Column::exact(30.0).layout(right),
+            ColumnSpec::TeamName => Column::initial(100.0).resizeable(true).min_width(70.0),
+            ColumnSpec::Driver => Column::initial(150.0).resizeable(true).min_width(70.0),
+            ColumnSpec::Car => Column::initial(75.0).resizeable(true).min_width(50.0),
+            ColumnSpec::Laps | ColumnSpec::PitStops => Column::exact(50.0).layout(right),
+            ColumnSpec::SplinePos
+            | ColumnSpec::BestLap
+            | ColumnSpec::LastLap
+            | ColumnSpec::Lap
+            | ColumnSpec::Delta
+            | ColumnSpec::ToLeader
+            | ColumnSpec::Interval
+            | ColumnSpec::Stint => Column::exact(70.0).layout(right),
+        }
+    }
+
+    fn header(&self) -> &'static str {
+        match self {
+            ColumnSpec::Status => "",
+            ColumnSpec::Position => "Pos",
+            ColumnSpec::CarNumber => "#",
+            ColumnSpec::TeamName => "Team name",
+            ColumnSpec::Driver => "Driver",
+            ColumnSpec::Car => "Car",
+            ColumnSpec::SplinePos => "Spline pos",
+            ColumnSpec::Laps => "Laps",
+            ColumnSpec::BestLap => "Best lap",
+            ColumnSpec::LastLap => "Last lap",
+            ColumnSpec::Lap => "Lap",
+            ColumnSpec::Delta => "Delta",
+            ColumnSpec::ToLeader => "To leader",
+            ColumnSpec::Interval => "Interval",
+            ColumnSpec::PitStops => "Pits",
+            ColumnSpec::Stint => "Stint",
+        }
+    }
+}
+
+/// The default columns of the entries table for a session type.
+///
+/// Practice and qualifying are ranked by lap time and show the lap times and the delta.
+/// A race is ranked by distance and shows the gaps and the pit stops.
+fn columns_for(session_type: SessionType) -> Vec<ColumnSpec> {
+    use ColumnSpec::*;
+    match session_type {
+        SessionType::Practice | SessionType::Qualifying => vec![
+            Status, Position, CarNumber, TeamName, Driver, Car, SplinePos, Laps, BestLap, LastLap,
+            Lap, Delta, Stint,
+        ],
+        SessionType::Race => vec![
+            Status, Position, CarNumber, TeamName, Driver, Car, SplinePos, Laps, LastLap, Lap,
+            ToLeader, Interval, PitStops, Stint,
+        ],
+        SessionType::None => vec![
+            Status, Position, CarNumber, TeamName, Driver, Car, SplinePos, Laps, BestLap, LastLap,
+            Lap, Delta, ToLeader, Interval, PitStops, Stint,
+        ],
+    }
+}
+
 fn display_entries_table(
     ui: &mut Ui,
-    entries: &HashMap<EntryId, Entry>,
+    session: &Session,
     windower: &mut Windower,
     adapter: &Adapter,
 ) {
-    let mut entries: Vec<&Entry> = entries.values().collect();
-    entries.sort_by_key(|e| *e.position);
+    let entries = session.entries_by_position();
 
     let focus_on_car = |entry_id| {
         adapter.send(AdapterCommand::FocusOnCar(entry_id));
     };
 
-    let center = egui::Layout::centered_and_justified(egui::Direction::LeftToRight);
-    let right = egui::Layout::right_to_left(egui::Align::Min);
-    Table::new()
-        .striped(true)
-        .column(Column::exact(25.0).layout(center)) // pit
-        .column(Column::exact(30.0).layout(right)) // pos
-        .column(Column::exact(30.0).layout(right)) // #
-        .column(Column::initial(100.0).resizeable(true).min_width(70.0)) // team
-        .column(Column::initial(150.0).resizeable(true).min_width(70.0)) // driver
-        .column(Column::initial(75.0).resizeable(true).min_width(50.0)) // car
-        .column(Column::exact(70.0).layout(right)) // spline pos
-        .column(Column::exact(50.0).layout(right)) // laps
-        .column(Column::exact(70.0).layout(right)) // best lap
-        .column(Column::exact(70.0).layout(right)) // lap
-        .column(Column::exact(70.0).layout(right)) // delta
-        .column(Column::exact(70.0).layout(right)) // to leader
-        .column(Column::exact(70.0).layout(right)) // stint
-        .column(Column::fill(1.0).min_width(0.1))
-        .column_lines(true)
-        .resize_full_height(false)
-        .scroll(true, true)
-        .show(ui, |table| {
-            // Headers
-            table.row(Row::new().height(20.0).fixed(true), |row| {
-                row.cell(|_| {});
-                row.cell(|ui| {
-                    ui.strong("Pos");
-                });
-                row.cell(|ui| {
-                    ui.strong("#");
-                });
-                row.cell(|ui| {
-                    ui.strong("Team name");
-                });
-                row.cell(|ui| {
-                    ui.strong("Driver");
-                });
-                row.cell(|ui| {
-                    ui.strong("Car");
-                });
-                row.cell(|ui| {
-                    ui.strong("Spline pos");
-                });
-                row.cell(|ui| {
-                    ui.strong("Laps");
-                });
-                row.cell(|ui| {
-                    ui.strong("Best lap");
-                });
-                row.cell(|ui| {
-                    ui.strong("Last lap");
-                });
-                row.cell(|ui| {
-                    ui.strong("Lap");
-                });
-                row.cell(|ui| {
-                    ui.strong("Delta");
-                });
-                row.cell(|ui| {
-                    ui.strong("To leader");
-                });
-                row.cell(|ui| {
-                    ui.strong("Interval");
-                });
-                row.cell(|ui| {
-                    ui.strong("Stint");
-                });
-                row.cell(|_| {});
-            });
-
-            // Body
-            for entry in entries {
-                let response = table.row(
-                    Row::new()
-                        .height(20.0)
-                        .hover_highlight(true)
-                        .highlight(entry.focused)
-                        .sense(Sense::click()),
-                    |row| {
-                        row.cell(|ui| {
-                            let mut s = String::new();
-                            if *entry.in_pits {
-                                s.push_str("P");
-                            }
-                            if *entry.is_finished {
-                                s.push_str("F");
-                            }
-                            ui.label(s);
-                        });
-                        row.cell(|ui| {
-                            let s = if *entry.connected {
-                                format!("{}", entry.position)
-                            } else {
-                                format!("[{}]", entry.position)
-                            };
-                            ui.add(egui::Label::new(s).wrap(false));
-                        });
-                        row.cell(|ui| {
-                            ui.label(format!("{}", entry.car_number));
-                        });
+    let columns = columns_for(*session.session_type);
+    let mut table = Table::new();
+    for spec in columns.iter() {
+        table = table.column(spec.column());
+    }
+    // Each column set keeps its own layout.
+    ui.push_id(format!("{:?}", *session.session_type), |ui| {
+        table
+            .column(Column::fill(1.0).min_width(0.1))
+            .striped(true)
+            .column_lines(true)
+            .resize_full_height(false)
+            .reorder_columns(true)
+            .scroll(true, true)
+            .show(ui, |table| {
+                // Headers
+                table.row(Row::new().height(20.0).fixed(true), |row| {
+                    for spec in columns.iter() {
                         row.cell(|ui| {
-                            ui.add(egui::Label::new(entry.team_name.as_ref()).wrap(false));
+                            ui.strong(spec.header());
                         });
-                        row.cell(|ui| {
-                            let driver = entry.drivers.get(&entry.current_driver);
-                            let driver_name = match driver {
-                                Some(driver) => {
-                                    format!("{} {}", driver.first_name, driver.last_name)
+                    }
+                    row.cell(|_| {});
+                });
+
+                // Body
+                for entry in entries {
+                    let response = table.row(
+                        Row::new()
+                            .height(20.0)
+                            .hover_highlight(true)
+                            .highlight(entry.focused)
+                            .sense(Sense::click()),
+                        |row| {
+                            for spec in columns.iter() {
+                                if *spec == ColumnSpec::SplinePos {
+                                    let r = row.cell_sense(Sense::click(), |ui| {
+                                        ui.label(format!("{:.3}", entry.distance_driven));
+                                    });
+                                    if let Some(response) = r {
+                                        if response.double_clicked() {
+                                            focus_on_car(entry.id);
+                                        }
+                                        response.context_menu(|ui| {
+                                            if ui.button("Focus").clicked() {
+                                                ui.close_menu();
+                                                focus_on_car(entry.id);
+                                            }
+                                            if ui.button("Graph").clicked() {
+                                                let graph = windower.new_window(Graph::new(
+                                                    adapter.clone(),
+                                                    entry.id,
+                                                ));
+                                                graph.borrow_dialog_mut().handle =
+                                                    Some(graph.clone());
+                                                ui.close_menu();
+                                            }
+                                        });
+                                    }
+                                } else {
+                                    row.cell(|ui| display_cell(ui, *spec, entry));
                                 }
-                                None => "No driver".to_string(),
-                            };
-                            ui.label(driver_name);
-                        });
-                        row.cell(|ui| {
-                            ui.label(entry.car.name());
-                        });
-                        let r = row.cell_sense(Sense::click(), |ui| {
-                            ui.label(format!("{:.3}", entry.distance_driven));
-                        });
-                        if let Some(response) = r {
-                            if response.double_clicked() {
-                                focus_on_car(entry.id);
                             }
-                            response.context_menu(|ui| {
-                                if ui.button("Focus").clicked() {
-                                    ui.close_menu();
-                                    focus_on_car(entry.id);
-                                }
-                                if ui.button("Graph").clicked() {
-                                    let graph =
-                                        windower.new_window(Graph::new(adapter.clone(), entry.id));
-                                    graph.borrow_dialog_mut().handle = Some(graph.clone());
-                                    ui.close_menu();
-                                }
-                            });
+                            row.cell(|_| {});
+                        },
+                    );
+                    if response.double_clicked() {
+                        focus_on_car(entry.id);
+                    }
+                    response.context_menu(|ui| {
+                        if ui.button("Focus").clicked() {
+                            ui.close_menu();
+                            focus_on_car(entry.id);
                         }
-                        row.cell(|ui| {
-                            ui.label(format!("{}", entry.lap_count));
-                        });
-                        row.cell(|ui| {
-                            let best_lap = entry
-                                .best_lap
-                                .as_ref()
-                                .as_ref()
-                                .map_or("-".to_string(), |lap| lap.time.format());
-                            ui.label(best_lap);
-                        });
-                        row.cell(|ui| {
-                            let last_lap = entry
-                                .laps
-                                .last()
-                                .map_or("-".to_string(), |lap| lap.time.format());
-                            ui.label(last_lap);
-                        });
-                        row.cell(|ui| {
-                            let mut lap_time = RichText::new(entry.current_lap.time.format());
-                            if *entry.current_lap.invalid {
-                                lap_time = lap_time.color(egui::Color32::RED);
+                        if let Some(driver) = entry.drivers.get(&entry.current_driver) {
+                            if ui.button("Copy driver name").clicked() {
+                                ui.close_menu();
+                                let name = format!("{} {}", driver.first_name, driver.last_name);
+                                ui.output_mut(|o| o.copied_text = name);
                             }
+                        }
+                    });
+                }
+            });
+    });
+}
 
-                            ui.label(lap_time);
-                        });
-                        row.cell(|ui| {
-                            let mut delta = RichText::new(entry.performance_delta.format());
-                            if *entry.current_lap.invalid {
-                                delta = delta.color(egui::Color32::RED);
-                            } else if entry.performance_delta.ms < 0.0 {
-                                delta = delta.color(egui::Color32::GREEN);
-                            }
-                            ui.label(delta);
-                        });
-                        row.cell(|ui| {
-                            ui.label(
-                                entry
-                                    .time_behind_leader
-                                    .get_available()
-                                    .map(|t| t.format())
-                                    .unwrap_or(String::from("-")),
-                            );
-                        });
-                        row.cell(|ui| {
-                            ui.label(
-                                entry
-                                    .time_behind_position_ahead
-                                    .get_available()
-                                    .map(|t| t.format())
-                                    .unwrap_or(String::from("-")),
-                            );
-                        });
-                        row.cell(|ui| {
-                            ui.label(entry.stint_time.format());
-                        });
-                        row.cell(|_| {});
-                    },
-                );
-                if response.double_clicked() {
-                    focus_on_car(entry.id);
+/// Show the content of a cell of the entries table.
+fn display_cell(ui: &mut Ui, spec: ColumnSpec, entry: &Entry) {
+    match spec {
+        ColumnSpec::Status => {
+            let mut s = String::new();
+            if *entry.in_pits {
+                s.push_str("P");
+            }
+            if *entry.is_finished {
+                s.push_str("F");
+            }
+            ui.label(s);
+        }
+        ColumnSpec::Position => {
+            let s = if *entry.connected {
+                format!("{}", entry.position)
+            } else {
+                format!("[{}]", entry.position)
+            };
+            ui.add(egui::Label::new(s).wrap(false));
+        }
+        ColumnSpec::CarNumber => {
+            ui.label(format!("{}", entry.car_number));
+        }
+        ColumnSpec::TeamName => {
+            ui.add(egui::Label::new(entry.team_name.as_ref()).wrap(false));
+        }
+        ColumnSpec::Driver => {
+            let driver = entry.drivers.get(&entry.current_driver);
+            let driver_name = match driver {
+                Some(driver) => {
+                    format!("{} {}", driver.first_name, driver.last_name)
                 }
-                response.context_menu(|ui| {
-                    if ui.button("Focus").clicked() {
-                        ui.close_menu();
-                        focus_on_car(entry.id);
-                    }
-                });
+                None => "No driver".to_string(),
+            };
+            ui.label(driver_name);
+        }
+        ColumnSpec::Car => {
+            ui.label(entry.car.name());
+        }
+        ColumnSpec::SplinePos => {
+            ui.label(format!("{:.3}", entry.distance_driven));
+        }
+        ColumnSpec::Laps => {
+            ui.label(format!("{}", entry.lap_count));
+        }
+        ColumnSpec::BestLap => {
+            let best_lap = entry
+                .best_lap
+                .as_ref()
+                .as_ref()
+                .map_or("-".to_string(), |lap| lap.time.format());
+            ui.label(best_lap);
+        }
+        ColumnSpec::LastLap => {
+            let last_lap = entry
+                .laps
+                .last()
+                .map_or("-".to_string(), |lap| lap.time.format());
+            ui.label(last_lap);
+        }
+        ColumnSpec::Lap => {
+            let mut lap_time = RichText::new(entry.current_lap.time.format());
+            if *entry.current_lap.invalid {
+                lap_time = lap_time.color(egui::Color32::RED);
             }
-        });
+            ui.label(lap_time);
+        }
+        ColumnSpec::Delta => {
+            let mut delta = RichText::new(entry.performance_delta.format());
+            if *entry.current_lap.invalid {
+                delta = delta.color(egui::Color32::RED);
+            } else if entry.performance_delta.ms < 0.0 {
+                delta = delta.color(egui::Color32::GREEN);
+            }
+            ui.label(delta);
+        }
+        ColumnSpec::ToLeader => {
+            ui.label(
+                entry
+                    .time_behind_leader
+                    .get_available()
+                    .map(|t| t.format())
+                    .unwrap_or(String::from("-")),
+            );
+        }
+        ColumnSpec::Interval => {
+            ui.label(
+                entry
+                    .time_behind_position_ahead
+                    .get_available()
+                    .map(|t| t.format())
+                    .unwrap_or(String::from("-")),
+            );
+        }
+        ColumnSpec::PitStops => {
+            ui.label(format!("{}", entry.pit_stops_completed));
+        }
+        ColumnSpec::Stint => {
+            ui.label(entry.stint_time.format());
+        }
+    }
 }
 
 fn display_actions(ui: &mut Ui, _model: &Model, adapter: &Adapter) {
@@ -18,8 +18,9 @@ fn main() {
     let mut adapter = Adapter::new_iracing();
 
     // Wait for an update and loop.
+    let mut event_watermark = 0;
     while adapter.wait_for_update().is_ok() {
-        let model = adapter.model.read().unwrap();
+        let model = adapter.model.read_recover();
 
         if let Some(session) = model.current_session() {
             info!(
@@ -28,11 +29,11 @@ fn main() {
             );
         };
 
-        for event in model.events.iter() {
-            info!("Event: {:?}", event);
+        for event in model.recent_events(event_watermark) {
+            info!("Event at {}: {:?}", event.time, event.event);
         }
+        event_watermark = model.next_event_id();
         std::mem::drop(model);
-        _ = adapter.clear_events();
     }
 
     if let Some(Err(e)) = adapter.join() {
@@ -1,4 +1,9 @@
 pub mod acc;
+pub mod auto;
 pub mod common;
+pub mod demo;
 pub mod dummy;
 pub mod iracing;
+#[cfg(feature = "bincode")]
+pub mod playback;
+pub mod reconnect;
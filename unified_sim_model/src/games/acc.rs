@@ -1,8 +1,8 @@
 use thiserror::Error;
-use tracing::error;
 
 use crate::{
-    model::{Model, Value},
+    games::common::max_entries::EntryLimit,
+    model::{ConnectionState, GameKind, Model, Value},
     AdapterCommand, GameAdapter, UpdateEvent,
 };
 use std::{
@@ -11,7 +11,7 @@ use std::{
     net::UdpSocket,
     result,
     sync::{
-        mpsc::{self, Receiver, TryRecvError},
+        mpsc::{self, Receiver},
         Arc, RwLock,
     },
     time::{Duration, Instant},
@@ -20,12 +20,14 @@ use std::{
 use self::{
     data::{IncompleteTypeError, Message},
     processors::{
-        base::BaseProcessor, connection::ConnectionProcessor, gap_to_leader::GapToLeaderProcessor, lap::LapProcessor, position::PositionProcessor, session_progress::SessionProgressProcessor, AccProcessor, AccProcessorContext
+        base::BaseProcessor, best_splits::BestSplitsProcessor, connection::ConnectionProcessor, gap_to_leader::GapToLeaderProcessor, grid_position::GridPositionProcessor, lap::LapProcessor, phase_start::PhaseStartProcessor, pit_time::PitTimeProcessor, position::PositionProcessor, projected_position::ProjectedPositionProcessor, session_progress::SessionProgressProcessor, AccProcessor, AccProcessorContext
     },
 };
 
 mod data;
 pub mod model;
+
+pub use data::car_category;
 mod processors;
 
 /// A specialized result for Connection errors.
@@ -57,7 +59,29 @@ impl From<AccConnectionError> for crate::AdapterError {
     }
 }
 
-pub struct AccAdapter;
+/// The adapter for Assetto Corsa Competizione.
+#[derive(Default)]
+pub struct AccAdapter {
+    /// The maximum number of entries in the model.
+    max_entries: Option<usize>,
+}
+
+impl AccAdapter {
+    /// Limit the number of entries in the current session of the model.
+    ///
+    /// Only the entries with the best `max_entries` positions and the focused car
+    /// are shown in the model. Cars that are excluded do not appear in the leaderboard
+    /// until they move into the top positions. Default is no limit.
+    ///
+    /// This only limits what the model exposes. The adapter still reads and processes every
+    /// car each update, so the limit makes the model smaller for its readers but does not
+    /// reduce the work of the adapter.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+}
+
 impl GameAdapter for AccAdapter {
     fn run(
         &mut self,
@@ -66,21 +90,60 @@ impl GameAdapter for AccAdapter {
         update_event: UpdateEvent,
     ) -> result::Result<(), crate::AdapterError> {
         let mut connection = AccConnection::new(model.clone(), command_rx, update_event)?;
+        connection.entry_limit = self.max_entries.map(EntryLimit::new);
 
         // Setup the model state for this game.
         if let Ok(mut model) = model.write() {
             model.event_name = Value::new("Assetto Corsa Competizione".to_string()).with_editable();
-            model.connected = true;
         }
 
         let result = connection.run_loop();
 
+        // The connection state is only changed if the game accepted the registration.
+        // A failed attempt keeps the state of a reconnecting adapter.
         if let Ok(mut model) = model.write() {
-            model.connected = false;
+            if model.connection_state == ConnectionState::Connected {
+                model.set_connection_state(ConnectionState::Disconnected);
+            }
         }
 
         result
     }
+
+    fn game_kind(&self) -> GameKind {
+        GameKind::Acc
+    }
+}
+
+/// Check if the game is running and accepts broadcasting connections.
+///
+/// Registers a connection with the game and closes it again right away.
+/// Blocks for up to `timeout` while waiting for the game to answer.
+pub(crate) fn is_game_available(timeout: Duration) -> bool {
+    let Ok(socket) = UdpSocket::bind("0.0.0.0:0") else {
+        return false;
+    };
+    if socket.connect("127.0.0.1:9000").is_err() || socket.set_read_timeout(Some(timeout)).is_err()
+    {
+        return false;
+    }
+    let mut socket = AccSocket {
+        socket,
+        connected: false,
+        connection_id: 0,
+        read_only: false,
+    };
+    if socket.send_registration_request(100, "", "").is_err() {
+        return false;
+    }
+    match socket.read_message() {
+        Ok(Message::RegistrationResult(result)) => {
+            socket.connection_id = result.connection_id;
+            _ = socket.send_unregister_request();
+            result.success
+        }
+        _ => false,
+    }
 }
 
 pub struct AccConnection {
@@ -89,6 +152,7 @@ pub struct AccConnection {
     update_event: UpdateEvent,
     socket: AccSocket,
     processors: Vec<Box<dyn AccProcessor>>,
+    entry_limit: Option<EntryLimit>,
 }
 
 impl AccConnection {
@@ -121,7 +185,13 @@ impl AccConnection {
                 Box::new(LapProcessor::default()),
                 Box::new(PositionProcessor::default()),
                 Box::new(GapToLeaderProcessor::default()),
+                Box::new(ProjectedPositionProcessor),
+                Box::new(PhaseStartProcessor),
+                Box::new(GridPositionProcessor),
+                Box::new(BestSplitsProcessor),
+                Box::new(PitTimeProcessor::default()),
             ],
+            entry_limit: None,
         })
     }
 
@@ -137,14 +207,8 @@ impl AccConnection {
 
             let should_close = match self.command_rx.try_recv() {
                 Ok(action) => self.handle_command(action)?,
-                Err(TryRecvError::Empty) => false,
-                Err(TryRecvError::Disconnected) => {
-                    // This should only happen if all adapters have been dropped.
-                    // In which case it is impossible to interact with this adapter any more.
-                    // To avoid leaking memory we quit.
-                    error!("All adapter handle have been dropped it is impossible to communicate with this game adapter.");
-                    true
-                }
+                // The command channel stays open while the adapter runs.
+                Err(_) => false,
             };
             if should_close {
                 break;
@@ -157,6 +221,8 @@ impl AccConnection {
                     e => return Err(e.into()),
                 },
             };
+            // ACC does not number its messages. Dropped messages cannot be detected.
+            self.update_event.frame(0);
             self.process_message(&message)?;
 
             // Technically the order of messages put the realtime updates with car information
@@ -188,7 +254,9 @@ impl AccConnection {
                     self.socket.send_change_camera_request(None, camera)?;
                 }
             }
-            AdapterCommand::Game(_) => (),
+            // The update interval is part of the registration and cannot be changed
+            // while connected.
+            _ => (),
         };
         Ok(false)
     }
@@ -202,6 +270,9 @@ impl AccConnection {
                 .map_err(|_| AccConnectionError::Other("Model was poisoned".into()))?,
             events: VecDeque::new(),
         };
+        if let Some(ref mut entry_limit) = self.entry_limit {
+            entry_limit.restore(context.model);
+        }
 
         // Process the message with each processor.
         for processor in &mut self.processors {
@@ -213,8 +284,13 @@ impl AccConnection {
             for processor in &mut self.processors {
                 processor.event(&event, &mut context)?;
             }
-            context.model.events.push(event);
+            context.model.push_event(event);
+        }
+        if let Some(ref mut entry_limit) = self.entry_limit {
+            // The broadcasting api does not tell which car belongs to the player.
+            entry_limit.apply(context.model, None);
         }
+        context.model.last_update = Some(Instant::now());
 
         Ok(())
     }
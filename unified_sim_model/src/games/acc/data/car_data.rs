@@ -1,3 +1,5 @@
+use crate::model::{Car, CarCategory};
+
 pub mod car_categories {
     use crate::model::CarCategory;
 
@@ -9,6 +11,7 @@ pub mod car_categories {
     pub const CUP21: CarCategory = CarCategory::new("CUP");
     pub const CHL: CarCategory = CarCategory::new("CHL");
     pub const TCX: CarCategory = CarCategory::new("TCX");
+    pub const GT2: CarCategory = CarCategory::new("GT2");
     pub const NONE: CarCategory = CarCategory::new("None");
 }
 
@@ -80,5 +83,107 @@ pub mod cars {
     pub const PORSCHE_718_CAYMAN_GT4_CLUBSPORT: Car =
         Car::new_static("Porsche 718 Cayman GT4 Clubsport", "Porsche", GT4);
     pub const MCLAREN_720S_GT3_EVO: Car = Car::new_static("McLaren 720S GT3 Evo", "McLaren", GT3);
+    pub const FORD_MUSTANG_GT3: Car = Car::new_static("Ford Mustang GT3", "Ford", GT3);
+    pub const AUDI_R8_LMS_GT2: Car = Car::new_static("Audi R8 LMS GT2", "Audi", GT2);
+    pub const KTM_X_BOW_GT2: Car = Car::new_static("KTM X-Bow GT2", "KTM", GT2);
+    pub const MASERATI_MC20_GT2: Car = Car::new_static("Maserati MC20 GT2", "Maserati", GT2);
+    pub const MERCEDES_AMG_GT2: Car = Car::new_static("Mercedes AMG GT2", "Mercedes-AMG", GT2);
+    pub const PORSCHE_911_GT2_RS_CS_EVO: Car =
+        Car::new_static("Porsche 911 GT2 RS CS Evo", "Porsche", GT2);
+    pub const PORSCHE_935: Car = Car::new_static("Porsche 935", "Porsche", GT2);
     pub const ERROR: Car = Car::new_static("ERROR", "Error", NONE);
 }
+
+/// Returns the car model for a car model id of the broadcasting protocol.
+/// `None` if the id is not a known car model.
+pub fn car_model(model_id: u8) -> Option<Car> {
+    Some(match model_id {
+        0 => cars::PORSCHE_991_GT3_R,
+        1 => cars::MERCEDES_AMG_GT3_2015,
+        2 => cars::FERRARI_488_GT3,
+        3 => cars::AUDI_R8_LMS,
+        4 => cars::LAMBORGHINI_HURACAN_GT3,
+        5 => cars::MCLAREN_650S_GT3,
+        6 => cars::NISSAN_GT_R_NISMO_GT3_2018,
+        7 => cars::BMW_M6_GT3,
+        8 => cars::BENTLEY_CONTINENTAL_GT3_2018,
+        9 => cars::PORSCHE_991_II_GT3_CUP,
+        10 => cars::NISSAN_GT_R_NISMO_GT3_2015,
+        11 => cars::BENTLEY_CONTINENTAL_GT3_2015,
+        12 => cars::AMR_V12_VANTAGE_GT3,
+        13 => cars::REITER_ENGINEERING_R_EX_GT3,
+        14 => cars::EMIL_FREY_JAGUAR_G3,
+        15 => cars::LEXUS_RC_F_GT3,
+        16 => cars::LAMBORGHINI_HURACAN_GT3_EVO,
+        17 => cars::HONDA_NSX_GT3,
+        18 => cars::LAMBORGHINI_HURACAN_ST,
+        19 => cars::AUDI_R8_LMS_EVO,
+        20 => cars::AMR_V8_VANTAGE,
+        21 => cars::HONDA_NSX_GT3_EVO,
+        22 => cars::MCLAREN_720S_GT3,
+        23 => cars::PORSCHE_911_II_GT3_R,
+        24 => cars::FERRARI_488_GT3_EVO,
+        25 => cars::MERCEDES_AMG_GT3_2020,
+        26 => cars::FERRARI_488_CHALLENGE_EVO,
+        27 => cars::BMW_M2_CS_RACING,
+        28 => cars::PORSCHE_,
+        29 => cars::LAMBORGHINI_HURACAN_ST_EVO2,
+        30 => cars::BMW_M4_GT3,
+        31 => cars::AUDI_R8_LMS_EVO2,
+        32 => cars::FERRARI_296_GT3,
+        33 => cars::LAMBORGHINI_HURACAN_EVO2,
+        34 => cars::PORSCHE_992_GT3_R,
+        35 => cars::MCLAREN_720S_GT3_EVO,
+        36 => cars::FORD_MUSTANG_GT3,
+        50 => cars::ALPINE_A110_GT4,
+        51 => cars::ASTON_MARTIN_VANTAGE_GT4,
+        52 => cars::AUDI_R8_LMS_GT4,
+        53 => cars::BMW_M4_GT4,
+        55 => cars::CHEVROLET_CAMARO_GT4,
+        56 => cars::GINETTA_G55_GT4,
+        57 => cars::KTM_X_BOW_GT4,
+        58 => cars::MASERATI_MC_GT4,
+        59 => cars::MCLAREN_570S_GT4,
+        60 => cars::MERCEDES_AMG_GT4,
+        61 => cars::PORSCHE_718_CAYMAN_GT4_CLUBSPORT,
+        80 => cars::AUDI_R8_LMS_GT2,
+        82 => cars::KTM_X_BOW_GT2,
+        83 => cars::MASERATI_MC20_GT2,
+        84 => cars::MERCEDES_AMG_GT2,
+        85 => cars::PORSCHE_911_GT2_RS_CS_EVO,
+        86 => cars::PORSCHE_935,
+        _ => return None,
+    })
+}
+
+/// Returns the category of a car model id of the broadcasting protocol.
+///
+/// The category is implied by the car model. Unknown car models have the category `None`.
+pub fn car_category(model_id: u8) -> CarCategory {
+    car_model(model_id).map_or(car_categories::NONE, |car| car.category().clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{car_categories, car_category};
+
+    #[test]
+    fn category_of_known_cars() {
+        // Porsche 991 GT3 R
+        assert_eq!(car_category(0), car_categories::GT3);
+        // Porsche 991 II GT3 Cup
+        assert_eq!(car_category(9), car_categories::CUP);
+        // BMW M2 CS Racing
+        assert_eq!(car_category(27), car_categories::TCX);
+        // Alpine A110 GT4
+        assert_eq!(car_category(50), car_categories::GT4);
+        // Porsche 935
+        assert_eq!(car_category(86), car_categories::GT2);
+    }
+
+    #[test]
+    fn category_of_unknown_car() {
+        assert_eq!(car_category(54), car_categories::NONE);
+        assert_eq!(car_category(255), car_categories::NONE);
+    }
+}
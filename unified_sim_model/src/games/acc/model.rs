@@ -2,6 +2,8 @@
 
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
 use crate::model::{Camera, EntryGameData, GameCamera, SessionGameData};
 
 use super::{data::CarLocation, AccConnectionError};
@@ -10,7 +12,7 @@ use super::{data::CarLocation, AccConnectionError};
 ///
 /// These fields may not necessairly be usefull to anyone but they
 /// exist to make all the data from the game available.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AccSession {
     /// This values doesnt look like it does anything.
     pub event_index: i16,
@@ -79,7 +81,7 @@ impl SessionGameData {
 ///
 /// These fields may not necessairly be usefull to anyone but they
 /// exist to make all the data from the game available.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AccEntry {
     /// The ingame id for this car.
     pub car_id: i16,
@@ -130,7 +132,7 @@ impl EntryGameData {
     }
 }
 
-#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AccCamera {
     /// A Helicopter camera.
     Helicam,
@@ -14,11 +14,16 @@ use super::{
 };
 
 pub mod base;
+pub mod best_splits;
 pub mod connection;
 pub mod distance_driven;
 pub mod entry_finished;
 pub mod gap_to_leader;
+pub mod grid_position;
 pub mod lap;
+pub mod phase_start;
+pub mod pit_time;
+pub mod projected_position;
 
 pub mod session_progress;
 pub mod position;
@@ -3,13 +3,16 @@ use std::collections::HashMap;
 use tracing::{debug, info};
 
 use crate::{
-    games::acc::{
-        data::{
-            CarLocation, EntryListCar, RealtimeCarUpdate, RegistrationResult, SessionPhase,
-            SessionType, SessionUpdate, TrackData,
+    games::{
+        acc::{
+            data::{
+                CarLocation, EntryListCar, RealtimeCarUpdate, RegistrationResult, SessionPhase,
+                SessionType, SessionUpdate, TrackData,
+            },
+            model::{AccCamera, AccEntry, AccSession},
+            AccConnectionError, AccProcessorContext, Result,
         },
-        model::{AccCamera, AccEntry, AccSession},
-        AccConnectionError, AccProcessorContext, Result,
+        common::pit_stops::PitStopCounter,
     },
     model::{
         self, Camera, Day, Driver, DriverId, Entry, EntryGameData, EntryId, Event, GameCamera, Lap,
@@ -30,6 +33,8 @@ pub struct BaseProcessor {
     /// Entries that we received an entry list entry for but are not yet added to
     /// the session. They are added with the next realtime update for that entry.
     prepared_entries: HashMap<EntryId, Entry>,
+    /// Counts the pit stops of the entries.
+    pit_stops: PitStopCounter,
 }
 
 impl AccProcessor for BaseProcessor {
@@ -92,6 +97,7 @@ impl AccProcessor for BaseProcessor {
                 day: Value::default_with_value(Day::Sunday).with_editable(),
                 game_data: SessionGameData::Acc(AccSession::default()),
                 best_lap: Value::new(None),
+                sector_count: Value::new(3),
                 ..Default::default()
             };
             let id = context.model.add_session(session);
@@ -151,10 +157,16 @@ impl AccProcessor for BaseProcessor {
         for entry in session.entries.values_mut() {
             entry.focused = entry.id == focused_entry;
         }
-        context.model.focused_entry = session
+        let focused_entry = session
             .entries
             .contains_key(&focused_entry)
             .then_some(focused_entry);
+        if context.model.focused_entry != focused_entry {
+            context.model.focused_entry = focused_entry;
+            context.events.push_back(Event::FocusChanged {
+                entry: focused_entry,
+            });
+        }
         context.model.active_camera = map_camera(&update.active_camera_set, &update.active_camera)
             .unwrap_or(Camera::None)
             .into();
@@ -206,6 +218,17 @@ impl AccProcessor for BaseProcessor {
             .expect("Entry must exists at this point");
 
         let current_driver_id = DriverId(update.driver_id as i32);
+        if entry.current_driver != current_driver_id {
+            info!(
+                "Driver change for #{}: {:?} -> {:?}",
+                *entry.car_number, entry.current_driver, current_driver_id
+            );
+            context.events.push_back(Event::DriverChange {
+                entry: entry_id,
+                from: entry.current_driver,
+                to: current_driver_id,
+            });
+        }
         entry.current_driver = current_driver_id;
         entry
             .orientation
@@ -218,13 +241,15 @@ impl AccProcessor for BaseProcessor {
             time: Time::from(update.current_lap.laptime_ms).into(),
             splits: Vec::new().into(),
             invalid: update.current_lap.is_invaliud.into(),
+            caution: Value::default(),
             driver_id: Some(current_driver_id),
             entry_id: Some(entry_id),
+            driving_metrics: None,
         });
         entry.performance_delta.set(update.delta.into());
-        entry
-            .in_pits
-            .set(update.car_location == CarLocation::Pitlane);
+        let in_pits = update.car_location == CarLocation::Pitlane;
+        self.pit_stops.update(entry, in_pits, update.kmh == 0);
+        entry.in_pits.set(in_pits);
         entry.gear.set(update.gear as i32);
         entry.speed.set(update.kmh as f32);
 
@@ -239,9 +264,12 @@ impl AccProcessor for BaseProcessor {
     fn track_data(&mut self, track: &TrackData, context: &mut AccProcessorContext) -> Result<()> {
         if let Some(session) = context.model.current_session_mut() {
             session.track_name.set(track.track_name.clone());
-            session
-                .track_length
-                .set(Distance::from_meter(track.track_meter as f32));
+            // The track length is zero while the track is still loading.
+            if track.track_meter > 0 {
+                session
+                    .track_length
+                    .set(Distance::from_meter(track.track_meter as f32));
+            }
         }
         let available_cameras = &mut context.model.available_cameras;
         for (set, cameras) in track.camera_sets.iter() {
@@ -370,3 +398,95 @@ fn map_camera(set: &str, camera: &str) -> Option<Camera> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, net::UdpSocket};
+
+    use crate::{
+        games::acc::{
+            data::{LapInfo, RealtimeCarUpdate},
+            model::AccEntry,
+            processors::{AccProcessor, AccProcessorContext},
+            AccSocket,
+        },
+        model::{DriverId, Entry, EntryGameData, EntryId, Event, Model, Session, SessionId},
+    };
+
+    use super::BaseProcessor;
+
+    fn realtime_update(driver_id: i16) -> RealtimeCarUpdate {
+        RealtimeCarUpdate {
+            car_id: 7,
+            driver_id,
+            driver_cound: 2,
+            gear: 3,
+            yaw: 0.0,
+            pitch: 0.0,
+            roll: 0.0,
+            car_location: Default::default(),
+            kmh: 150,
+            position: 1,
+            cup_position: 1,
+            track_position: 1,
+            spline_position: 0.5,
+            laps: 3,
+            delta: 0,
+            best_session_lap: LapInfo::default(),
+            last_lap: LapInfo::default(),
+            current_lap: LapInfo::default(),
+        }
+    }
+
+    #[test]
+    fn driver_change_from_realtime_update() {
+        let mut session = Session {
+            id: SessionId(0),
+            ..Default::default()
+        };
+        let entry = Entry {
+            id: EntryId(7),
+            current_driver: DriverId(0),
+            game_data: EntryGameData::Acc(AccEntry::default()),
+            ..Default::default()
+        };
+        session.entries.insert(entry.id, entry);
+        let mut model = Model::default();
+        model.sessions.insert(session.id, session);
+        model.current_session = Some(SessionId(0));
+
+        let mut socket = AccSocket {
+            connected: true,
+            connection_id: 0,
+            read_only: false,
+            socket: UdpSocket::bind("127.0.0.1:0").unwrap(),
+        };
+        let mut context = AccProcessorContext {
+            socket: &mut socket,
+            model: &mut model,
+            events: VecDeque::new(),
+        };
+        let mut processor = BaseProcessor::default();
+        assert!(processor
+            .realtime_car_update(&realtime_update(0), &mut context)
+            .is_ok());
+        assert!(processor
+            .realtime_car_update(&realtime_update(1), &mut context)
+            .is_ok());
+
+        let driver_changes: Vec<_> = context
+            .events
+            .iter()
+            .filter(|event| matches!(event, Event::DriverChange { .. }))
+            .collect();
+        assert_eq!(driver_changes.len(), 1);
+        assert!(matches!(
+            driver_changes[0],
+            Event::DriverChange {
+                entry: EntryId(7),
+                from: DriverId(0),
+                to: DriverId(1),
+            }
+        ));
+    }
+}
@@ -0,0 +1,16 @@
+use crate::games::common::best_splits;
+
+use super::AccProcessor;
+
+/// Records the best time of each sector in a session.
+pub struct BestSplitsProcessor;
+impl AccProcessor for BestSplitsProcessor {
+    fn event(
+        &mut self,
+        event: &crate::model::Event,
+        context: &mut super::AccProcessorContext,
+    ) -> crate::games::acc::Result<()> {
+        best_splits::calc_best_splits(event, context.model);
+        Ok(())
+    }
+}
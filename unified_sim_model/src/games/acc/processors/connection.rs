@@ -4,10 +4,10 @@ use tracing::info;
 
 use crate::{
     games::acc::{
-        data::{RealtimeCarUpdate, SessionUpdate},
+        data::{RealtimeCarUpdate, RegistrationResult, SessionUpdate},
         AccProcessorContext, Result,
     },
-    model::{EntryId, Event},
+    model::{ConnectionState, EntryId, Event},
 };
 
 use super::AccProcessor;
@@ -25,6 +25,7 @@ use super::AccProcessor;
 /// If an entry starts receiving updates after it was disconnected,
 /// it will be reconnected and trigger the event.
 ///
+/// The processor also marks the model as connected once the game accepted the registration.
 #[derive(Debug, Default)]
 pub struct ConnectionProcessor {
     /// Maps an entry to their connection status.
@@ -32,6 +33,20 @@ pub struct ConnectionProcessor {
 }
 
 impl AccProcessor for ConnectionProcessor {
+    fn registration_result(
+        &mut self,
+        result: &RegistrationResult,
+        context: &mut AccProcessorContext,
+    ) -> Result<()> {
+        // The adapter is only connected once the game has accepted the registration.
+        if result.success {
+            context
+                .model
+                .set_connection_state(ConnectionState::Connected);
+        }
+        Ok(())
+    }
+
     fn session_update(
         &mut self,
         _update: &SessionUpdate,
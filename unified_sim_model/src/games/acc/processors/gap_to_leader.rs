@@ -21,7 +21,7 @@ use std::{collections::HashMap, time::Instant};
 
 use crate::{
     games::acc::data::SessionUpdate,
-    model::{Entry, EntryId, Event, ScoringType, Value},
+    model::{EntryId, Event, ScoringType, Value},
     Time,
 };
 
@@ -93,9 +93,7 @@ impl AccProcessor for BestLapTime {
             return Ok(());
         };
 
-        let mut entries: Vec<&Entry> = session.entries.values().collect();
-        entries.sort_by_key(|e| *e.position);
-        let entries = entries.iter().map(|e| e.id).collect::<Vec<_>>();
+        let entries: Vec<EntryId> = session.entries_by_position().iter().map(|e| e.id).collect();
 
         let mut prev_position_best_lap_time = session_best_lap_time;
         for entry_id in entries {
@@ -135,16 +133,17 @@ impl AccProcessor for DistanceThenTime {
         let Some(session) = context.model.current_session_mut() else {
             return Ok(());
         };
+        let Some(track_length) = session.known_track_length() else {
+            return Ok(());
+        };
 
-        let mut entries: Vec<&Entry> = session.entries.values().collect();
-        entries.sort_by_key(|e| *e.position);
-        let entries = entries.iter().map(|e| e.id).collect::<Vec<_>>();
+        let entries: Vec<EntryId> = session.entries_by_position().iter().map(|e| e.id).collect();
 
         let now = Instant::now();
         for entry_id in entries {
             let entry = session.entries.get_mut(&entry_id).unwrap();
-            let sector_number = (*entry.distance_driven * session.track_length.as_meters() / 200.0)
-                .floor() as usize;
+            let sector_number =
+                (*entry.distance_driven * track_length.as_meters() / 200.0).floor() as usize;
 
             // Check if this entry has crossed the next sector line.
             if let Some(prev_sector_number) = self.sectors_positions.get(&entry_id) {
@@ -0,0 +1,16 @@
+use crate::games::common::grid_position;
+
+use super::AccProcessor;
+
+/// Records the grid position of the entries when a race starts.
+pub struct GridPositionProcessor;
+impl AccProcessor for GridPositionProcessor {
+    fn event(
+        &mut self,
+        event: &crate::model::Event,
+        context: &mut super::AccProcessorContext,
+    ) -> crate::games::acc::Result<()> {
+        grid_position::calc_grid_position(event, context.model);
+        Ok(())
+    }
+}
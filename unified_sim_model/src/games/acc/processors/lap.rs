@@ -1,13 +1,13 @@
 use std::collections::HashMap;
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::{
     games::acc::{
         data::{LapInfo, RealtimeCarUpdate},
         AccProcessorContext, Result,
     },
-    model::{DriverId, EntryId, Event, Lap, LapCompleted, Session},
+    model::{DriverId, EntryId, Event, Lap, LapCompleted, Session, Value},
     types::Time,
 };
 
@@ -60,8 +60,10 @@ fn map_lap(lap_info: &LapInfo, driver_id: DriverId, entry_id: EntryId) -> Lap {
             .collect::<Vec<_>>()
             .into(),
         invalid: lap_info.is_invaliud.into(),
+        caution: Value::default(),
         driver_id: Some(driver_id),
         entry_id: Some(entry_id),
+        driving_metrics: None,
     }
 }
 
@@ -125,6 +127,12 @@ fn lap_completed(session: &mut Session, entry_id: EntryId, update: &RealtimeCarU
     let current_driver = entry.current_driver;
 
     let lap = map_lap(&update.last_lap, current_driver, entry.id);
+    if !lap.splits_consistent(Time::from(10)) {
+        warn!(
+            "The splits of the lap of car #{} do not add up to the lap time. A sector may have been missed",
+            entry.car_number
+        );
+    }
     entry.laps.push(lap.clone());
 
     let personal_best = entry
@@ -0,0 +1,17 @@
+use crate::games::common::phase_start;
+
+use super::AccProcessor;
+
+/// Records when the phase of a session starts.
+#[derive(Default)]
+pub struct PhaseStartProcessor;
+impl AccProcessor for PhaseStartProcessor {
+    fn event(
+        &mut self,
+        event: &crate::model::Event,
+        context: &mut super::AccProcessorContext,
+    ) -> crate::games::acc::Result<()> {
+        phase_start::calc_phase_start(event, context.model);
+        Ok(())
+    }
+}
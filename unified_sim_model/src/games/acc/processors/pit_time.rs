@@ -0,0 +1,41 @@
+use std::time::Instant;
+
+use crate::{
+    games::{acc::data::SessionUpdate, common::pit_time::PitTimer},
+    model::Event,
+};
+
+use super::{AccProcessor, AccProcessorContext};
+
+/// Measures the time entries spend in the pitlane.
+///
+/// Runs on the session update so that the pit state of all entries has been updated
+/// by the realtime car updates before.
+#[derive(Default)]
+pub struct PitTimeProcessor {
+    timer: PitTimer,
+}
+
+impl AccProcessor for PitTimeProcessor {
+    fn session_update(
+        &mut self,
+        _update: &SessionUpdate,
+        context: &mut AccProcessorContext,
+    ) -> crate::games::acc::Result<()> {
+        if let Some(session) = context.model.current_session_mut() {
+            self.timer.update(session, Instant::now());
+        }
+        Ok(())
+    }
+
+    fn event(
+        &mut self,
+        event: &Event,
+        _context: &mut AccProcessorContext,
+    ) -> crate::games::acc::Result<()> {
+        if let Event::SessionChanged(_) = event {
+            self.timer.clear();
+        }
+        Ok(())
+    }
+}
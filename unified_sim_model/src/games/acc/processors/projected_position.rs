@@ -0,0 +1,22 @@
+use crate::games::{acc::data::SessionUpdate, common::projected_position};
+
+use super::{AccProcessor, AccProcessorContext};
+
+/// Projects the position of all entries at the next timing line.
+///
+/// Runs on the session update so that the distance driven of all entries has been updated
+/// by the realtime car updates before.
+#[derive(Default)]
+pub struct ProjectedPositionProcessor;
+impl AccProcessor for ProjectedPositionProcessor {
+    fn session_update(
+        &mut self,
+        _update: &SessionUpdate,
+        context: &mut AccProcessorContext,
+    ) -> crate::games::acc::Result<()> {
+        if let Some(session) = context.model.current_session_mut() {
+            projected_position::calc_projected_positions(session);
+        }
+        Ok(())
+    }
+}
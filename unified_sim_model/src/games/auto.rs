@@ -0,0 +1,82 @@
+//! An adapter that connects to the first game that is available.
+
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
+
+use tracing::info;
+
+use crate::{
+    model::{ConnectionState, GameKind, Model},
+    AdapterCommand, AdapterError, GameAdapter, UpdateEvent,
+};
+
+use super::{acc, iracing};
+
+/// An adapter that probes for a running game and connects to it.
+///
+/// The games are probed in this order:
+/// 1. Assetto Corsa Competizione
+/// 2. iRacing
+///
+/// Once a game is found, the adapter runs the adapter for that game.
+pub struct AutoAdapter {
+    /// The time to wait between probing for games.
+    probe_interval: Duration,
+}
+
+impl Default for AutoAdapter {
+    fn default() -> Self {
+        Self {
+            probe_interval: Duration::from_secs(1),
+        }
+    }
+}
+
+impl GameAdapter for AutoAdapter {
+    fn run(
+        &mut self,
+        model: Arc<RwLock<Model>>,
+        command_rx: mpsc::Receiver<AdapterCommand>,
+        update_event: UpdateEvent,
+    ) -> Result<(), AdapterError> {
+        if let Ok(mut model) = model.write() {
+            model.set_connection_state(ConnectionState::Connecting);
+        }
+        loop {
+            if acc::is_game_available(Duration::from_millis(200)) {
+                info!("Found Assetto Corsa Competizione");
+                set_game(&model, GameKind::Acc);
+                return acc::AccAdapter::default().run(model, command_rx, update_event);
+            }
+            if iracing::is_game_available() {
+                info!("Found iRacing");
+                set_game(&model, GameKind::IRacing);
+                return iracing::IRacingAdapter::default().run(model, command_rx, update_event);
+            }
+
+            // Wait for the next probe while still reacting to commands.
+            // Other commands than `Close` have no effect without a game.
+            match command_rx.recv_timeout(self.probe_interval) {
+                // A closed channel cannot send a `Close` command anymore.
+                Ok(AdapterCommand::Close) | Err(RecvTimeoutError::Disconnected) => break,
+                Ok(_) | Err(RecvTimeoutError::Timeout) => (),
+            }
+        }
+
+        if let Ok(mut model) = model.write() {
+            model.set_connection_state(ConnectionState::Disconnected);
+        }
+        Ok(())
+    }
+}
+
+fn set_game(model: &RwLock<Model>, game: GameKind) {
+    if let Ok(mut model) = model.write() {
+        model.game = game;
+    }
+}
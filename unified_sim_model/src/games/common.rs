@@ -1,2 +1,11 @@
+pub mod best_splits;
+pub mod debounce;
 pub mod distance_driven;
+pub mod driving_metrics;
 pub mod entry_finished;
+pub mod grid_position;
+pub mod max_entries;
+pub mod phase_start;
+pub mod pit_stops;
+pub mod pit_time;
+pub mod projected_position;
@@ -0,0 +1,85 @@
+//! This processor records the best time of each sector in a session.
+
+use crate::model::{Event, LapCompleted, Model};
+
+/// Update the best splits of the current session when a valid lap is completed.
+pub fn calc_best_splits(event: &Event, model: &mut Model) {
+    let Event::LapCompleted(LapCompleted { lap, .. }) = event else {
+        return;
+    };
+    if *lap.invalid {
+        return;
+    }
+    let Some(splits) = lap.splits.get_available() else {
+        return;
+    };
+    let Some(session) = model.current_session_mut() else {
+        return;
+    };
+    for (sector, split) in splits.iter().enumerate() {
+        match session.best_splits.get_mut(sector) {
+            Some(best) => {
+                if split.ms > 0.0 && (best.ms <= 0.0 || split.ms < best.ms) {
+                    *best = *split;
+                }
+            }
+            None => session.best_splits.push(*split),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        model::{Entry, EntryId, Event, Lap, LapCompleted, Model, Session, Value},
+        Time,
+    };
+
+    use super::calc_best_splits;
+
+    fn lap_completed(splits: [i32; 3], invalid: bool) -> Event {
+        Event::LapCompleted(LapCompleted {
+            lap: Lap {
+                splits: Value::new(splits.into_iter().map(Time::from).collect()),
+                invalid: Value::new(invalid),
+                entry_id: Some(EntryId(1)),
+                ..Default::default()
+            },
+            is_session_best: false,
+            is_entry_best: false,
+            is_driver_best: false,
+        })
+    }
+
+    #[test]
+    fn best_splits_of_valid_laps() {
+        let mut model = Model::default();
+        let id = model.add_session(Session::default());
+        model.current_session = Some(id);
+
+        calc_best_splits(&lap_completed([31_000, 32_000, 33_000], false), &mut model);
+        calc_best_splits(&lap_completed([30_000, 35_000, 32_500], false), &mut model);
+        calc_best_splits(&lap_completed([29_000, 29_000, 29_000], true), &mut model);
+
+        let session = model.current_session_mut().unwrap();
+        let expected: Vec<Time> = [30_000, 32_000, 32_500].map(Time::from).into();
+        assert_eq!(session.best_splits, expected);
+
+        // The entry has the best time in the first and last sector.
+        let mut entry = Entry {
+            id: EntryId(1),
+            ..Default::default()
+        };
+        let Event::LapCompleted(LapCompleted { lap, .. }) =
+            lap_completed([30_000, 35_000, 32_500], false)
+        else {
+            unreachable!();
+        };
+        entry.laps.push(lap);
+        session.entries.insert(entry.id, entry);
+        let bests: Vec<bool> = (0..4)
+            .map(|s| session.is_sector_best(EntryId(1), s))
+            .collect();
+        assert_eq!(bests, vec![true, false, true, false]);
+    }
+}
@@ -0,0 +1,101 @@
+//! Debounces a value that is read from the game.
+//!
+//! Some values in the game data can flicker for a single update, for example a flag
+//! that is shown for one frame during a green flag. A debounced value only changes once the
+//! new value has been seen for a minimum amount of time. Changes that are shorter than that
+//! are ignored.
+
+use std::time::{Duration, Instant};
+
+/// A value that only changes once a new value has been stable for a minimum duration.
+pub struct Debounce<T> {
+    /// The minimum time a new value must be stable before it is accepted.
+    hold: Duration,
+    /// The current accepted value.
+    value: T,
+    /// A new value and the instant it was first seen.
+    pending: Option<(T, Instant)>,
+}
+
+impl<T: PartialEq> Debounce<T> {
+    /// Create a new debounced value.
+    pub fn new(value: T, hold: Duration) -> Self {
+        Self {
+            hold,
+            value,
+            pending: None,
+        }
+    }
+
+    /// Returns the current accepted value.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Accept a value immediately and forget any pending change.
+    pub fn reset(&mut self, value: T) {
+        self.value = value;
+        self.pending = None;
+    }
+
+    /// Update the debounced value with a new value from the game.
+    /// Returns `true` if the accepted value changed.
+    pub fn update(&mut self, value: T, now: Instant) -> bool {
+        if value == self.value {
+            self.pending = None;
+            return false;
+        }
+        let since = match self.pending {
+            Some((ref pending, since)) if *pending == value => since,
+            _ => {
+                self.pending = Some((value, now));
+                now
+            }
+        };
+        if now.duration_since(since) < self.hold {
+            return false;
+        }
+        if let Some((value, _)) = self.pending.take() {
+            self.value = value;
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::Debounce;
+
+    #[test]
+    fn ignore_single_frame_change() {
+        let start = Instant::now();
+        let frame = Duration::from_millis(16);
+        let mut caution = Debounce::new(false, Duration::from_millis(500));
+
+        assert!(!caution.update(true, start + frame));
+        assert!(!caution.update(false, start + frame * 2));
+        assert!(!caution.update(false, start + frame * 40));
+        assert!(!caution.value());
+    }
+
+    #[test]
+    fn accept_stable_change() {
+        let start = Instant::now();
+        let mut caution = Debounce::new(false, Duration::from_millis(500));
+
+        assert!(!caution.update(true, start));
+        assert!(!caution.update(true, start + Duration::from_millis(300)));
+        assert!(caution.update(true, start + Duration::from_millis(500)));
+        assert!(caution.value());
+        assert!(!caution.update(true, start + Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn no_hold_accepts_immediately() {
+        let mut caution = Debounce::new(false, Duration::ZERO);
+        assert!(caution.update(true, Instant::now()));
+        assert!(caution.value());
+    }
+}
@@ -0,0 +1,94 @@
+//! Measures how the pedals are used during a lap.
+//!
+//! The throttle and brake are sampled while the lap is driven. Each sample is weighted
+//! with the time since the previous sample so that an uneven sample rate does not
+//! skew the result.
+
+use crate::{model::DrivingMetrics, Time};
+
+/// A pedal below this value is considered released.
+const RELEASED: f32 = 0.05;
+/// A throttle above this value is considered fully pressed.
+const FULL_THROTTLE: f32 = 0.98;
+
+/// Collects the pedal samples of a lap.
+#[derive(Debug, Default)]
+pub struct DrivingMetricsRecorder {
+    /// The time of the lap that was sampled in seconds.
+    total: f64,
+    /// The time where no pedal was pressed in seconds.
+    coasting: f64,
+    /// The time where throttle and brake were pressed in seconds.
+    overlap: f64,
+    /// The time with full throttle in seconds.
+    full_throttle: f64,
+}
+
+impl DrivingMetricsRecorder {
+    /// Add a sample of the pedals. `throttle` and `brake` are between 0.0 and 1.0.
+    /// `duration` is the time since the previous sample.
+    pub fn sample(&mut self, throttle: f32, brake: f32, duration: Time) {
+        let seconds = duration.ms / 1000.0;
+        if seconds <= 0.0 || !seconds.is_finite() {
+            return;
+        }
+        self.total += seconds;
+        let throttle_pressed = throttle >= RELEASED;
+        let brake_pressed = brake >= RELEASED;
+        if !throttle_pressed && !brake_pressed {
+            self.coasting += seconds;
+        }
+        if throttle_pressed && brake_pressed {
+            self.overlap += seconds;
+        }
+        if throttle >= FULL_THROTTLE {
+            self.full_throttle += seconds;
+        }
+    }
+
+    /// Returns the metrics of the samples and starts a new lap.
+    /// `None` if no samples were collected.
+    pub fn finish(&mut self) -> Option<DrivingMetrics> {
+        let recorder = std::mem::take(self);
+        if recorder.total <= 0.0 {
+            return None;
+        }
+        let percent = |seconds: f64| (seconds / recorder.total * 100.0) as f32;
+        Some(DrivingMetrics {
+            coasting_time: Time::from_secs(recorder.coasting),
+            trail_braking: percent(recorder.overlap),
+            full_throttle: percent(recorder.full_throttle),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Time;
+
+    use super::DrivingMetricsRecorder;
+
+    #[test]
+    fn metrics_of_synthetic_trace() {
+        let mut recorder = DrivingMetricsRecorder::default();
+        // (throttle, brake, duration in ms) for a 10 second lap.
+        let trace = [
+            (1.0, 0.0, 4_000),
+            (0.0, 0.0, 1_000),
+            (0.0, 0.9, 2_000),
+            (0.3, 0.4, 1_000),
+            (0.6, 0.0, 1_500),
+            (1.0, 0.0, 500),
+        ];
+        for (throttle, brake, ms) in trace {
+            recorder.sample(throttle, brake, Time::from(ms));
+        }
+        let metrics = recorder.finish().unwrap();
+        assert_eq!(metrics.coasting_time, Time::from(1_000));
+        assert!((metrics.trail_braking - 10.0).abs() < 1e-4);
+        assert!((metrics.full_throttle - 45.0).abs() < 1e-4);
+
+        // The recorder starts over after a lap.
+        assert!(recorder.finish().is_none());
+    }
+}
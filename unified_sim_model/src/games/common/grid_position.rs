@@ -0,0 +1,81 @@
+//! This processor records the grid position of each entry when a race starts.
+
+use crate::model::{Event, Model, SessionPhase, SessionType};
+
+/// Set the grid position of all entries when a race goes green.
+///
+/// The grid position is only recorded if the leader has not completed more than the formation
+/// lap. When the adapter connects to a race that has already started, the phase changes to
+/// active as soon as the session is seen. The positions at that point are not the grid, so
+/// the grid position is left unavailable.
+pub fn calc_grid_position(event: &Event, model: &mut Model) {
+    let Event::SessionPhaseChanged(id, SessionPhase::Active) = event else {
+        return;
+    };
+    let Some(session) = model.sessions.get_mut(id) else {
+        return;
+    };
+    if *session.session_type != SessionType::Race {
+        return;
+    }
+    let race_started = session.entries.values().any(|entry| *entry.lap_count > 1);
+    if race_started {
+        return;
+    }
+    for entry in session.entries.values_mut() {
+        if *entry.position > 0 && !entry.grid_position.is_avaliable() {
+            entry.grid_position.set(*entry.position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Entry, EntryId, Event, Model, Session, SessionPhase, SessionType, Value};
+
+    use super::calc_grid_position;
+
+    fn race(lap_count: i32) -> (Model, Event) {
+        let mut model = Model::default();
+        let mut session = Session {
+            session_type: Value::new(SessionType::Race),
+            ..Default::default()
+        };
+        for (id, position) in [(0, 12), (1, 1)] {
+            let entry = Entry {
+                id: EntryId(id),
+                position: Value::new(position),
+                lap_count: Value::new(lap_count),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        let id = model.add_session(session);
+        (model, Event::SessionPhaseChanged(id, SessionPhase::Active))
+    }
+
+    #[test]
+    fn grid_position_at_green() {
+        let (mut model, event) = race(0);
+        calc_grid_position(&event, &mut model);
+
+        let session = model.sessions.values_mut().next().unwrap();
+        let entry = session.entries.get_mut(&EntryId(0)).unwrap();
+        assert_eq!(*entry.grid_position, 12);
+        entry.position.set(5);
+        assert_eq!(entry.positions_gained(), Some(7));
+        entry.position.set(14);
+        assert_eq!(entry.positions_gained(), Some(-2));
+    }
+
+    #[test]
+    fn connected_after_start() {
+        let (mut model, event) = race(5);
+        calc_grid_position(&event, &mut model);
+
+        let session = model.sessions.values().next().unwrap();
+        let entry = session.entries.get(&EntryId(0)).unwrap();
+        assert!(!entry.grid_position.is_avaliable());
+        assert_eq!(entry.positions_gained(), None);
+    }
+}
@@ -0,0 +1,139 @@
+//! Limits the number of entries in the current session of the model.
+//!
+//! On a crowded server a game can report many more cars than an overlay wants to show.
+//! The limit keeps the entries with the best positions and always keeps the player and
+//! the focused car. The other entries are set aside while the model is shared and put back
+//! before the adapter updates the model again. This way an entry that moves into the top
+//! positions shows up again and the adapter processors always see the full field.
+//!
+//! The limit only applies to what the model exposes. Every entry is still processed by the
+//! adapter on every update.
+
+use std::collections::HashMap;
+
+use crate::model::{Entry, EntryId, Model, SessionId};
+
+/// Keeps at most a maximum number of entries in the current session.
+pub struct EntryLimit {
+    /// The maximum number of entries in the session, not counting the player and focused car.
+    max_entries: usize,
+    /// The session the hidden entries were removed from.
+    session: Option<SessionId>,
+    /// The entries that were removed from the session.
+    hidden: HashMap<EntryId, Entry>,
+}
+
+impl EntryLimit {
+    /// Create a new limit with a maximum number of entries.
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            session: None,
+            hidden: HashMap::new(),
+        }
+    }
+
+    /// Put the hidden entries back into the session they were removed from.
+    /// Call this before the adapter updates the model so that every entry is processed.
+    ///
+    /// Entries that were added to the session again in the meantime are not replaced.
+    pub fn restore(&mut self, model: &mut Model) {
+        let Some(session) = self
+            .session
+            .take()
+            .and_then(|id| model.sessions.get_mut(&id))
+        else {
+            self.hidden.clear();
+            return;
+        };
+        for (id, entry) in self.hidden.drain() {
+            session.entries.entry(id).or_insert(entry);
+        }
+    }
+
+    /// Remove all entries from the current session that are not in the top positions.
+    ///
+    /// The `player` entry and the focused entry of the model are always kept. Entries without
+    /// a position are placed behind the entries with a position.
+    pub fn apply(&mut self, model: &mut Model, player: Option<EntryId>) {
+        self.restore(model);
+        let focused_entry = model.focused_entry;
+        let Some(session) = model.current_session_mut() else {
+            return;
+        };
+        if session.entries.len() <= self.max_entries {
+            return;
+        }
+
+        let mut order: Vec<(i32, EntryId)> = session
+            .entries
+            .values()
+            .map(|entry| (*entry.position, entry.id))
+            .collect();
+        order.sort_by_key(|&(position, id)| (position <= 0, position, id.0));
+        for (_, id) in order.into_iter().skip(self.max_entries) {
+            if Some(id) == player || Some(id) == focused_entry {
+                continue;
+            }
+            if let Some(entry) = session.entries.remove(&id) {
+                self.hidden.insert(id, entry);
+            }
+        }
+        self.session = Some(session.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Entry, EntryId, Model, Session, Value};
+
+    use super::EntryLimit;
+
+    fn model(entry_count: i32) -> Model {
+        let mut model = Model::default();
+        let mut session = Session::default();
+        for id in 0..entry_count {
+            let entry = Entry {
+                id: EntryId(id),
+                position: Value::new(entry_count - id),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        let id = model.add_session(session);
+        model.current_session = Some(id);
+        model
+    }
+
+    fn entry_ids(model: &Model) -> Vec<i32> {
+        let mut ids: Vec<i32> = model
+            .current_session()
+            .unwrap()
+            .entries
+            .keys()
+            .map(|id| id.0)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn keeps_top_positions() {
+        let mut model = model(10);
+        let mut limit = EntryLimit::new(3);
+        limit.apply(&mut model, None);
+        assert_eq!(entry_ids(&model), vec![7, 8, 9]);
+
+        limit.restore(&mut model);
+        assert_eq!(entry_ids(&model).len(), 10);
+    }
+
+    #[test]
+    fn keeps_player_and_focused_entry() {
+        let mut model = model(10);
+        model.focused_entry = Some(EntryId(1));
+        let mut limit = EntryLimit::new(3);
+        limit.apply(&mut model, Some(EntryId(0)));
+        assert_eq!(entry_ids(&model), vec![0, 1, 7, 8, 9]);
+    }
+}
@@ -0,0 +1,25 @@
+//! This processor records when the phase of a session starts.
+
+use std::time::Instant;
+
+use crate::model::{Event, Model, PhaseStart};
+
+/// Set the phase start of a session when its phase changes.
+pub fn calc_phase_start(event: &Event, model: &mut Model) {
+    let Event::SessionPhaseChanged(id, _) = event else {
+        return;
+    };
+    let Some(session) = model.sessions.get_mut(id) else {
+        return;
+    };
+    let leader_distance = session
+        .entries
+        .values()
+        .map(|entry| *entry.distance_driven)
+        .max_by(|a, b| a.total_cmp(b))
+        .unwrap_or_default();
+    session.phase_start = Some(PhaseStart {
+        instant: Instant::now(),
+        leader_distance,
+    });
+}
@@ -0,0 +1,61 @@
+//! Counts the pit stops of the entries.
+//!
+//! Entering the pitlane alone is not a pit stop since a car may only drive through it.
+//! A stop is counted once per visit to the pitlane, the first time the car is seen
+//! stationary or in its pit box.
+
+use std::collections::HashSet;
+
+use crate::model::{Entry, EntryId};
+
+/// Counts the pit stops of entries.
+#[derive(Debug, Default)]
+pub struct PitStopCounter {
+    /// Entries that have already stopped during their current visit to the pitlane.
+    stopped: HashSet<EntryId>,
+}
+
+impl PitStopCounter {
+    /// Update the pit stop count of an entry.
+    ///
+    /// `stopped` is true while the entry is stationary or being serviced in its pit box.
+    pub fn update(&mut self, entry: &mut Entry, in_pits: bool, stopped: bool) {
+        if !in_pits {
+            self.stopped.remove(&entry.id);
+        } else if stopped && self.stopped.insert(entry.id) {
+            let pit_stops = *entry.pit_stops_completed + 1;
+            entry.pit_stops_completed.set(pit_stops);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::model::{Entry, EntryId};
+
+    use super::PitStopCounter;
+
+    #[test]
+    fn count_stops_once_per_visit() {
+        let mut entry = Entry {
+            id: EntryId(1),
+            ..Default::default()
+        };
+        let mut counter = PitStopCounter::default();
+
+        // Drive through the pitlane without stopping.
+        counter.update(&mut entry, true, false);
+        counter.update(&mut entry, false, false);
+        assert_eq!(*entry.pit_stops_completed, 0);
+
+        // Stop twice during the same visit.
+        counter.update(&mut entry, true, true);
+        counter.update(&mut entry, true, false);
+        counter.update(&mut entry, true, true);
+        counter.update(&mut entry, false, false);
+        assert_eq!(*entry.pit_stops_completed, 1);
+
+        counter.update(&mut entry, true, true);
+        assert_eq!(*entry.pit_stops_completed, 2);
+    }
+}
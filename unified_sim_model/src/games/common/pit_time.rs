@@ -0,0 +1,90 @@
+//! This processor measures how long an entry spends in the pitlane.
+//!
+//! The time starts when an entry is first seen in the pitlane and stops when
+//! it is first seen out of the pitlane again.
+
+use std::{collections::HashMap, time::Instant};
+
+use crate::{
+    model::{EntryId, Session},
+    Time,
+};
+
+/// Measures the time entries spend in the pitlane.
+#[derive(Default)]
+pub struct PitTimer {
+    /// The instant an entry entered the pitlane.
+    pit_entry: HashMap<EntryId, Instant>,
+}
+
+impl PitTimer {
+    /// Update the pit times of all entries in the session.
+    pub fn update(&mut self, session: &mut Session, now: Instant) {
+        for entry in session.entries.values_mut() {
+            if *entry.in_pits {
+                let pit_entry = self.pit_entry.entry(entry.id).or_insert(now);
+                entry
+                    .pit_time
+                    .set(Time::from(now.duration_since(*pit_entry)));
+            } else if let Some(pit_entry) = self.pit_entry.remove(&entry.id) {
+                entry
+                    .last_pit_duration
+                    .set(Time::from(now.duration_since(pit_entry)));
+                entry.pit_time = Default::default();
+            }
+        }
+    }
+
+    /// Forget all running pit stops.
+    /// Must be called when the session changes.
+    pub fn clear(&mut self) {
+        self.pit_entry.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        model::{Entry, EntryId, Session, Value},
+        Time,
+    };
+
+    use super::PitTimer;
+
+    #[test]
+    fn measure_pit_stop() {
+        let mut session = Session::default();
+        let entry = Entry {
+            id: EntryId(1),
+            in_pits: Value::new(false),
+            ..Default::default()
+        };
+        session.entries.insert(entry.id, entry);
+        let mut timer = PitTimer::default();
+        let start = Instant::now();
+        let set_in_pits = |session: &mut Session, in_pits: bool| {
+            session
+                .entries
+                .get_mut(&EntryId(1))
+                .unwrap()
+                .in_pits
+                .set(in_pits);
+        };
+
+        timer.update(&mut session, start);
+        set_in_pits(&mut session, true);
+        timer.update(&mut session, start + Duration::from_secs(10));
+        timer.update(&mut session, start + Duration::from_secs(25));
+        let entry = &session.entries[&EntryId(1)];
+        assert_eq!(*entry.pit_time, Time::from(15_000));
+        assert!(!entry.last_pit_duration.is_avaliable());
+
+        set_in_pits(&mut session, false);
+        timer.update(&mut session, start + Duration::from_secs(42));
+        let entry = &session.entries[&EntryId(1)];
+        assert_eq!(*entry.last_pit_duration, Time::from(32_000));
+        assert!(!entry.pit_time.is_avaliable());
+    }
+}
@@ -0,0 +1,157 @@
+//! This processor projects the position of every entry at the next timing line
+//! using the sector pace of the entries.
+//!
+//! The timing line is the next sector line the leader crosses. Every entry is moved forward
+//! for the time the leader needs to reach that line, using the sector times of its last lap
+//! with split times. The entries are then ordered by their projected distance.
+//!
+//! The track position of the sector lines is not known so all sectors are assumed to be
+//! the same length. Entries without sector times are assumed to stand still.
+
+use crate::model::{Entry, EntryId, Session};
+
+/// Calculate the projected positions of all entries in a session.
+pub fn calc_projected_positions(session: &mut Session) {
+    let Some(leader) = session
+        .entries
+        .values()
+        .max_by(|a, b| a.distance_driven.total_cmp(&b.distance_driven))
+    else {
+        return;
+    };
+    let Some(horizon) = sector_pace(leader).map(|pace| pace.time_to_next_line(leader)) else {
+        for entry in session.entries.values_mut() {
+            entry.projected_position = Default::default();
+        }
+        return;
+    };
+
+    let mut projected: Vec<(EntryId, f32, i32)> = session
+        .entries
+        .values()
+        .map(|entry| {
+            let distance = match sector_pace(entry) {
+                Some(pace) => pace.project(*entry.distance_driven, horizon),
+                None => *entry.distance_driven,
+            };
+            (entry.id, distance, *entry.position)
+        })
+        .collect();
+    projected.sort_by(|(_, distance_a, position_a), (_, distance_b, position_b)| {
+        distance_b
+            .total_cmp(distance_a)
+            .then(position_a.cmp(position_b))
+    });
+
+    for (index, (entry_id, _, _)) in projected.iter().enumerate() {
+        if let Some(entry) = session.entries.get_mut(entry_id) {
+            entry.projected_position.estimate(index as i32 + 1);
+        }
+    }
+}
+
+/// The sector times of an entry in ms.
+struct SectorPace(Vec<f64>);
+
+/// Returns the sector times of the last lap that has split times.
+fn sector_pace(entry: &Entry) -> Option<SectorPace> {
+    entry
+        .laps
+        .iter()
+        .rev()
+        .find(|lap| !lap.splits.is_empty() && lap.splits.iter().all(|split| split.ms > 0.0))
+        .map(|lap| SectorPace(lap.splits.iter().map(|split| split.ms).collect()))
+}
+
+impl SectorPace {
+    fn sector_length(&self) -> f64 {
+        1.0 / self.0.len() as f64
+    }
+
+    /// Returns the sector index and the fraction of the sector that is completed.
+    fn sector_at(&self, distance: f64) -> (usize, f64) {
+        let lap_pos = distance.rem_euclid(1.0) / self.sector_length();
+        let sector = (lap_pos.floor() as usize).min(self.0.len() - 1);
+        (sector, lap_pos - sector as f64)
+    }
+
+    /// Time in ms for an entry to reach its next sector line.
+    fn time_to_next_line(&self, entry: &Entry) -> f64 {
+        let (sector, completed) = self.sector_at(*entry.distance_driven as f64);
+        self.0[sector] * (1.0 - completed)
+    }
+
+    /// Project the distance after driving for a time in ms.
+    fn project(&self, distance: f32, mut time: f64) -> f32 {
+        let mut distance = distance as f64;
+        let (mut sector, completed) = self.sector_at(distance);
+        let mut remaining = 1.0 - completed;
+        loop {
+            let time_to_line = self.0[sector] * remaining;
+            if time <= time_to_line {
+                return (distance + time / self.0[sector] * self.sector_length()) as f32;
+            }
+            distance += remaining * self.sector_length();
+            time -= time_to_line;
+            sector = (sector + 1) % self.0.len();
+            remaining = 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        model::{Entry, EntryId, Lap, Session, Value},
+        Time,
+    };
+
+    use super::calc_projected_positions;
+
+    fn entry(id: i32, position: i32, distance: f32, splits: [i32; 3]) -> Entry {
+        Entry {
+            id: EntryId(id),
+            position: Value::new(position),
+            distance_driven: Value::new(distance),
+            laps: vec![Lap {
+                splits: Value::new(splits.iter().map(|ms| Time::from(*ms)).collect()),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn faster_car_is_projected_ahead() {
+        let mut session = Session::default();
+        // The leader is half way through the first sector with a sector time of 30s.
+        // It needs 15s to reach the next sector line.
+        // The second car is slightly behind but twice as fast in the first sector.
+        let leader = entry(1, 1, 1.0 + 0.5 / 3.0, [30_000; 3]);
+        let chaser = entry(2, 2, 1.0 + 0.4 / 3.0, [15_000, 30_000, 30_000]);
+        session.entries.insert(leader.id, leader);
+        session.entries.insert(chaser.id, chaser);
+
+        calc_projected_positions(&mut session);
+
+        let leader = &session.entries[&EntryId(1)];
+        let chaser = &session.entries[&EntryId(2)];
+        assert_eq!(*leader.projected_position, 2);
+        assert_eq!(*chaser.projected_position, 1);
+        assert!(chaser.projected_position.is_estimate());
+    }
+
+    #[test]
+    fn slower_car_stays_behind() {
+        let mut session = Session::default();
+        let leader = entry(1, 1, 1.5 / 3.0, [30_000; 3]);
+        let chaser = entry(2, 2, 1.4 / 3.0, [40_000; 3]);
+        session.entries.insert(leader.id, leader);
+        session.entries.insert(chaser.id, chaser);
+
+        calc_projected_positions(&mut session);
+
+        assert_eq!(*session.entries[&EntryId(1)].projected_position, 1);
+        assert_eq!(*session.entries[&EntryId(2)].projected_position, 2);
+    }
+}
@@ -0,0 +1,624 @@
+//! A demo adapter that simulates a full race without a running game.
+//!
+//! The demo is meant for screenshots, development of user interfaces and to evaluate a user
+//! interface without a sim. It generates a grid of entries in different car classes and
+//! simulates a lapped race from the grid to the checkered flag:
+//! - The entries wait on the grid while the session is preparing.
+//! - The field drives a formation lap at a reduced pace. The race goes green when the leader
+//!   crosses the line.
+//! - Every entry laps with its own pace and a small variation from lap to lap. Each entry
+//!   makes one pit stop during the pit window.
+//! - The session ends when the leader completes the last lap and finishes when every
+//!   entry has taken the checkered flag.
+
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{
+    games::{
+        common::{best_splits, entry_finished, grid_position, phase_start, pit_time::PitTimer},
+        dummy::{focus_entry, random_car, random_driver},
+    },
+    model::{
+        Day, DriverId, Entry, EntryId, Event, GameKind, Lap, LapCompleted, Model, PitWindow,
+        Session, SessionPhase, SessionType, Value,
+    },
+    types::Time,
+    AdapterCommand, AdapterError, Distance, GameAdapter, Temperature, UpdateEvent, MAX_POLL_RATE,
+};
+
+/// The time the entries wait on the grid before the formation lap.
+const PREPARING_TIME: Duration = Duration::from_secs(10);
+/// The formation lap is driven at this fraction of the race pace.
+const FORMATION_PACE: f64 = 0.5;
+/// The number of sectors of the demo track.
+const SECTORS: usize = 3;
+/// The length of the demo track in meters.
+const TRACK_LENGTH: f32 = 4_800.0;
+/// The time of day when the simulation starts in ms.
+const START_TIME_OF_DAY: f64 = 14.0 * 3_600_000.0;
+
+/// The configuration of the demo race.
+#[derive(Debug, Clone)]
+pub struct DemoConfig {
+    /// The number of entries in the race.
+    pub entry_count: usize,
+    /// The number of laps of the race.
+    pub race_laps: i32,
+    /// The lap time of the fastest class.
+    pub lap_time: Time,
+    /// How much faster than real time the race is simulated.
+    /// A value of 2.0 simulates the race twice as fast.
+    pub time_scale: f64,
+}
+
+impl Default for DemoConfig {
+    fn default() -> Self {
+        Self {
+            entry_count: 24,
+            race_laps: 20,
+            lap_time: Time::from(105_000),
+            time_scale: 1.0,
+        }
+    }
+}
+
+/// An adapter that simulates a race with generated data.
+pub struct DemoAdapter {
+    config: DemoConfig,
+}
+
+impl DemoAdapter {
+    /// Create a new demo adapter.
+    pub fn new(config: DemoConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl GameAdapter for DemoAdapter {
+    fn run(
+        &mut self,
+        model: Arc<RwLock<Model>>,
+        command_rx: mpsc::Receiver<AdapterCommand>,
+        update_event: UpdateEvent,
+    ) -> Result<(), AdapterError> {
+        let mut race = DemoRace::new(self.config.clone());
+        if let Ok(mut model) = model.write() {
+            race.setup(&mut model);
+        }
+
+        let mut last_step = Instant::now();
+        let mut poll_rate = Duration::from_millis(16);
+        loop {
+            match command_rx.try_recv() {
+                Ok(AdapterCommand::Close) => break,
+                Ok(AdapterCommand::SetPollRate(rate)) => poll_rate = rate.min(MAX_POLL_RATE),
+                Ok(AdapterCommand::FocusOnCar(entry_id)) => {
+                    if let Ok(mut model) = model.write() {
+                        focus_entry(&mut model, entry_id);
+                    }
+                }
+                Ok(_) => (),
+                // The command channel stays open while the adapter runs.
+                Err(_) => (),
+            };
+
+            let now = Instant::now();
+            if let Ok(mut model) = model.write() {
+                race.step(
+                    &mut model,
+                    now.duration_since(last_step)
+                        .mul_f64(self.config.time_scale.max(0.0)),
+                );
+                model.last_update = Some(now);
+            }
+            last_step = now;
+            update_event.frame(0);
+            update_event.trigger();
+            std::thread::sleep(poll_rate);
+        }
+
+        Ok(())
+    }
+
+    fn game_kind(&self) -> GameKind {
+        GameKind::Demo
+    }
+}
+
+/// The simulation state of a single car.
+struct DemoCar {
+    /// The distance driven in laps from the start line. Negative while on the grid and
+    /// until the car crosses the line for the first time after the green flag.
+    distance: f64,
+    /// The lap time this car can drive in ms.
+    pace: f64,
+    /// The lap time of the current lap in ms. Varies slightly from lap to lap.
+    lap_pace: f64,
+    /// The simulation time when the current lap started.
+    lap_start: Duration,
+    /// The simulation time when the current sector started.
+    sector_start: Duration,
+    /// The sector times of the current lap.
+    splits: Vec<Time>,
+    /// The simulation time when the current stint started.
+    stint_start: Duration,
+    /// The lap after which the car makes its pit stop.
+    pit_lap: i32,
+    /// The time the car still has to stand in the pits.
+    pit_remaining: Option<Duration>,
+    /// The number of pit stops the car has completed.
+    pit_stops: i32,
+    /// The position in which the car has finished the race.
+    finish: Option<usize>,
+}
+
+/// The state of the simulated race.
+struct DemoRace {
+    config: DemoConfig,
+    /// The time since the start of the simulation.
+    clock: Duration,
+    /// The instant the simulation started. Used to give the pit timer a simulated instant.
+    start: Instant,
+    /// The simulation time when the current phase started.
+    phase_since: Duration,
+    cars: HashMap<EntryId, DemoCar>,
+    finished: usize,
+    pit_timer: PitTimer,
+}
+
+impl DemoRace {
+    fn new(config: DemoConfig) -> Self {
+        Self {
+            config,
+            clock: Duration::ZERO,
+            start: Instant::now(),
+            phase_since: Duration::ZERO,
+            cars: HashMap::new(),
+            finished: 0,
+            pit_timer: PitTimer::default(),
+        }
+    }
+
+    /// Add the race session and the grid to the model.
+    fn setup(&mut self, model: &mut Model) {
+        let mut rand = rand::thread_rng();
+        let race_laps = self.config.race_laps.max(1);
+        let pit_window = PitWindow {
+            open_lap: race_laps / 3,
+            close_lap: race_laps * 2 / 3,
+            mandatory_stops: 1,
+        };
+
+        model.event_name.set("Demo event".to_owned());
+        model.focused_entry = None;
+
+        let mut session = Session {
+            session_type: Value::new(SessionType::Race),
+            name: Value::new("RACE".to_owned()),
+            phase: Value::new(SessionPhase::Preparing),
+            laps: Value::new(race_laps),
+            laps_remaining: Value::new(race_laps),
+            time_of_day: Value::new(Time::from(START_TIME_OF_DAY)),
+            day: Value::new(Day::Sunday),
+            ambient_temp: Value::new(Temperature::from_celcius(22.0)),
+            track_temp: Value::new(Temperature::from_celcius(31.0)),
+            best_lap: Value::new(None),
+            track_name: Value::new("Demo Ring".to_owned()),
+            track_length: Value::new(Distance::from_meter(TRACK_LENGTH)),
+            pit_window: Value::new(Some(pit_window.clone())),
+            ..Default::default()
+        };
+
+        let mut car_numbers: Vec<i32> = (1..=(self.config.entry_count as i32).max(99)).collect();
+        car_numbers.shuffle(&mut rand);
+
+        let mut grid = Vec::new();
+        for (index, car_number) in car_numbers
+            .into_iter()
+            .take(self.config.entry_count)
+            .enumerate()
+        {
+            let id = EntryId(index as i32);
+            let driver_id = DriverId(0);
+            let car = random_car();
+            let class_pace = match car.category().name.as_ref() {
+                "GT3" => 1.0,
+                "GT4" => 1.07,
+                _ => 1.03,
+            };
+            let pace = self.config.lap_time.ms * class_pace * rand.gen_range(1.0..1.02);
+            let entry = Entry {
+                id,
+                drivers: HashMap::from([(driver_id, random_driver(driver_id))]),
+                current_driver: driver_id,
+                team_name: Value::new(format!("Team nr.{}", car_number)),
+                car: Value::new(car),
+                car_number: Value::new(car_number),
+                mandatory_stops_remaining: Value::new(Some(pit_window.mandatory_stops)),
+                connected: Value::new(true),
+                ..Default::default()
+            };
+            session.entries.insert(id, entry);
+            self.cars.insert(
+                id,
+                DemoCar {
+                    distance: 0.0,
+                    pace,
+                    lap_pace: pace,
+                    lap_start: Duration::ZERO,
+                    sector_start: Duration::ZERO,
+                    splits: Vec::new(),
+                    stint_start: Duration::ZERO,
+                    pit_lap: rand.gen_range(pit_window.open_lap..=pit_window.close_lap),
+                    pit_remaining: None,
+                    pit_stops: 0,
+                    finish: None,
+                },
+            );
+            // The grid is roughly ordered by pace.
+            grid.push((id, pace * rand.gen_range(1.0..1.01)));
+        }
+
+        grid.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+        for (index, (id, _)) in grid.iter().enumerate() {
+            if let Some(car) = self.cars.get_mut(id) {
+                car.distance = -0.003 * (index + 1) as f64;
+            }
+        }
+
+        let id = model.add_session(session);
+        model.current_session = Some(id);
+        model.push_event(Event::SessionChanged(id));
+        if let Some(session) = model.current_session_mut() {
+            self.update_entries(session);
+        }
+    }
+
+    /// Advance the simulation by a time step.
+    fn step(&mut self, model: &mut Model, dt: Duration) {
+        self.clock += dt;
+        let Some(session) = model.current_session_mut() else {
+            return;
+        };
+
+        let mut events = Vec::new();
+        match *session.phase {
+            SessionPhase::Preparing if self.clock - self.phase_since >= PREPARING_TIME => {
+                self.change_phase(session, SessionPhase::Formation, &mut events);
+            }
+            SessionPhase::Formation => {
+                for car in self.cars.values_mut() {
+                    car.distance += dt.as_secs_f64() * 1000.0 * FORMATION_PACE / car.pace;
+                }
+                let leader_distance = self
+                    .cars
+                    .values()
+                    .map(|car| car.distance)
+                    .max_by(|a, b| a.total_cmp(b))
+                    .unwrap_or_default();
+                if leader_distance >= 1.0 {
+                    // The field is back on the line and the race goes green.
+                    for car in self.cars.values_mut() {
+                        car.distance -= 1.0;
+                        car.lap_start = self.clock;
+                        car.sector_start = self.clock;
+                        car.stint_start = self.clock;
+                    }
+                    self.change_phase(session, SessionPhase::Active, &mut events);
+                }
+            }
+            SessionPhase::Active | SessionPhase::Ending => {
+                let mut completed = Vec::new();
+                for (entry_id, car) in self.cars.iter_mut() {
+                    if let Some(lap) = drive(car, self.clock, dt) {
+                        completed.push((*entry_id, lap));
+                    }
+                }
+                completed.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+                for (entry_id, _) in completed {
+                    self.lap_completed(session, entry_id, &mut events);
+                }
+            }
+            _ => (),
+        }
+
+        self.update_entries(session);
+        self.pit_timer.update(session, self.start + self.clock);
+        publish(model, events);
+
+        // Entries that took the checkered flag stop on the line.
+        let Some(session) = model.current_session_mut() else {
+            return;
+        };
+        for entry in session.entries.values() {
+            let Some(car) = self.cars.get_mut(&entry.id) else {
+                continue;
+            };
+            if *entry.is_finished && car.finish.is_none() {
+                car.finish = Some(self.finished);
+                car.distance = car.distance.floor();
+                self.finished += 1;
+            }
+        }
+        let mut events = Vec::new();
+        if *session.phase == SessionPhase::Ending && self.finished == self.cars.len() {
+            self.change_phase(session, SessionPhase::Finished, &mut events);
+        }
+        self.update_entries(session);
+        publish(model, events);
+    }
+
+    fn change_phase(
+        &mut self,
+        session: &mut Session,
+        phase: SessionPhase,
+        events: &mut Vec<Event>,
+    ) {
+        session.phase.set(phase);
+        self.phase_since = self.clock;
+        events.push(Event::SessionPhaseChanged(session.id, phase));
+    }
+
+    /// Record a completed lap of an entry and handle the pit stop and the end of the race.
+    fn lap_completed(&mut self, session: &mut Session, entry_id: EntryId, events: &mut Vec<Event>) {
+        let Some(car) = self.cars.get_mut(&entry_id) else {
+            return;
+        };
+        let lap_count = car.distance.floor() as i32;
+        let mut rand = rand::thread_rng();
+        let lap_time = Time::from(self.clock - car.lap_start);
+        let lap = Lap {
+            time: Value::new(lap_time),
+            splits: Value::new(std::mem::take(&mut car.splits)),
+            driver_id: Some(DriverId(0)),
+            entry_id: Some(entry_id),
+            driving_metrics: None,
+            invalid: Value::new(rand.gen_bool(0.05)),
+            caution: Value::new(false),
+        };
+        car.lap_start = self.clock;
+        car.lap_pace = car.pace * rand.gen_range(0.995..1.01);
+        if lap_count == car.pit_lap {
+            car.pit_remaining = Some(Duration::from_millis(rand.gen_range(25_000..35_000)));
+        }
+
+        // The race ends when the first entry completes the last lap.
+        if *session.phase == SessionPhase::Active && lap_count >= *session.laps {
+            self.change_phase(session, SessionPhase::Ending, events);
+        }
+
+        let Some(entry) = session.entries.get_mut(&entry_id) else {
+            return;
+        };
+        entry.lap_count.set(lap_count);
+        entry.laps.push(lap.clone());
+
+        let valid = !*lap.invalid;
+        let is_driver_best = valid
+            && entry
+                .drivers
+                .get(&entry.current_driver)
+                .and_then(|driver| driver.best_lap.as_ref().as_ref())
+                .is_none_or(|best_lap| lap.time < best_lap.time);
+        if is_driver_best {
+            if let Some(driver) = entry.drivers.get_mut(&entry.current_driver) {
+                driver.best_lap.set(Some(lap.clone()));
+            }
+        }
+        let is_entry_best = valid
+            && entry
+                .best_lap
+                .as_ref()
+                .as_ref()
+                .is_none_or(|best_lap| lap.time < best_lap.time);
+        if is_entry_best {
+            entry.best_lap.set(Some(lap.clone()));
+        }
+        let is_session_best = valid
+            && session
+                .best_lap
+                .as_ref()
+                .as_ref()
+                .is_none_or(|best_lap| lap.time < best_lap.time);
+        if is_session_best {
+            session.best_lap.set(Some(lap.clone()));
+        }
+
+        events.push(Event::LapCompleted(LapCompleted {
+            lap,
+            is_session_best,
+            is_entry_best,
+            is_driver_best,
+        }));
+    }
+
+    /// Update the values of the entries that follow from the simulation state.
+    fn update_entries(&self, session: &mut Session) {
+        let mut order: Vec<(EntryId, f64, usize)> = self
+            .cars
+            .iter()
+            .map(|(id, car)| (*id, car.distance, car.finish.unwrap_or(usize::MAX)))
+            .collect();
+        order.sort_by(|(_, distance_a, finish_a), (_, distance_b, finish_b)| {
+            distance_b
+                .total_cmp(distance_a)
+                .then(finish_a.cmp(finish_b))
+        });
+        let leader_distance = order.first().map_or(0.0, |(_, distance, _)| *distance);
+        let leader_laps = leader_distance.floor().max(0.0) as i32;
+
+        let mut distance_ahead = leader_distance;
+        for (index, (entry_id, distance, _)) in order.iter().enumerate() {
+            let (Some(entry), Some(car)) =
+                (session.entries.get_mut(entry_id), self.cars.get(entry_id))
+            else {
+                continue;
+            };
+            entry.position.set(index as i32 + 1);
+            entry.spline_pos.set(distance.rem_euclid(1.0) as f32);
+            entry.distance_driven.set(*distance as f32);
+            entry
+                .time_behind_leader
+                .set(Time::from((leader_distance - distance) * car.pace));
+            entry
+                .time_behind_position_ahead
+                .set(Time::from((distance_ahead - distance) * car.pace));
+            distance_ahead = *distance;
+
+            let driving = matches!(
+                *session.phase,
+                SessionPhase::Formation | SessionPhase::Active | SessionPhase::Ending
+            ) && car.finish.is_none();
+            entry.in_pits.set(car.pit_remaining.is_some());
+            entry.pit_stops_completed.set(car.pit_stops);
+            if let Some(pit_window) = session.pit_window.as_ref() {
+                entry
+                    .mandatory_stops_remaining
+                    .set(Some((pit_window.mandatory_stops - car.pit_stops).max(0)));
+            }
+            if driving && car.pit_remaining.is_none() {
+                let pace = if *session.phase == SessionPhase::Formation {
+                    car.pace / FORMATION_PACE
+                } else {
+                    car.lap_pace
+                };
+                // Vary the speed along the lap for corners and straights.
+                let average = TRACK_LENGTH as f64 / (pace / 1000.0);
+                let corners = 1.0 + 0.3 * (distance * std::f64::consts::TAU * 5.0).sin();
+                let speed = (average * corners) as f32;
+                entry.speed.set(speed);
+                entry.gear.set((speed / 15.0).clamp(1.0, 6.0) as i32);
+            } else {
+                entry.speed.set(0.0);
+                entry.gear.set(0);
+            }
+
+            if matches!(*session.phase, SessionPhase::Active | SessionPhase::Ending) {
+                entry
+                    .stint_time
+                    .set(Time::from(self.clock - car.stint_start));
+                entry
+                    .current_lap
+                    .time
+                    .set(Time::from(self.clock - car.lap_start));
+                entry.current_lap.entry_id = Some(*entry_id);
+                entry.current_lap.driver_id = Some(entry.current_driver);
+            }
+        }
+
+        session
+            .laps_remaining
+            .set((*session.laps - leader_laps).max(0));
+        session
+            .time_of_day
+            .set(Time::from(START_TIME_OF_DAY + Time::from(self.clock).ms));
+    }
+}
+
+/// Drive a car for a time step.
+///
+/// Returns the distance driven past the line if the car completed a lap. Cars that crossed
+/// the line earlier in the time step have driven further past it.
+/// The first crossing of the line after the green flag starts the first lap.
+fn drive(car: &mut DemoCar, clock: Duration, dt: Duration) -> Option<f64> {
+    if car.finish.is_some() {
+        return None;
+    }
+    if let Some(remaining) = car.pit_remaining {
+        car.pit_remaining = remaining.checked_sub(dt);
+        if car.pit_remaining.is_none() {
+            car.pit_stops += 1;
+            car.stint_start = clock;
+        }
+        return None;
+    }
+
+    let before = car.distance;
+    car.distance += dt.as_secs_f64() * 1000.0 / car.lap_pace;
+    if before < 0.0 {
+        if car.distance >= 0.0 {
+            car.lap_start = clock;
+            car.sector_start = clock;
+        }
+        return None;
+    }
+
+    let sector_before = (before * SECTORS as f64).floor();
+    let sector_after = (car.distance * SECTORS as f64).floor();
+    if sector_after > sector_before {
+        car.splits.push(Time::from(clock - car.sector_start));
+        car.sector_start = clock;
+    }
+    (car.distance.floor() > before.floor()).then(|| car.distance.fract())
+}
+
+/// Publish the events to the model and run the common processors.
+fn publish(model: &mut Model, events: Vec<Event>) {
+    for event in events {
+        entry_finished::calc_entry_finished(&event, model);
+        best_splits::calc_best_splits(&event, model);
+        phase_start::calc_phase_start(&event, model);
+        grid_position::calc_grid_position(&event, model);
+        model.push_event(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        model::{Model, SessionPhase},
+        types::Time,
+    };
+
+    use super::{DemoConfig, DemoRace};
+
+    #[test]
+    fn race_runs_to_the_finish() {
+        let mut model = Model::default();
+        let mut race = DemoRace::new(DemoConfig {
+            entry_count: 6,
+            race_laps: 4,
+            lap_time: Time::from(20_000),
+            time_scale: 1.0,
+        });
+        race.setup(&mut model);
+        assert_eq!(
+            *model.current_session().unwrap().phase,
+            SessionPhase::Preparing
+        );
+
+        for _ in 0..2_000 {
+            race.step(&mut model, Duration::from_millis(200));
+        }
+
+        let session = model.current_session().unwrap();
+        assert_eq!(*session.phase, SessionPhase::Finished);
+        // The best lap is a full lap at race pace.
+        let best_lap = session.best_lap.as_ref().as_ref().unwrap();
+        assert!(
+            (19_000.0..23_000.0).contains(&best_lap.time.ms),
+            "{:?}",
+            best_lap.time
+        );
+        for entry in session.entries.values() {
+            assert!(*entry.is_finished);
+            assert!(entry.grid_position.is_avaliable());
+            assert_eq!(*entry.pit_stops_completed, 1);
+            assert_eq!(entry.laps.len() as i32, *entry.lap_count);
+        }
+        let winner = session
+            .entries
+            .values()
+            .find(|entry| *entry.position == 1)
+            .unwrap();
+        assert_eq!(*winner.lap_count, 4);
+    }
+}
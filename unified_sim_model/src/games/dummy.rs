@@ -1,24 +1,21 @@
 use std::{
     collections::HashMap,
     ops::ControlFlow,
-    sync::{
-        mpsc::{self, TryRecvError},
-        Arc, RwLock,
-    },
-    time::Duration,
+    sync::{mpsc, Arc, RwLock},
+    time::{Duration, Instant},
 };
 
 use rand::Rng;
-use tracing::error;
 
 use crate::{
     model::{
-        Camera, Car, CarCategory, Day, Driver, DriverId, Entry, EntryGameData, EntryId, Event, Lap,
-        Model, Nationality, Session, SessionGameData, SessionId, SessionPhase, SessionType, Value,
+        Camera, Car, CarCategory, Day, Driver, DriverId, Entry, EntryGameData, EntryId, Event,
+        GameKind, Lap, Limit, Model, Nationality, PitWindow, SafetyCarState, Session,
+        SessionGameData, SessionId, SessionPhase, SessionRules, SessionType, Stint, Value,
     },
     types::Time,
     AdapterCommand, AdapterError, Distance, GameAdapter, GameAdapterCommand, Temperature,
-    UpdateEvent,
+    UpdateEvent, MAX_POLL_RATE,
 };
 
 /// Commands for the dummy adapter.
@@ -40,30 +37,34 @@ impl GameAdapter for DummyAdapter {
     ) -> Result<(), AdapterError> {
         setup_model(&model);
 
+        let mut poll_rate = Duration::from_millis(16);
         loop {
             match command_rx.try_recv() {
                 Ok(AdapterCommand::Close) => break,
+                Ok(AdapterCommand::SetPollRate(rate)) => poll_rate = rate.min(MAX_POLL_RATE),
                 Ok(action) => {
                     if self.handle_command(&model, action).is_break() {
                         break;
                     }
                 }
-                Err(TryRecvError::Empty) => (),
-                Err(TryRecvError::Disconnected) => {
-                    // This should only happen if all adapters have been dropped.
-                    // In which case it is impossible to interact with this adapter any more.
-                    // To avoid leaking memory we quit.
-                    error!("All adapter handle have been dropped it is impossible to communicate with this game adapter.");
-                    break;
-                }
+                // The command channel stays open while the adapter runs.
+                Err(_) => (),
             };
 
+            if let Ok(mut model) = model.write() {
+                model.last_update = Some(Instant::now());
+            }
+            update_event.frame(0);
             update_event.trigger();
-            std::thread::sleep(Duration::from_millis(16));
+            std::thread::sleep(poll_rate);
         }
 
         Ok(())
     }
+
+    fn game_kind(&self) -> GameKind {
+        GameKind::Dummy
+    }
 }
 impl DummyAdapter {
     fn handle_command(
@@ -74,15 +75,7 @@ impl DummyAdapter {
         let mut model = model.write().expect("Should be able to lock for writing");
         match command {
             AdapterCommand::Close => return ControlFlow::Break(()),
-            AdapterCommand::FocusOnCar(entry_id) => {
-                model.focused_entry = Some(entry_id);
-                if let Some(session) = model.current_session_mut() {
-                    session
-                        .entries
-                        .values_mut()
-                        .for_each(|entry| entry.focused = entry.id == entry_id);
-                }
-            }
+            AdapterCommand::FocusOnCar(entry_id) => focus_entry(&mut model, entry_id),
             AdapterCommand::Game(GameAdapterCommand::Dummy(command)) => match command {
                 DummyCommands::SetEntryAmount(amount) => {
                     if let Some(session) = model.current_session_mut() {
@@ -114,6 +107,22 @@ impl DummyAdapter {
     }
 }
 
+/// Focus an entry as if the focus had changed in the game.
+pub(crate) fn focus_entry(model: &mut Model, entry_id: EntryId) {
+    if model.focused_entry != Some(entry_id) {
+        model.push_event(Event::FocusChanged {
+            entry: Some(entry_id),
+        });
+    }
+    model.focused_entry = Some(entry_id);
+    if let Some(session) = model.current_session_mut() {
+        session
+            .entries
+            .values_mut()
+            .for_each(|entry| entry.focused = entry.id == entry_id);
+    }
+}
+
 fn setup_model(model: &Arc<RwLock<Model>>) {
     let mut model = model.write().expect("Should be able to lock for writing");
 
@@ -131,6 +140,7 @@ fn setup_model(model: &Arc<RwLock<Model>>) {
         id: SessionId(0),
         entries: HashMap::new(),
         session_type: Value::new(SessionType::Race),
+        name: Value::new("RACE".to_owned()),
         session_time: Value::new(Time::from(1_200_123)),
         time_remaining: Value::new(Time::from(754_123)),
         laps: Value::new(20),
@@ -149,14 +159,30 @@ fn setup_model(model: &Arc<RwLock<Model>>) {
             ]),
             driver_id: Some(DriverId::default()),
             entry_id: Some(EntryId::default()),
+            driving_metrics: None,
             invalid: Value::new(false),
+            caution: Value::new(false),
         })),
         track_name: Value::new("Dummy track".to_string()),
         track_length: Value::new(Distance::from_meter(1234.0)),
+        sector_count: Value::new(3),
+        best_splits: vec![Time::from(12_345), Time::from(67_891), Time::from(111_213)],
+        pit_window: Value::new(Some(PitWindow {
+            open_lap: 5,
+            close_lap: 15,
+            mandatory_stops: 1,
+        })),
+        phase_start: None,
+        safety_car: Value::new(SafetyCarState::default()),
+        rules: Value::new(SessionRules {
+            incident_limit: Value::new(Limit::Limited(17)),
+            fast_repairs: Value::new(Limit::Limited(1)),
+            joker_laps: 0,
+        }),
         game_data: SessionGameData::None,
     });
     model.current_session = Some(id);
-    model.events.push(Event::SessionChanged(SessionId(0)));
+    model.push_event(Event::SessionChanged(SessionId(0)));
 
     for i in 0..10 {
         let session = model.current_session_mut().unwrap();
@@ -181,10 +207,12 @@ fn random_entry(number: i32) -> Entry {
         team_name: Value::new(format!("Team nr.{}", number)),
         car: Value::new(random_car()),
         car_number: Value::new(rand.gen::<i32>().abs() % 100),
+        livery_color: Value::default(),
         nationality: Value::new(Nationality::NONE),
         world_pos: Value::new([0.0, 0.0, 0.0]),
         orientation: Value::new([0.0, 0.0, 0.0]),
         position: Value::new(number + 1),
+        projected_position: Value::new_estimate(number + 1),
         spline_pos: Value::new(0.1234),
         lap_count: Value::new(0),
         laps: Vec::new(),
@@ -193,17 +221,38 @@ fn random_entry(number: i32) -> Entry {
             splits: Value::new(Vec::new()),
             driver_id: Some(DriverId(0)),
             entry_id: Some(EntryId(number)),
+            driving_metrics: None,
             invalid: Value::new(number % 2 == 0),
+            caution: Value::default(),
         }),
         best_lap: Value::new(None),
         performance_delta: Value::new(Time::from(-1_234)),
         time_behind_leader: Value::new(Time::from(12_345)),
         time_behind_position_ahead: Value::new(Time::from(567)),
         in_pits: Value::new(number % 3 == 0),
+        pit_stops_completed: Value::new(number % 3),
+        grid_position: Value::new(number % 20 + 1),
+        pit_time: if number % 3 == 0 {
+            Value::new(Time::from(12_345))
+        } else {
+            Value::default()
+        },
+        last_pit_duration: Value::new(Time::from(31_234)),
+        mandatory_stops_remaining: Value::new(Some(1 - (number % 3).min(1))),
         gear: Value::new(4),
+        inputs: Value::default(),
+        overtake: None,
+        brakes: None,
+        pit_service: Value::default(),
         speed: Value::new(128.0),
         connected: Value::new(true),
         stint_time: Value::new(Time::from(56_789)),
+        stints: (0..number % 3)
+            .map(|stint| Stint {
+                driver_id: DriverId(0),
+                time: Time::from(40 * 60_000 + stint * 5 * 60_000),
+            })
+            .collect(),
         distance_driven: Value::new(number as f32 * 0.345),
         focused: number == 0,
         game_data: EntryGameData::None,
@@ -211,7 +260,7 @@ fn random_entry(number: i32) -> Entry {
     }
 }
 
-fn random_driver(id: DriverId) -> Driver {
+pub(crate) fn random_driver(id: DriverId) -> Driver {
     const FIRST_NAMES: [&str; 20] = [
         "Liam",
         "Noah",
@@ -271,7 +320,7 @@ fn random_driver(id: DriverId) -> Driver {
         best_lap: Value::new(None),
     }
 }
-fn random_car() -> Car {
+pub(crate) fn random_car() -> Car {
     const GT3: CarCategory = CarCategory::new("GT3");
     const GT4: CarCategory = CarCategory::new("GT4");
     const ST: CarCategory = CarCategory::new("ST");
@@ -331,3 +380,30 @@ fn random_car() -> Car {
     let mut rand = rand::thread_rng();
     CARS[rand.gen::<usize>() % CARS.len()].clone()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, RwLock};
+
+    use crate::model::Model;
+
+    use super::setup_model;
+
+    #[test]
+    fn model_round_trip() {
+        let model = Arc::new(RwLock::new(Model::default()));
+        setup_model(&model);
+        let model = model.read().unwrap();
+
+        let data = serde_yaml::to_string(&*model).unwrap();
+        let mut read: Model = serde_yaml::from_str(&data).unwrap();
+
+        // The cameras are a set which is written in no particular order.
+        assert_eq!(read.available_cameras, model.available_cameras);
+        read.available_cameras = model.available_cameras.clone();
+        assert_eq!(
+            serde_yaml::to_value(&read).unwrap(),
+            serde_yaml::to_value(&*model).unwrap()
+        );
+    }
+}
@@ -1,28 +1,34 @@
 use std::{
     collections::VecDeque,
     fmt::Display,
-    sync::{
-        mpsc::{Receiver, TryRecvError},
-        Arc, RwLock,
-    },
-    time::Instant,
+    sync::{mpsc::Receiver, Arc, RwLock},
+    time::{Duration, Instant},
 };
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tracing::{error, warn};
+use tracing::warn;
 
-use crate::{model::Model, AdapterCommand, GameAdapter, UpdateEvent};
+use crate::{
+    model::{ConnectionState, EntryId, GameKind, Model},
+    AdapterCommand, GameAdapter, UpdateEvent, MAX_POLL_RATE,
+};
 
 use self::{
     irsdk::{defines::Messages, Data, Irsdk},
     processors::{
-        base::BaseProcessor, camera::CameraProcessor, lap::LapProcessor, speed::SpeedProcessor,
-        IRacingProcessor, IRacingProcessorContext,
+        base::BaseProcessor, camera::CameraProcessor, ffb::FfbProcessor, inputs::InputsProcessor,
+        lap::LapProcessor, pit_service::PitServiceProcessor, pit_time::PitTimeProcessor,
+        safety_car::SafetyCarProcessor, speed::SpeedProcessor, IRacingProcessor,
+        IRacingProcessorContext,
     },
 };
 
-use super::common::entry_finished;
+use super::common::{
+    best_splits, entry_finished, grid_position, max_entries::EntryLimit, phase_start,
+};
 
+pub mod ibt;
 pub mod irsdk;
 mod processors;
 
@@ -51,7 +57,73 @@ impl From<IRacingError> for crate::AdapterError {
     }
 }
 
-pub struct IRacingAdapter;
+/// The adapter for iRacing.
+pub struct IRacingAdapter {
+    /// If the driver inputs should be read into the model.
+    inputs: bool,
+    /// If the force feedback settings of the player should be read into the model.
+    ffb: bool,
+    /// The time a change in the flags or the session phase must be stable before it is accepted.
+    flag_hold: Duration,
+    /// The maximum number of entries in the model.
+    max_entries: Option<usize>,
+}
+
+impl Default for IRacingAdapter {
+    fn default() -> Self {
+        Self {
+            inputs: false,
+            ffb: false,
+            flag_hold: Duration::from_millis(500),
+            max_entries: None,
+        }
+    }
+}
+
+impl IRacingAdapter {
+    /// Enable reading the driver inputs and the brake state of the player car into the model.
+    ///
+    /// Inputs change every frame and are disabled by default.
+    pub fn with_inputs(mut self, inputs: bool) -> Self {
+        self.inputs = inputs;
+        self
+    }
+
+    /// Enable reading the force feedback settings of the player into the model.
+    ///
+    /// Disabled by default.
+    pub fn with_ffb(mut self, ffb: bool) -> Self {
+        self.ffb = ffb;
+        self
+    }
+
+    /// Set the time a change in the flags or the session phase must be stable before it
+    /// is accepted.
+    ///
+    /// Flags can flicker for a single update. Changes that are shorter than this
+    /// time are ignored and do not generate events. This applies to the safety car and to
+    /// the session phase, which is partly derived from the flags.
+    /// A duration of zero accepts every change immediately. Default is 500ms.
+    pub fn with_flag_hold(mut self, flag_hold: Duration) -> Self {
+        self.flag_hold = flag_hold;
+        self
+    }
+
+    /// Limit the number of entries in the current session of the model.
+    ///
+    /// Only the entries with the best `max_entries` positions, the player car and the
+    /// focused car are shown in the model. Cars that are excluded do not appear in the
+    /// leaderboard until they move into the top positions. Default is no limit.
+    ///
+    /// This only limits what the model exposes. The adapter still reads and processes every
+    /// car each update, so the limit makes the model smaller for its readers but does not
+    /// reduce the work of the adapter.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+}
+
 impl GameAdapter for IRacingAdapter {
     fn run(
         &mut self,
@@ -62,18 +134,71 @@ impl GameAdapter for IRacingAdapter {
         let sdk = Irsdk::new().map_err(|_| IRacingError::GameNotRunning)?;
 
         if let Ok(mut model) = model.write() {
-            model.connected = true;
+            model.set_connection_state(ConnectionState::Connected);
             model.event_name.set("iRacing".to_owned());
         }
-        let mut connection = IRacingConnection::new(model.clone(), command_rx, update_event, sdk);
+        let mut connection =
+            IRacingConnection::new(model.clone(), command_rx, update_event, sdk, self.flag_hold);
+        if self.inputs {
+            connection.inputs_processor = Some(InputsProcessor::default());
+        }
+        if self.ffb {
+            connection.ffb_processor = Some(FfbProcessor);
+        }
+        connection.entry_limit = self.max_entries.map(EntryLimit::new);
         let result = connection.run_loop();
 
         if let Ok(mut model) = model.write() {
-            model.connected = false;
+            model.set_connection_state(ConnectionState::Disconnected);
         }
 
         result
     }
+
+    fn game_kind(&self) -> GameKind {
+        GameKind::IRacing
+    }
+}
+
+/// The number of cars iRacing supports in a session.
+///
+/// The `CarIdx` arrays of the live data have an item for each car. Car indices
+/// outside of this range are ignored.
+pub const MAX_CARS: usize = 64;
+
+/// Returns the car index as an index into the `CarIdx` arrays of the live data.
+/// `None` if the index is negative or not smaller than [`MAX_CARS`].
+pub(crate) fn car_index(car_idx: i32) -> Option<usize> {
+    usize::try_from(car_idx)
+        .ok()
+        .filter(|index| *index < MAX_CARS)
+}
+
+/// Returns the value of a `CarIdx` array for a car.
+/// `None` if the car index is out of range or the array has no value for the car.
+pub(crate) fn car_value<T>(values: &[T], car_idx: i32) -> Option<&T> {
+    values.get(car_index(car_idx)?)
+}
+
+/// Returns the signed shortest distance on track from `from` to `to` as a fraction of a lap.
+///
+/// The lap distance in iRacing wraps from 1.0 to 0.0 at the start finish line. The returned
+/// distance takes the wrap into account and is always between -0.5 and 0.5. It is positive
+/// when `to` is ahead of `from`.
+pub(crate) fn spline_delta(from: f32, to: f32) -> f32 {
+    let delta = (to - from).rem_euclid(1.0);
+    if delta >= 0.5 {
+        delta - 1.0
+    } else {
+        delta
+    }
+}
+
+/// Check if the game is running.
+///
+/// Opens the shared memory of the sdk and closes it again right away.
+pub(crate) fn is_game_available() -> bool {
+    Irsdk::new().is_ok()
 }
 
 struct IRacingConnection {
@@ -82,10 +207,22 @@ struct IRacingConnection {
     update_event: UpdateEvent,
     sdk: Irsdk,
     static_data_update_count: Option<i32>,
+    /// The tick count of the last frame.
+    last_tick_count: Option<i32>,
     lap_processor: LapProcessor,
     base_processor: BaseProcessor,
     camera_processor: CameraProcessor,
     speed_processor: SpeedProcessor,
+    safety_car_processor: SafetyCarProcessor,
+    pit_time_processor: PitTimeProcessor,
+    pit_service_processor: PitServiceProcessor,
+    inputs_processor: Option<InputsProcessor>,
+    ffb_processor: Option<FfbProcessor>,
+    entry_limit: Option<EntryLimit>,
+    /// The minimum time between two reads of the game.
+    poll_rate: Option<Duration>,
+    /// The time of the last read of the game.
+    last_poll: Option<Instant>,
 }
 
 impl IRacingConnection {
@@ -94,6 +231,7 @@ impl IRacingConnection {
         command_rx: Receiver<AdapterCommand>,
         update_event: UpdateEvent,
         sdk: Irsdk,
+        flag_hold: Duration,
     ) -> Self {
         Self {
             model,
@@ -101,10 +239,19 @@ impl IRacingConnection {
             update_event,
             sdk,
             static_data_update_count: None,
+            last_tick_count: None,
             lap_processor: LapProcessor::new(),
-            base_processor: BaseProcessor {},
+            base_processor: BaseProcessor::new(flag_hold),
             camera_processor: CameraProcessor::new(),
             speed_processor: SpeedProcessor::new(),
+            safety_car_processor: SafetyCarProcessor::new(flag_hold),
+            pit_time_processor: PitTimeProcessor::default(),
+            pit_service_processor: PitServiceProcessor,
+            inputs_processor: None,
+            ffb_processor: None,
+            entry_limit: None,
+            poll_rate: None,
+            last_poll: None,
         }
     }
 
@@ -128,10 +275,30 @@ impl IRacingConnection {
                 }
             }
 
+            if let (Some(poll_rate), Some(last_poll)) = (self.poll_rate, self.last_poll) {
+                if last_poll.elapsed() < poll_rate {
+                    last_update = now;
+                    continue;
+                }
+            }
+            self.last_poll = Some(Instant::now());
+
             let data = self.sdk.poll().map_err(|e| match e {
                 irsdk::PollError::NotConnected => IRacingError::Disconnected,
             })?;
 
+            // The game advances the tick count once per frame. A jump of more than one
+            // means frames were produced that were not read.
+            // Frames skipped because of the poll rate are not dropped.
+            let dropped = match self.poll_rate {
+                Some(_) => 0,
+                None => self
+                    .last_tick_count
+                    .map_or(0, |last| (data.tick_count - last - 1).max(0)),
+            };
+            self.last_tick_count = Some(data.tick_count);
+            self.update_event.frame(dropped as u64);
+
             self.update_model(&data)?;
             self.update_event.trigger();
 
@@ -144,7 +311,7 @@ impl IRacingConnection {
         Ok(())
     }
 
-    fn handle_commands(&self) -> IRacingResult<bool> {
+    fn handle_commands(&mut self) -> IRacingResult<bool> {
         let should_close = match self.command_rx.try_recv() {
             Ok(command) => match command {
                 AdapterCommand::Close => true,
@@ -153,11 +320,13 @@ impl IRacingConnection {
                     let entry = model
                         .current_session()
                         .and_then(|session| session.entries.get(entry_id));
+                    // Keep the active camera while switching the focus.
+                    let camera = self.camera_processor.get_camera_def(&model.active_camera);
                     if let Some(entry) = entry {
                         self.sdk.send_message(Messages::CamSwitchNum {
                             driver_num: *entry.car_number as u16,
-                            camera_group: 0,
-                            camera: 0,
+                            camera_group: camera.map_or(0, |camera| camera.group_num as u16),
+                            camera: camera.map_or(0, |camera| camera.camera_num as u16),
                         });
                     }
                     false
@@ -177,6 +346,13 @@ impl IRacingConnection {
                                 camera_group: camera.group_num as u16,
                                 camera: camera.camera_num as u16,
                             });
+                        } else {
+                            // Without a focused car only the camera is switched.
+                            self.sdk.send_message(Messages::CamSwitchPos {
+                                position: 0,
+                                camera_group: camera.group_num as u16,
+                                camera: camera.camera_num as u16,
+                            });
                         }
                     } else {
                         warn!(
@@ -186,16 +362,14 @@ impl IRacingConnection {
                     }
                     false
                 }
-                AdapterCommand::Game(_) => false,
+                AdapterCommand::SetPollRate(rate) => {
+                    self.poll_rate = Some(rate.min(MAX_POLL_RATE));
+                    false
+                }
+                _ => false,
             },
-            Err(TryRecvError::Empty) => false,
-            Err(TryRecvError::Disconnected) => {
-                // This should only happen if all adapters have been dropped.
-                // In which case it is impossible to interact with this adapter any more.
-                // To avoid leaking memory we quit.
-                error!("All adapter handle have been dropped it is impossible to communicate with this game adapter.");
-                true
-            }
+            // The command channel stays open while the adapter runs.
+            Err(_) => false,
         };
 
         Ok(should_close)
@@ -210,6 +384,9 @@ impl IRacingConnection {
             events: VecDeque::new(),
             data,
         };
+        if let Some(ref mut entry_limit) = self.entry_limit {
+            entry_limit.restore(context.model);
+        }
 
         if self
             .static_data_update_count
@@ -219,6 +396,9 @@ impl IRacingConnection {
             self.lap_processor.static_data(&mut context)?;
             self.camera_processor.static_data(&mut context)?;
             self.speed_processor.static_data(&mut context)?;
+            self.safety_car_processor.static_data(&mut context)?;
+            self.pit_time_processor.static_data(&mut context)?;
+            self.pit_service_processor.static_data(&mut context)?;
 
             self.static_data_update_count = Some(data.static_data.update_count);
         }
@@ -227,6 +407,15 @@ impl IRacingConnection {
         self.lap_processor.live_data(&mut context)?;
         self.camera_processor.live_data(&mut context)?;
         self.speed_processor.live_data(&mut context)?;
+        self.safety_car_processor.live_data(&mut context)?;
+        self.pit_time_processor.live_data(&mut context)?;
+        self.pit_service_processor.live_data(&mut context)?;
+        if let Some(ref mut inputs_processor) = self.inputs_processor {
+            inputs_processor.live_data(&mut context)?;
+        }
+        if let Some(ref mut ffb_processor) = self.ffb_processor {
+            ffb_processor.live_data(&mut context)?;
+        }
 
         while !context.events.is_empty() {
             let event = context.events.pop_front().unwrap();
@@ -234,16 +423,30 @@ impl IRacingConnection {
             self.lap_processor.event(&mut context, &event)?;
             self.camera_processor.event(&mut context, &event)?;
             self.speed_processor.event(&mut context, &event)?;
+            self.safety_car_processor.event(&mut context, &event)?;
+            self.pit_time_processor.event(&mut context, &event)?;
+            self.pit_service_processor.event(&mut context, &event)?;
+            if let Some(ref mut inputs_processor) = self.inputs_processor {
+                inputs_processor.event(&mut context, &event)?;
+            }
 
             entry_finished::calc_entry_finished(&event, context.model);
-            context.model.events.push(event);
+            best_splits::calc_best_splits(&event, context.model);
+            phase_start::calc_phase_start(&event, context.model);
+            grid_position::calc_grid_position(&event, context.model);
+            context.model.push_event(event);
+        }
+        if let Some(ref mut entry_limit) = self.entry_limit {
+            let player = data.live_data.player_car_idx.map(EntryId);
+            entry_limit.apply(context.model, player);
         }
+        context.model.last_update = Some(Instant::now());
 
         Ok(())
     }
 }
 
-#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IRacingCamera {
     group_num: i32,
     group_name: String,
@@ -256,3 +459,47 @@ impl Display for IRacingCamera {
         write!(f, "iRacing {}", self.group_name)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::assert_close;
+
+    use super::{car_index, car_value, spline_delta, MAX_CARS};
+
+    #[test]
+    fn car_index_in_range() {
+        assert_eq!(car_index(0), Some(0));
+        assert_eq!(car_index(MAX_CARS as i32 - 1), Some(MAX_CARS - 1));
+        assert_eq!(car_index(MAX_CARS as i32), None);
+        assert_eq!(car_index(-1), None);
+    }
+
+    #[test]
+    fn car_value_of_oversized_and_undersized_arrays() {
+        let oversized: Vec<i32> = (0..MAX_CARS as i32 + 8).collect();
+        assert_eq!(car_value(&oversized, 3), Some(&3));
+        assert_eq!(car_value(&oversized, MAX_CARS as i32 + 2), None);
+        assert_eq!(car_value(&oversized, -2), None);
+
+        let undersized = [10, 11, 12];
+        assert_eq!(car_value(&undersized, 2), Some(&12));
+        assert_eq!(car_value(&undersized, 3), None);
+    }
+
+    #[test]
+    fn delta_on_the_same_lap() {
+        assert_close(spline_delta(0.2, 0.3), 0.1);
+        assert_close(spline_delta(0.3, 0.2), -0.1);
+        assert_close(spline_delta(0.5, 0.5), 0.0);
+    }
+
+    #[test]
+    fn delta_across_the_line() {
+        // Driving forward across the line.
+        assert_close(spline_delta(0.99, 0.01), 0.02);
+        // The car ahead has already crossed the line.
+        assert_close(spline_delta(0.01, 0.99), -0.02);
+        assert_close(spline_delta(0.9, 0.1), 0.2);
+        assert_close(spline_delta(0.1, 0.9), -0.2);
+    }
+}
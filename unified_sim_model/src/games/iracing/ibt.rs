@@ -0,0 +1,344 @@
+//! Reader for the telemetry files that iRacing writes to disk.
+//!
+//! A `.ibt` file starts with the same header as the shared memory followed by a
+//! disk sub header. The variable headers and the session string are stored at the
+//! offsets given in the header. The records of the variable buffer follow one after
+//! another starting at the offset of the first variable buffer.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use thiserror::Error;
+use tracing::warn;
+
+use super::irsdk::{
+    decode_session_str,
+    defines::{DiskSubHeader, Header, VarHeader},
+    live_data::LiveData,
+    static_data::StaticData,
+    VarHandler,
+};
+
+#[derive(Debug, Error)]
+pub enum IbtError {
+    #[error("Io error: {0}")]
+    IoError(io::Error),
+    #[error("The file is not a valid telemetry file: {0}")]
+    InvalidFile(String),
+    #[error("Cannot parse the session string: {0}")]
+    InvalidSessionData(serde_yaml::Error),
+}
+
+impl From<io::Error> for IbtError {
+    fn from(value: io::Error) -> Self {
+        IbtError::IoError(value)
+    }
+}
+
+/// Reads the recorded ticks of a `.ibt` telemetry file.
+pub struct IbtReader<R> {
+    reader: R,
+    header: Header,
+    disk_header: DiskSubHeader,
+    static_data: StaticData,
+    var_handlers: Vec<VarHandler>,
+}
+
+impl IbtReader<BufReader<File>> {
+    /// Open a telemetry file.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IbtError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> IbtReader<R> {
+    /// Create a reader for a telemetry file.
+    /// Reads the headers and the session string of the file.
+    pub fn new(mut reader: R) -> Result<Self, IbtError> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut buffer = vec![0u8; Header::SIZE + DiskSubHeader::SIZE];
+        reader.read_exact(&mut buffer)?;
+        let header = Header::from_bytes(&buffer)
+            .ok_or_else(|| IbtError::InvalidFile("Header is incomplete".into()))?;
+        let disk_header = DiskSubHeader::from_bytes(&buffer[Header::SIZE..])
+            .ok_or_else(|| IbtError::InvalidFile("Disk header is incomplete".into()))?;
+
+        // Every length in the header is checked against the size of the file before
+        // anything is allocated for it.
+        let file_len = reader.seek(SeekFrom::End(0))?;
+        let var_header_len = offset(header.var_header_element_count)? * VarHeader::SIZE as u64;
+        check_range(
+            "Variable headers",
+            offset(header.var_header_offset)?,
+            var_header_len,
+            file_len,
+        )?;
+        check_range(
+            "Session string",
+            offset(header.session_data_offset)?,
+            offset(header.session_data_len)?,
+            file_len,
+        )?;
+        let var_buffer_len = offset(header.var_buffer_len)?;
+        if var_buffer_len > file_len {
+            return Err(IbtError::InvalidFile(format!(
+                "Variable buffer length {var_buffer_len} is larger than the file"
+            )));
+        }
+        // Without a variable buffer no frame would ever move the reader forward.
+        if var_buffer_len == 0 {
+            return Err(IbtError::InvalidFile("Variable buffer is empty".into()));
+        }
+
+        let mut buffer = vec![0u8; var_header_len as usize];
+        reader.seek(SeekFrom::Start(offset(header.var_header_offset)?))?;
+        reader.read_exact(&mut buffer)?;
+        let var_handlers = buffer
+            .chunks_exact(VarHeader::SIZE)
+            .map(|bytes| {
+                let var_header = VarHeader::from_bytes(bytes)
+                    .ok_or_else(|| IbtError::InvalidFile("Unknown variable type".into()))?;
+                check_var_header(&var_header, var_buffer_len)?;
+                Ok::<_, IbtError>(VarHandler::new(var_header))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut buffer = vec![0u8; header.session_data_len as usize];
+        reader.seek(SeekFrom::Start(offset(header.session_data_offset)?))?;
+        reader.read_exact(&mut buffer)?;
+        let mut static_data = decode_session_str(&buffer).map_err(IbtError::InvalidSessionData)?;
+        static_data.update_count = header.session_data_update;
+
+        Ok(Self {
+            reader,
+            header,
+            disk_header,
+            static_data,
+            var_handlers,
+        })
+    }
+
+    /// The session data of the recording.
+    pub fn static_data(&self) -> &StaticData {
+        &self.static_data
+    }
+
+    /// The disk header of the recording.
+    pub fn disk_header(&self) -> &DiskSubHeader {
+        &self.disk_header
+    }
+
+    /// Ticks per second of the recording.
+    pub fn tick_rate(&self) -> i32 {
+        self.header.tick_rate
+    }
+
+    /// Iterate over the recorded ticks from the start of the file.
+    ///
+    /// The iterator ends at the end of the file or after the number of records given in
+    /// the disk header. A recording that was not closed properly has a record count of zero.
+    /// In that case all records until the end of the file are read.
+    pub fn frames(&mut self) -> impl Iterator<Item = LiveData> + '_ {
+        let start = offset(self.header.var_buffers[0].offset).ok();
+        let mut remaining = match self.disk_header.session_record_count {
+            count if count > 0 => Some(count as usize),
+            _ => None,
+        };
+        let mut buffer = vec![0u8; self.header.var_buffer_len as usize];
+        let mut valid = match start {
+            Some(start) => self.reader.seek(SeekFrom::Start(start)).is_ok(),
+            None => false,
+        };
+        std::iter::from_fn(move || {
+            if !valid || remaining == Some(0) {
+                return None;
+            }
+            if let Err(e) = self.reader.read_exact(&mut buffer) {
+                if e.kind() != io::ErrorKind::UnexpectedEof {
+                    warn!("Error reading telemetry record: {e}");
+                }
+                valid = false;
+                return None;
+            }
+            remaining = remaining.map(|r| r - 1);
+            let mut live_data = LiveData::default();
+            for handler in self.var_handlers.iter() {
+                handler.process(&buffer, &mut live_data);
+            }
+            Some(live_data)
+        })
+    }
+}
+
+/// Convert an offset from the header into a file position.
+fn offset(value: i32) -> Result<u64, IbtError> {
+    u64::try_from(value).map_err(|_| IbtError::InvalidFile(format!("Invalid offset {value}")))
+}
+
+/// Check that a section of the file lies within the file.
+fn check_range(name: &str, start: u64, len: u64, file_len: u64) -> Result<(), IbtError> {
+    if start + len > file_len {
+        return Err(IbtError::InvalidFile(format!(
+            "{name} at {start} with length {len} does not fit into a file of length {file_len}"
+        )));
+    }
+    Ok(())
+}
+
+/// Check that a variable lies within the variable buffer.
+fn check_var_header(var_header: &VarHeader, var_buffer_len: u64) -> Result<(), IbtError> {
+    let name = String::from_utf8_lossy(&var_header.name)
+        .trim_matches(char::from(0))
+        .to_owned();
+    if var_header.count < 1 {
+        return Err(IbtError::InvalidFile(format!(
+            "Variable {name} has an invalid count of {}",
+            var_header.count
+        )));
+    }
+    let end =
+        offset(var_header.offset)? + var_header.var_type.size() as u64 * var_header.count as u64;
+    if end > var_buffer_len {
+        return Err(IbtError::InvalidFile(format!(
+            "Variable {name} exceeds the variable buffer length {var_buffer_len}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::games::iracing::irsdk::defines::{DiskSubHeader, Header, VarHeader};
+
+    use super::{IbtError, IbtReader};
+
+    fn var_header(var_type: i32, offset: i32, name: &str) -> Vec<u8> {
+        let mut bytes = vec![0u8; VarHeader::SIZE];
+        bytes[0..4].copy_from_slice(&var_type.to_le_bytes());
+        bytes[4..8].copy_from_slice(&offset.to_le_bytes());
+        bytes[8..12].copy_from_slice(&1i32.to_le_bytes());
+        bytes[16..16 + name.len()].copy_from_slice(name.as_bytes());
+        bytes
+    }
+
+    const SESSION_STR: &str = "WeekendInfo:\n TrackName: spa\nSessionInfo:\n Sessions: []\n\
+        CameraInfo:\n Groups: []\nRadioInfo:\n Radios: []\nDriverInfo:\n Drivers: []\n\
+        SplitTimeInfo:\n Sectors: []\nCarSetup: {}\n";
+
+    /// Build a file with the `Speed` and `Lap` variables and one record for each speed.
+    fn ibt_file(speeds: &[f32], record_count: i32) -> Vec<u8> {
+        let var_header_offset = Header::SIZE + DiskSubHeader::SIZE;
+        let session_offset = var_header_offset + 2 * VarHeader::SIZE;
+        let buffer_offset = session_offset + SESSION_STR.len();
+        let ints = [
+            2,                        // version
+            1,                        // status
+            60,                       // tick rate
+            0,                        // session data update
+            SESSION_STR.len() as i32, // session data len
+            session_offset as i32,    // session data offset
+            2,                        // var header count
+            var_header_offset as i32, // var header offset
+            1,                        // var buffer count
+            8,                        // var buffer len
+            0,                        // pad
+            0,                        // pad
+            0,                        // tick count
+            buffer_offset as i32,     // var buffer offset
+        ];
+        let mut file: Vec<u8> = ints.iter().flat_map(|i: &i32| i.to_le_bytes()).collect();
+        file.resize(Header::SIZE + 28, 0);
+        file.extend(record_count.to_le_bytes());
+        file.extend(var_header(4, 0, "Speed"));
+        file.extend(var_header(2, 4, "Lap"));
+        file.extend(SESSION_STR.as_bytes());
+        for (lap, speed) in speeds.iter().enumerate() {
+            file.extend(speed.to_le_bytes());
+            file.extend((lap as i32).to_le_bytes());
+        }
+        file
+    }
+
+    #[test]
+    fn read_frames() {
+        let reader = IbtReader::new(Cursor::new(ibt_file(&[10.0, 20.0, 30.0], 3)));
+        assert!(reader.is_ok());
+        let mut reader = reader.unwrap();
+        assert_eq!(reader.tick_rate(), 60);
+        assert_eq!(
+            reader.static_data().weekend_info.track_name,
+            Some("spa".to_string())
+        );
+        let frames = reader.frames().collect::<Vec<_>>();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[1].speed, Some(20.0));
+        assert_eq!(frames[2].lap, Some(2));
+
+        // Reading again starts from the beginning.
+        assert_eq!(reader.frames().count(), 3);
+    }
+
+    #[test]
+    fn read_frames_without_record_count() {
+        let reader = IbtReader::new(Cursor::new(ibt_file(&[10.0, 20.0], 0)));
+        assert!(reader.is_ok());
+        assert_eq!(reader.unwrap().frames().count(), 2);
+    }
+
+    fn set_i32(file: &mut [u8], position: usize, value: i32) {
+        file[position..position + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn is_invalid(file: Vec<u8>) -> bool {
+        matches!(
+            IbtReader::new(Cursor::new(file)),
+            Err(IbtError::InvalidFile(_))
+        )
+    }
+
+    #[test]
+    fn reject_lengths_larger_than_the_file() {
+        let file = ibt_file(&[10.0], 1);
+        let mut session_data_len = file.clone();
+        set_i32(&mut session_data_len, 16, i32::MAX);
+        assert!(is_invalid(session_data_len));
+
+        let mut var_header_count = file.clone();
+        set_i32(&mut var_header_count, 24, i32::MAX);
+        assert!(is_invalid(var_header_count));
+
+        let mut var_buffer_len = file.clone();
+        set_i32(&mut var_buffer_len, 36, i32::MAX);
+        assert!(is_invalid(var_buffer_len));
+
+        let mut empty_var_buffer = file.clone();
+        set_i32(&mut empty_var_buffer, 36, 0);
+        assert!(is_invalid(empty_var_buffer));
+
+        let mut negative_var_buffer = file;
+        set_i32(&mut negative_var_buffer, 36, -1);
+        assert!(is_invalid(negative_var_buffer));
+    }
+
+    #[test]
+    fn reject_variables_outside_of_the_buffer() {
+        let var_header_offset = Header::SIZE + DiskSubHeader::SIZE;
+        let file = ibt_file(&[10.0], 1);
+        let mut count = file.clone();
+        set_i32(&mut count, var_header_offset + 8, 0);
+        assert!(is_invalid(count));
+
+        let mut negative_offset = file.clone();
+        set_i32(&mut negative_offset, var_header_offset + 4, -4);
+        assert!(is_invalid(negative_offset));
+
+        let mut past_the_end = file;
+        set_i32(&mut past_the_end, var_header_offset + 4, 6);
+        assert!(is_invalid(past_the_end));
+    }
+}
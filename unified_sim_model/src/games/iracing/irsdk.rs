@@ -1,4 +1,4 @@
-use core::slice;
+use core::{ptr, slice};
 use std::{ffi::c_void, fmt::Debug};
 use thiserror::Error;
 use tracing::{debug, info, warn};
@@ -33,6 +33,9 @@ pub mod defines;
 pub mod live_data;
 pub mod static_data;
 
+/// How often the variable buffer is read before giving up if it changes while reading.
+const MAX_VAR_BUFFER_READS: usize = 3;
+
 /// Special handle used to SendMessage
 const BROADCAST_HANDLE: HWND = HWND(0xffff);
 
@@ -40,6 +43,8 @@ const BROADCAST_HANDLE: HWND = HWND(0xffff);
 pub struct Data {
     pub static_data: StaticData,
     pub live_data: LiveData,
+    /// The tick count of the variable buffer the live data was read from.
+    pub tick_count: i32,
 }
 
 #[derive(Debug, Error)]
@@ -66,14 +71,16 @@ pub struct Irsdk {
     message_id: u32,
     /// pointer into the memory mapped file.
     view: *const u8,
-    /// Tick count of the last update.
-    _last_tick_count: i32,
+    /// Tick count of the last consistent variable buffer.
+    last_tick_count: i32,
+    /// The last consistent variable buffer.
+    last_var_buffer: Vec<u8>,
     /// List of var handlers to write the value into the data model.
     var_handlers: Vec<VarHandler>,
     /// If this helper is currently connected to the game or not.
     connected: bool,
-    /// Last update number of the session data.
-    session_data_last_udpate: i32,
+    /// The updates of the session string that were decoded.
+    session_str_updates: SessionStrUpdates,
     /// The current session data.
     session_data: StaticData,
 }
@@ -127,10 +134,11 @@ impl Irsdk {
         Ok(Self {
             file_mapping: handle,
             view,
-            _last_tick_count: 0,
+            last_tick_count: 0,
+            last_var_buffer: Vec::new(),
             var_handlers: Vec::new(),
             connected: false,
-            session_data_last_udpate: 0,
+            session_str_updates: SessionStrUpdates::default(),
             session_data: StaticData::default(),
             data_valid_event,
             message_id,
@@ -184,8 +192,11 @@ impl Irsdk {
         self.connected = is_connected;
 
         // Read session data
-        let session_str_changed = header.session_data_update != self.session_data_last_udpate;
-        if session_str_changed || is_new_connection {
+        if self
+            .session_str_updates
+            .is_pending(header.session_data_update)
+            || is_new_connection
+        {
             self.parse_session_str(header);
         }
 
@@ -208,31 +219,19 @@ impl Irsdk {
 
     fn parse_session_str(&mut self, header: &Header) {
         debug!("Process session data");
-        self.session_data_last_udpate = header.session_data_update;
         let session_str_buffer = unsafe {
             slice::from_raw_parts(
                 self.view.offset(header.session_data_offset as isize),
                 header.session_data_len as usize,
             )
-            .to_vec()
         };
-        let session_str = CP1252
-            .decode(&session_str_buffer)
-            .trim_matches('\0')
-            .to_string();
-        let session_data = serde_yaml::from_str::<StaticData>(&session_str);
-        if let Err(ref e) = session_data {
-            warn!(
-                "Error parsing session data yaml. Using default instead: {}",
-                e
-            );
-        }
-        // TODO: This should probably create an error instead of using the default.
-        self.session_data = session_data.unwrap_or_default();
-        self.session_data.update_count = header.session_data_update;
-        for entry in self.session_data.get_unmapped().iter() {
-            warn!("Unmapped field in session string: {:?}", entry);
-        }
+        let parsed = update_session_data(
+            &mut self.session_data,
+            session_str_buffer,
+            header.session_data_update,
+        );
+        self.session_str_updates
+            .record(header.session_data_update, parsed);
     }
 
     fn parse_var_headers(&mut self, header: &Header) {
@@ -244,51 +243,29 @@ impl Irsdk {
             )
             .to_vec()
         };
-        self.var_handlers.clear();
-        for header in var_headers {
-            let name = String::from_utf8_lossy(&header.name)
-                .trim_matches(char::from(0))
-                .to_owned();
-
-            let processor = map_processors(&name);
-            if let Processor::None = processor {
-                let desc = String::from_utf8_lossy(&header.description)
-                    .trim_matches(char::from(0))
-                    .to_owned();
-                let unit = String::from_utf8_lossy(&header.unit)
-                    .trim_matches(char::from(0))
-                    .to_owned();
-                info!("Unmapped variable \"{name}\".\ndesc: {desc}\n:unit: {unit}\n type: {:?}, count: {}" , header.var_type, header.count);
-            }
-
-            self.var_handlers.push(VarHandler { header, processor });
-        }
+        self.var_handlers = var_headers.into_iter().map(VarHandler::new).collect();
     }
 
-    fn parse_var_buffer(&self, header: &Header, data: &mut Data) {
-        let var_buffer = {
-            let newest_buffer = header
-                .var_buffers
-                .iter()
-                .max_by(|b1, b2| b1.tick_count.cmp(&b2.tick_count))
-                .expect("The iterate should not be empty");
-            let current_tick_count = newest_buffer.tick_count;
-            let var_buffer = unsafe {
-                slice::from_raw_parts(
-                    self.view.offset(newest_buffer.offset as isize),
-                    header.var_buffer_len as usize,
-                )
-                .to_vec()
-            };
-            if newest_buffer.tick_count != current_tick_count {
-                warn!("The variable buffer has changed while reading");
+    fn parse_var_buffer(&mut self, header: &Header, data: &mut Data) {
+        // The game may write to the buffer while it is copied. In that case the copy is torn
+        // and the buffer is read again.
+        let snapshot =
+            (0..MAX_VAR_BUFFER_READS).find_map(|_| unsafe { read_var_buffer(self.view, header) });
+        match snapshot {
+            Some((tick_count, var_buffer)) => {
+                self.last_tick_count = tick_count;
+                self.last_var_buffer = var_buffer;
             }
-            var_buffer
-        };
+            None => warn!("The variable buffer has changed while reading. Using the last consistent buffer instead"),
+        }
+        data.tick_count = self.last_tick_count;
+        if self.last_var_buffer.is_empty() {
+            return;
+        }
 
         // Write variables into data struct.
         for handler in self.var_handlers.iter() {
-            handler.process(&var_buffer, &mut data.live_data);
+            handler.process(&self.last_var_buffer, &mut data.live_data);
         }
     }
     pub fn is_connected(&self) -> bool {
@@ -296,6 +273,87 @@ impl Irsdk {
     }
 }
 
+/// Copy the newest variable buffer.
+///
+/// Returns the tick count and the copy of the buffer or `None` if the game has
+/// written to the buffer while it was copied.
+///
+/// # Safety
+/// `view` must point to the memory that starts with `header` and contains the variable
+/// buffers described by it.
+unsafe fn read_var_buffer(view: *const u8, header: &Header) -> Option<(i32, Vec<u8>)> {
+    // The tick counts are changed by the game and must be read from memory every time.
+    let tick_count = |index: usize| ptr::read_volatile(&header.var_buffers[index].tick_count);
+    let index = (0..header.var_buffers.len())
+        .max_by_key(|index| tick_count(*index))
+        .expect("The iterate should not be empty");
+    let current_tick_count = tick_count(index);
+    let var_buffer = slice::from_raw_parts(
+        view.offset(header.var_buffers[index].offset as isize),
+        header.var_buffer_len as usize,
+    )
+    .to_vec();
+    (tick_count(index) == current_tick_count).then_some((current_tick_count, var_buffer))
+}
+
+/// Decode the session string into the static data.
+pub(crate) fn decode_session_str(buffer: &[u8]) -> Result<StaticData, serde_yaml::Error> {
+    let session_str = CP1252.decode(buffer).trim_matches('\0').to_string();
+    let session_data = serde_yaml::from_str::<StaticData>(&session_str)?;
+    for entry in session_data.get_unmapped().iter() {
+        warn!("Unmapped field in session string: {:?}", entry);
+    }
+    Ok(session_data)
+}
+
+/// Replace the session data with the decoded session string.
+///
+/// The session string may be incomplete if it was read while the game was writing it.
+/// In that case the previous session data is kept and `false` is returned.
+fn update_session_data(session_data: &mut StaticData, buffer: &[u8], update_count: i32) -> bool {
+    match decode_session_str(buffer) {
+        Ok(new_data) => {
+            *session_data = new_data;
+            session_data.update_count = update_count;
+            true
+        }
+        Err(e) => {
+            warn!("Error parsing session data yaml. Keeping the previous data: {e}");
+            false
+        }
+    }
+}
+
+/// Tracks which updates of the session string were decoded.
+///
+/// An update that cannot be decoded is not decoded again. Otherwise a session string that
+/// never parses would be decoded on every poll. iRacing writes a new update whenever the
+/// session string changes, which is decoded again.
+#[derive(Debug, Default)]
+struct SessionStrUpdates {
+    /// The last update that was decoded.
+    parsed: i32,
+    /// The last update that could not be decoded.
+    failed: Option<i32>,
+}
+
+impl SessionStrUpdates {
+    /// Returns if an update of the session string still has to be decoded.
+    fn is_pending(&self, update: i32) -> bool {
+        update != self.parsed && Some(update) != self.failed
+    }
+
+    /// Record the result of decoding an update.
+    fn record(&mut self, update: i32, parsed: bool) {
+        if parsed {
+            self.parsed = update;
+            self.failed = None;
+        } else {
+            self.failed = Some(update);
+        }
+    }
+}
+
 /// A handler to read a variable from the var buffer and write its data into the model.
 #[derive(Debug)]
 pub struct VarHandler {
@@ -304,7 +362,27 @@ pub struct VarHandler {
 }
 
 impl VarHandler {
-    fn process(&self, buffer: &[u8], data: &mut LiveData) {
+    /// Create a handler for a variable. Variables without a processor are ignored.
+    pub(crate) fn new(header: VarHeader) -> Self {
+        let name = String::from_utf8_lossy(&header.name)
+            .trim_matches(char::from(0))
+            .to_owned();
+
+        let processor = map_processors(&name);
+        if let Processor::None = processor {
+            let desc = String::from_utf8_lossy(&header.description)
+                .trim_matches(char::from(0))
+                .to_owned();
+            let unit = String::from_utf8_lossy(&header.unit)
+                .trim_matches(char::from(0))
+                .to_owned();
+            info!("Unmapped variable \"{name}\".\ndesc: {desc}\n:unit: {unit}\n type: {:?}, count: {}" , header.var_type, header.count);
+        }
+        Self { header, processor }
+    }
+
+    /// Read the variable from the buffer and write it into the live data.
+    pub(crate) fn process(&self, buffer: &[u8], data: &mut LiveData) {
         let offset = self.header.offset as usize;
         let count = self.header.count as usize;
         let size = self.processor.size();
@@ -871,3 +949,67 @@ fn map_processors(name: &str) -> Processor {
         _ => Processor::None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        defines::Header, read_var_buffer, static_data::StaticData, update_session_data,
+        SessionStrUpdates,
+    };
+
+    #[test]
+    fn malformed_session_str_is_decoded_once() {
+        let mut session_data = StaticData::default();
+        let mut updates = SessionStrUpdates::default();
+        let mut decoded = 0;
+        // The same update is polled twice.
+        for _ in 0..2 {
+            if updates.is_pending(4) {
+                decoded += 1;
+                let parsed = update_session_data(&mut session_data, b"WeekendInfo: [", 4);
+                updates.record(4, parsed);
+            }
+        }
+        assert_eq!(decoded, 1);
+        // A new update is decoded again.
+        assert!(updates.is_pending(5));
+    }
+
+    #[test]
+    fn malformed_session_str_keeps_previous_data() {
+        let mut session_data = StaticData::default();
+        session_data.update_count = 3;
+        session_data.weekend_info.track_name = Some("spa".to_string());
+
+        assert!(!update_session_data(
+            &mut session_data,
+            b"WeekendInfo:\n TrackName: [spa",
+            4
+        ));
+        assert_eq!(session_data.update_count, 3);
+        assert_eq!(
+            session_data.weekend_info.track_name,
+            Some("spa".to_string())
+        );
+    }
+
+    #[test]
+    fn read_newest_var_buffer() {
+        let mut memory = vec![0u8; Header::SIZE + 8];
+        let ints = [
+            (36, 4),                       // var buffer len
+            (48, 4),                       // tick count of buffer 0
+            (52, Header::SIZE as i32),     // offset of buffer 0
+            (64, 5),                       // tick count of buffer 1
+            (68, Header::SIZE as i32 + 4), // offset of buffer 1
+        ];
+        for (offset, value) in ints {
+            memory[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        }
+        memory[Header::SIZE..].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let header = Header::from_bytes(&memory).unwrap();
+        let snapshot = unsafe { read_var_buffer(memory.as_ptr(), &header) };
+        assert_eq!(snapshot, Some((5, vec![5, 6, 7, 8])));
+    }
+}
@@ -292,6 +292,59 @@ pub struct Header {
     pub var_buffers: [VarBuffer; MAX_BUFFERS],
 }
 
+impl Header {
+    /// Size of the header in bytes.
+    pub const SIZE: usize = std::mem::size_of::<Header>();
+
+    /// Read a header from the start of a byte buffer.
+    /// Returns `None` if the buffer is too small to contain a header.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::SIZE {
+            return None;
+        }
+        // SAFETY: The buffer has been checked to be large enough.
+        // A Header struct is plain data and for all fields any bit pattern is a vlaid value.
+        // Therefore reading it is fine.
+        Some(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const Header) })
+    }
+}
+
+/// The header of a telemetry file on disk. Follows directly after the `Header`.
+#[derive(Debug, Clone)]
+#[repr(C)]
+pub struct DiskSubHeader {
+    /// Start date of the session as a unix timestamp.
+    pub session_start_date: i64,
+    /// Session time when the recording started.
+    pub session_start_time: f64,
+    /// Session time when the recording ended.
+    pub session_end_time: f64,
+    /// Number of laps in the recording.
+    pub session_lap_count: i32,
+    /// Number of records in the recording.
+    pub session_record_count: i32,
+}
+
+impl DiskSubHeader {
+    /// Size of the disk sub header in bytes.
+    pub const SIZE: usize = std::mem::size_of::<DiskSubHeader>();
+
+    /// Read a disk sub header from the start of a byte buffer.
+    /// Returns `None` if the buffer is too small to contain a disk sub header.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::SIZE {
+            return None;
+        }
+        Some(Self {
+            session_start_date: i64::from_le_bytes(bytes[0..8].try_into().ok()?),
+            session_start_time: f64::from_le_bytes(bytes[8..16].try_into().ok()?),
+            session_end_time: f64::from_le_bytes(bytes[16..24].try_into().ok()?),
+            session_lap_count: i32::from_le_bytes(bytes[24..28].try_into().ok()?),
+            session_record_count: i32::from_le_bytes(bytes[28..32].try_into().ok()?),
+        })
+    }
+}
+
 bitflags! {
     /// Shared memory status bifflags
     #[derive(Debug, Clone)]
@@ -324,6 +377,42 @@ pub struct VarHeader {
     pub unit: [u8; MAX_STRING],
 }
 
+impl VarHeader {
+    /// Size of a variable header in bytes.
+    pub const SIZE: usize = std::mem::size_of::<VarHeader>();
+
+    /// Read a variable header from the start of a byte buffer.
+    /// Returns `None` if the buffer is too small or the variable type is unknown.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::SIZE {
+            return None;
+        }
+        let var_type = match i32::from_le_bytes(bytes[0..4].try_into().ok()?) {
+            0 => VarType::Char,
+            1 => VarType::Bool,
+            2 => VarType::Int,
+            3 => VarType::Bitfield,
+            4 => VarType::Float,
+            5 => VarType::Double,
+            _ => return None,
+        };
+        let name_end = 16 + MAX_STRING;
+        let description_end = name_end + MAX_DESC;
+        Some(Self {
+            var_type,
+            offset: i32::from_le_bytes(bytes[4..8].try_into().ok()?),
+            count: i32::from_le_bytes(bytes[8..12].try_into().ok()?),
+            count_as_time: bytes[12] > 0,
+            pad: [0; 3],
+            name: bytes[16..name_end].try_into().ok()?,
+            description: bytes[name_end..description_end].try_into().ok()?,
+            unit: bytes[description_end..description_end + MAX_STRING]
+                .try_into()
+                .ok()?,
+        })
+    }
+}
+
 /// Types of variables in the shared memory.
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -337,6 +426,17 @@ pub enum VarType {
     Double,
 }
 
+impl VarType {
+    /// Size of a single value of this type in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            VarType::Char | VarType::Bool => 1,
+            VarType::Int | VarType::Bitfield | VarType::Float => 4,
+            VarType::Double => 8,
+        }
+    }
+}
+
 /// A buffer that holds the variables in the shared memory.
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -1089,7 +1089,7 @@ where
 {
     deserializer
         .deserialize_str(UnitVisitor { unit: "m" })
-        .map(|km| Some(Distance::from_kilometers(km)))
+        .map(|m| Some(Distance::from_meter(m)))
 }
 
 fn mm_deserializer<'de, D>(deserializer: D) -> Result<Option<Distance>, D::Error>
@@ -1098,7 +1098,7 @@ where
 {
     deserializer
         .deserialize_str(UnitVisitor { unit: "mm" })
-        .map(|km| Some(Distance::from_kilometers(km)))
+        .map(|mm| Some(Distance::from_millimeters(mm)))
 }
 
 fn decimal_degrees_deserializer<'de, D>(deserializer: D) -> Result<Option<Angle>, D::Error>
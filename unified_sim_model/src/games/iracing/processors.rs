@@ -6,7 +6,12 @@ use super::{irsdk::Data, IRacingResult};
 
 pub mod base;
 pub mod camera;
+pub mod ffb;
+pub mod inputs;
 pub mod lap;
+pub mod pit_service;
+pub mod pit_time;
+pub mod safety_car;
 pub mod speed;
 
 /// The context for a iracing processor.
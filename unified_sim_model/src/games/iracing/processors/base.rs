@@ -1,13 +1,19 @@
-use std::collections::{HashMap, VecDeque};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
 
 use tracing::{info, warn};
+use yore::code_pages::CP1252;
 
 use crate::{
     games::{
-        common::distance_driven,
+        common::{debounce::Debounce, distance_driven, pit_stops::PitStopCounter},
         iracing::{
+            car_index, car_value,
             irsdk::{
-                defines::{SessionState, TrkLoc},
+                defines::{Flags, SessionState, TrkLoc},
+                live_data::LiveData,
                 static_data::{self, ResultFastedLap},
                 Data,
             },
@@ -15,12 +21,31 @@ use crate::{
         },
     },
     model::{self, Value},
-    Temperature, Time,
+    Color, Temperature, Time,
 };
 
 use super::{IRacingProcessor, IRacingProcessorContext};
 
-pub struct BaseProcessor {}
+pub struct BaseProcessor {
+    /// Counts the pit stops of the entries.
+    pit_stops: PitStopCounter,
+    /// The session phase read from the game.
+    phase: Debounce<model::SessionPhase>,
+    /// Entries that were seen with push to pass.
+    push_to_pass: HashSet<model::EntryId>,
+}
+
+impl BaseProcessor {
+    /// Create a new processor.
+    /// `hold` is the time a change in the session phase must be stable before it is accepted.
+    pub fn new(hold: Duration) -> Self {
+        Self {
+            pit_stops: PitStopCounter::default(),
+            phase: Debounce::new(model::SessionPhase::Waiting, hold),
+            push_to_pass: HashSet::new(),
+        }
+    }
+}
 
 impl IRacingProcessor for BaseProcessor {
     fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
@@ -74,28 +99,58 @@ impl IRacingProcessor for BaseProcessor {
                 "New {:?} session detected",
                 current_session.session_type.as_ref()
             );
+            self.phase.reset(*current_session.phase);
+            self.push_to_pass.clear();
             context
                 .events
                 .push_back(model::Event::SessionChanged(current_session.id));
         }
 
         // Set the focused entry
-        if let Some(ref cam_car_idx) = context.data.live_data.cam_car_idx {
-            context.model.focused_entry = Some(model::EntryId(*cam_car_idx));
-        } else {
-            context.model.focused_entry = None;
+        let focused_entry = context.data.live_data.cam_car_idx.map(model::EntryId);
+        if context.model.focused_entry != focused_entry {
+            context.model.focused_entry = focused_entry;
+            context.events.push_back(model::Event::FocusChanged {
+                entry: focused_entry,
+            });
         }
 
         // Update session.
-        update_session_live(context);
+        update_session_live(context, &mut self.phase);
 
         // Update entries
         let current_session = context
             .model
             .current_session_mut()
             .expect("The current session should be available");
+        // While the positions are updated iRacing can report the same position for multiple
+        // cars for a single frame. Only take the new positions when they are a valid order,
+        // otherwise keep the positions from the last frame.
+        let positions = context
+            .data
+            .live_data
+            .car_idx_position
+            .as_deref()
+            .filter(|positions| {
+                is_valid_order(
+                    current_session
+                        .entries
+                        .keys()
+                        .filter_map(|entry_id| car_value(positions, entry_id.0).copied()),
+                )
+            });
         for (_entry_id, entry) in current_session.entries.iter_mut() {
-            update_entry_live(entry, context.data, &mut context.events);
+            if let Some(position) = positions.and_then(|p| car_value(p, entry.id.0)) {
+                entry.position.set(*position);
+            }
+            update_entry_live(
+                entry,
+                context.data,
+                &mut context.events,
+                &mut self.pit_stops,
+            );
+            entry.overtake =
+                map_overtake(&context.data.live_data, entry.id, &mut self.push_to_pass);
             distance_driven::calc_distance_driven(entry);
         }
         Ok(())
@@ -178,6 +233,16 @@ fn init_session(session_info: &static_data::Session, data: &Data) -> IRacingResu
         _ => model::Value::default(),
     };
 
+    let rules = match data.static_data.weekend_info.weekend_options {
+        Some(ref options) => model::SessionRules {
+            incident_limit: map_limit(&options.incident_limit),
+            fast_repairs: map_limit(&options.fast_repairs_limit),
+            joker_laps: options.num_joker_laps.unwrap_or_default(),
+        }
+        .into(),
+        None => model::Value::default(),
+    };
+
     let ambient_temp = match data.static_data.weekend_info.track_air_temp {
         Some(temp) => temp.into(),
         None => model::Value::default(),
@@ -194,8 +259,13 @@ fn init_session(session_info: &static_data::Session, data: &Data) -> IRacingResu
     };
 
     let track_length = match data.static_data.weekend_info.track_length {
-        Some(track_length) => track_length.into(),
-        None => model::Value::default(),
+        Some(track_length) if track_length.as_meters() > 0.0 => track_length.into(),
+        _ => model::Value::default(),
+    };
+
+    let sector_count = match data.static_data.split_time_info.sectors.len() {
+        0 => model::Value::default(),
+        count => count.into(),
     };
 
     let entries = init_entries(session_info, data)?;
@@ -212,8 +282,10 @@ fn init_session(session_info: &static_data::Session, data: &Data) -> IRacingResu
                     time: Time::from_secs(*time).into(),
                     splits: Vec::new().into(),
                     invalid: false.into(),
+                    caution: model::Value::default(),
                     driver_id: None,
                     entry_id: Some(entry_id),
+                    driving_metrics: None,
                 })
                 .into()
             } else {
@@ -227,6 +299,10 @@ fn init_session(session_info: &static_data::Session, data: &Data) -> IRacingResu
         id,
         entries,
         session_type,
+        name: session_info
+            .session_name
+            .clone()
+            .map_or_else(model::Value::default, model::Value::new),
         phase: model::SessionPhase::Waiting.into(),
         session_time,
         time_remaining: model::Value::default(),
@@ -239,10 +315,24 @@ fn init_session(session_info: &static_data::Session, data: &Data) -> IRacingResu
         best_lap,
         track_name,
         track_length,
+        sector_count,
+        best_splits: Vec::new(),
+        pit_window: model::Value::default(),
+        phase_start: None,
+        safety_car: model::Value::default(),
+        rules,
         game_data: model::SessionGameData::None,
     })
 }
 
+fn map_limit(value: &Option<static_data::MaybeUnlimited<i32>>) -> model::Value<model::Limit<i32>> {
+    match value {
+        Some(static_data::MaybeUnlimited::Value(limit)) => model::Limit::Limited(*limit).into(),
+        Some(static_data::MaybeUnlimited::Unlimited) => model::Limit::Unlimited.into(),
+        None => model::Value::default(),
+    }
+}
+
 fn init_entries(
     session_info: &static_data::Session,
     data: &Data,
@@ -259,6 +349,10 @@ fn init_entries(
         let Some(car_idx) = driver_info.car_idx else {
             Err(IRacingError::MissingData("car_idx".into()))?
         };
+        if car_index(car_idx).is_none() {
+            warn!("Driver with an invalid car index {car_idx} ignored");
+            continue;
+        }
         let entry_id = model::EntryId(car_idx);
         if !entries.contains_key(&entry_id) {
             let entry = map_entry(driver_info)?;
@@ -285,8 +379,10 @@ fn init_entries(
             time: Time::from_secs(fastest_lap_time).into(),
             splits: Vec::new().into(),
             invalid: false.into(),
+            caution: model::Value::default(),
             driver_id: None,
             entry_id: Some(entry_id),
+            driving_metrics: None,
         }));
     }
 
@@ -301,7 +397,7 @@ fn map_entry(driver_info: &static_data::Driver) -> IRacingResult<model::Entry> {
         .ok_or_else(|| IRacingError::MissingData("car_idx".into()))?;
 
     let team_name = match driver_info.team_name {
-        Some(ref name) => name.clone().into(),
+        Some(ref name) => normalize_name(name).into(),
         None => model::Value::default(),
     };
 
@@ -309,7 +405,7 @@ fn map_entry(driver_info: &static_data::Driver) -> IRacingResult<model::Entry> {
         Some(ref car_name) => model::Car::new(
             car_name.to_owned(),
             "".to_owned(),
-            model::CarCategory::new(""),
+            car_category(driver_info.car_class_short_name.as_deref().unwrap_or("")),
         )
         .into(),
         None => model::Value::default(),
@@ -320,6 +416,18 @@ fn map_entry(driver_info: &static_data::Driver) -> IRacingResult<model::Entry> {
         None => model::Value::default(),
     };
 
+    // The car design string has the format "pattern,color1,color2,color3" where the
+    // first color is the primary color of the livery.
+    let livery_color = match driver_info
+        .car_design_str
+        .as_ref()
+        .and_then(|design| design.split(',').nth(1))
+        .and_then(Color::from_hex)
+    {
+        Some(color) => color.into(),
+        None => model::Value::default(),
+    };
+
     Ok(model::Entry {
         id: model::EntryId(car_idx),
         drivers: {
@@ -331,10 +439,12 @@ fn map_entry(driver_info: &static_data::Driver) -> IRacingResult<model::Entry> {
         team_name,
         car,
         car_number,
+        livery_color,
         nationality: model::Value::<model::Nationality>::default().with_editable(),
         world_pos: model::Value::default(),
         orientation: model::Value::default(),
         position: model::Value::default(),
+        projected_position: model::Value::default(),
         spline_pos: model::Value::default(),
         lap_count: model::Value::default(),
         laps: Vec::new(),
@@ -344,10 +454,20 @@ fn map_entry(driver_info: &static_data::Driver) -> IRacingResult<model::Entry> {
         time_behind_leader: model::Value::default(),
         time_behind_position_ahead: Value::default(),
         in_pits: model::Value::default(),
+        pit_stops_completed: model::Value::new(0),
+        grid_position: model::Value::default(),
+        pit_time: model::Value::default(),
+        last_pit_duration: model::Value::default(),
+        mandatory_stops_remaining: model::Value::default(),
         gear: model::Value::default(),
         speed: model::Value::default(),
+        inputs: model::Value::default(),
+        overtake: None,
+        brakes: None,
+        pit_service: model::Value::default(),
         connected: model::Value::default(),
         stint_time: model::Value::default(),
+        stints: Vec::new(),
         distance_driven: model::Value::default(),
         focused: false,
         game_data: model::EntryGameData::None,
@@ -355,10 +475,41 @@ fn map_entry(driver_info: &static_data::Driver) -> IRacingResult<model::Entry> {
     })
 }
 
+/// Car categories of well known car classes.
+///
+/// iRacing names the classes per series, for example "GT3 Class" or "IMSA GTP".
+/// A class is part of a category if its name contains the name of the category as a word.
+const KNOWN_CATEGORIES: [model::CarCategory; 6] = [
+    model::CarCategory::new("GT3"),
+    model::CarCategory::new("GT4"),
+    model::CarCategory::new("GTP"),
+    model::CarCategory::new("LMP2"),
+    model::CarCategory::new("LMP3"),
+    model::CarCategory::new("TCR"),
+];
+
+/// Returns the car category for the short name of a car class.
+///
+/// Classes of a well known category are mapped to that category. Any other class
+/// is a category of its own.
+fn car_category(class_name: &str) -> model::CarCategory {
+    let class_name = class_name.trim();
+    if class_name.is_empty() {
+        return model::CarCategory::new("None");
+    }
+    let known = KNOWN_CATEGORIES.into_iter().find(|category| {
+        class_name
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|word| word.eq_ignore_ascii_case(&category.name))
+    });
+    known.unwrap_or_else(|| model::CarCategory::new_owned(class_name.to_owned()))
+}
+
 fn map_driver(driver_info: &static_data::Driver) -> IRacingResult<model::Driver> {
     let (first_name, last_name) = {
-        let split: Option<(String, String)> = driver_info.user_name.clone().and_then(|name| {
-            name.split_once(' ')
+        let split: Option<(String, String)> = driver_info.user_name.as_ref().and_then(|name| {
+            normalize_name(name)
+                .split_once(' ')
                 .map(|(l, r)| (l.to_owned(), r.to_owned()))
         });
         if let Some((first_name, last_name)) = split {
@@ -383,6 +534,33 @@ fn map_driver(driver_info: &static_data::Driver) -> IRacingResult<model::Driver>
     })
 }
 
+/// Normalizes a name from the session string into a clean UTF-8 string.
+///
+/// The session string is decoded as CP1252. Names that were written to the session string
+/// as UTF-8 end up double encoded after decoding (e.g. "MÃ¼ller"). Those are repaired by
+/// encoding them back to CP1252 and reading the bytes as UTF-8.
+/// Control characters are replaced with a space and repeated whitespace is collapsed.
+/// A warning is logged if the name contains characters that could not be decoded.
+fn normalize_name(name: &str) -> String {
+    let name = CP1252
+        .encode(name)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes.into_owned()).ok())
+        .unwrap_or_else(|| name.to_owned());
+    if name.contains(char::REPLACEMENT_CHARACTER) {
+        warn!(
+            "Name contains characters that could not be decoded: {}",
+            name
+        );
+    }
+    name.chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn map_session_type(session_type_str: &str) -> model::SessionType {
     match session_type_str {
         "Race" => model::SessionType::Race,
@@ -395,22 +573,25 @@ fn map_session_type(session_type_str: &str) -> model::SessionType {
     }
 }
 
-fn update_session_live(context: &mut IRacingProcessorContext) {
+fn update_session_live(
+    context: &mut IRacingProcessorContext,
+    phase: &mut Debounce<model::SessionPhase>,
+) {
     let session = context
         .model
         .current_session_mut()
         .expect("Current session should be valid");
 
     if let Some(ref session_state) = context.data.live_data.session_state {
-        let new_phase = map_session_phase(session_state);
-        if session.phase != new_phase {
-            info!("Session phase changed to {:?}", new_phase);
-            session.phase.set(new_phase);
-            context.events.push_back(model::Event::SessionPhaseChanged(
-                session.id,
-                *session.phase,
-            ));
-        }
+        let new_phase =
+            map_session_phase(session_state, context.data.live_data.session_flags.as_ref());
+        update_session_phase(
+            session,
+            phase,
+            new_phase,
+            Instant::now(),
+            &mut context.events,
+        );
     }
 
     if let Some(time_remaining) = context.data.live_data.session_time_remain {
@@ -438,34 +619,113 @@ fn update_session_live(context: &mut IRacingProcessorContext) {
     }
 }
 
-fn map_session_phase(session_state: &SessionState) -> model::SessionPhase {
+/// Update the phase of the session with the phase read from the game.
+///
+/// The phase is partly derived from the flags which can flicker for a single update.
+/// A new phase is only applied once it has been stable for the hold time of the debounce.
+fn update_session_phase(
+    session: &mut model::Session,
+    phase: &mut Debounce<model::SessionPhase>,
+    new_phase: model::SessionPhase,
+    now: Instant,
+    events: &mut VecDeque<model::Event>,
+) {
+    phase.update(new_phase, now);
+    advance_session_phase(session, *phase.value(), events);
+}
+
+/// Advance the phase of the session up to the new phase.
+///
+/// The phase only moves forward so that noisy data cannot move the session back.
+/// A phase that is skipped is still passed through so that every phase change has an event.
+fn advance_session_phase(
+    session: &mut model::Session,
+    new_phase: model::SessionPhase,
+    events: &mut VecDeque<model::Event>,
+) {
+    while *session.phase < new_phase {
+        session.phase.set(session.phase.next());
+        info!("Session phase changed to {:?}", *session.phase);
+        events.push_back(model::Event::SessionPhaseChanged(
+            session.id,
+            *session.phase,
+        ));
+    }
+}
+
+/// Map the session state and the session flags to a session phase.
+///
+/// The flags can show a phase change before the session state changes. The green flag
+/// ends the parade laps and the checkered flag ends the racing.
+fn map_session_phase(session_state: &SessionState, flags: Option<&Flags>) -> model::SessionPhase {
+    let has_flag = |flag: Flags| flags.is_some_and(|flags| flags.contains(flag));
     match session_state {
         SessionState::StateInvalid => model::SessionPhase::Waiting,
         SessionState::StateGetInCar => model::SessionPhase::Preparing,
         SessionState::StateWarmup => model::SessionPhase::Preparing,
+        SessionState::StateParadeLaps if has_flag(Flags::irsdk_startGo) => {
+            model::SessionPhase::Active
+        }
         SessionState::StateParadeLaps => model::SessionPhase::Formation,
+        SessionState::StateRacing if has_flag(Flags::irsdk_checkered) => {
+            model::SessionPhase::Ending
+        }
         SessionState::StateRacing => model::SessionPhase::Active,
         SessionState::StateCheckered => model::SessionPhase::Ending,
         SessionState::StateCoolDown => model::SessionPhase::Finished,
     }
 }
 
-fn update_entry_live(entry: &mut model::Entry, data: &Data, events: &mut VecDeque<model::Event>) {
-    let car_idx = entry.id.0 as usize;
-
-    // TODO: Update current driver for team races.
-
-    if let Some(ref car_idx_position) = data.live_data.car_idx_position {
-        if let Some(position) = car_idx_position.get(car_idx) {
-            entry.position.set(*position);
+/// Check if the positions are a valid order.
+///
+/// A position of zero means the car has no position yet. All other positions must be
+/// unique and if any car has a position the leader must be present.
+fn is_valid_order(positions: impl Iterator<Item = i32>) -> bool {
+    let mut seen = HashSet::new();
+    for position in positions.filter(|position| *position > 0) {
+        if !seen.insert(position) {
+            return false;
         }
     }
+    seen.is_empty() || seen.contains(&1)
+}
+
+/// Update the spline position and the gear of an entry.
+///
+/// iRacing reports a spline position of -1 for cars that are not on track. The gear array
+/// also reports -1 for those cars, which is the same as reverse. Both values keep their
+/// last known value in that case.
+fn update_entry_telemetry(entry: &mut model::Entry, live_data: &LiveData, car_idx: usize) {
+    let spline_pos = live_data
+        .car_idx_lap_dist_pct
+        .as_ref()
+        .and_then(|car_idx_lap_dist_pct| car_idx_lap_dist_pct.get(car_idx))
+        .filter(|spline_pos| **spline_pos >= 0.0);
+    let Some(spline_pos) = spline_pos else {
+        return;
+    };
+    entry.spline_pos.set(*spline_pos);
 
-    if let Some(ref car_idx_lap_dist_pct) = data.live_data.car_idx_lap_dist_pct {
-        if let Some(spline_pos) = car_idx_lap_dist_pct.get(car_idx) {
-            entry.spline_pos.set(*spline_pos);
+    if let Some(ref car_idx_gear) = live_data.car_idx_gear {
+        if let Some(gear) = car_idx_gear.get(car_idx) {
+            entry.gear.set(*gear);
         }
     }
+}
+
+fn update_entry_live(
+    entry: &mut model::Entry,
+    data: &Data,
+    events: &mut VecDeque<model::Event>,
+    pit_stops: &mut PitStopCounter,
+) {
+    let Some(car_idx) = car_index(entry.id.0) else {
+        return;
+    };
+
+    // TODO: Update current driver for team races.
+
+    update_entry_telemetry(entry, &data.live_data, car_idx);
 
     if let Some(ref car_idx_laps) = data.live_data.car_idx_lap_completed {
         if let Some(laps) = car_idx_laps.get(car_idx) {
@@ -479,8 +739,10 @@ fn update_entry_live(entry: &mut model::Entry, data: &Data, events: &mut VecDequ
                 time: (*time).into(),
                 splits: Vec::new().into(),
                 invalid: model::Value::default(),
+                caution: model::Value::default(),
                 driver_id: Some(entry.current_driver),
                 entry_id: Some(entry.id),
+                driving_metrics: None,
             });
         }
     }
@@ -493,16 +755,17 @@ fn update_entry_live(entry: &mut model::Entry, data: &Data, events: &mut VecDequ
 
     if let Some(ref car_idx_on_pit_road) = data.live_data.car_idx_on_pit_road {
         if let Some(on_pit_road) = car_idx_on_pit_road.get(car_idx) {
+            let in_pit_stall = data
+                .live_data
+                .car_idx_track_surface
+                .as_ref()
+                .and_then(|track_surface| track_surface.get(car_idx))
+                .is_some_and(|track_location| matches!(track_location, TrkLoc::InPitStall));
+            pit_stops.update(entry, *on_pit_road, in_pit_stall);
             entry.in_pits.set(*on_pit_road);
         }
     }
 
-    if let Some(ref car_idx_gear) = data.live_data.car_idx_gear {
-        if let Some(gear) = car_idx_gear.get(car_idx) {
-            entry.gear.set(*gear);
-        }
-    }
-
     if let Some(ref cam_car_idx) = data.live_data.cam_car_idx {
         entry.focused = *cam_car_idx as usize == car_idx;
     }
@@ -530,3 +793,355 @@ fn update_entry_live(entry: &mut model::Entry, data: &Data, events: &mut VecDequ
         }
     }
 }
+
+/// Map the push to pass state of a car.
+///
+/// iRacing reports the push to pass arrays for every car, even if the car does not have
+/// push to pass. A car only has push to pass once it was seen with a count or an active
+/// push to pass. `None` for cars where that was never the case.
+fn map_overtake(
+    live_data: &LiveData,
+    entry_id: model::EntryId,
+    push_to_pass: &mut HashSet<model::EntryId>,
+) -> Option<model::OvertakeState> {
+    let active = *car_value(live_data.car_idx_p2p_status.as_ref()?, entry_id.0)?;
+    let remaining = *car_value(live_data.car_idx_p2p_count.as_ref()?, entry_id.0)?;
+    if active || remaining > 0 {
+        push_to_pass.insert(entry_id);
+    }
+    push_to_pass
+        .contains(&entry_id)
+        .then_some(model::OvertakeState { active, remaining })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashSet, VecDeque},
+        time::{Duration, Instant},
+    };
+
+    use yore::code_pages::CP1252;
+
+    use crate::{
+        games::{
+            common::debounce::Debounce,
+            iracing::irsdk::{
+                defines::{Flags, SessionState},
+                live_data::LiveData,
+                static_data::MaybeUnlimited,
+                Data,
+            },
+        },
+        model::{self, EntryId, Limit, OvertakeState, SessionPhase},
+    };
+
+    use super::{
+        advance_session_phase, car_category, is_valid_order, map_limit, map_overtake,
+        map_session_phase, normalize_name, update_entry_telemetry, update_session_phase,
+        BaseProcessor, IRacingProcessor, IRacingProcessorContext,
+    };
+
+    #[test]
+    fn decode_cp1252_name() {
+        // "Jürgen Müller" encoded as CP1252.
+        let name = CP1252.decode(b"J\xfcrgen M\xfcller");
+        assert_eq!(normalize_name(&name), "Jürgen Müller");
+    }
+
+    #[test]
+    fn repair_utf8_name() {
+        // "Jürgen Müller" encoded as UTF-8 but decoded as CP1252.
+        let name = CP1252.decode("Jürgen Müller".as_bytes());
+        assert_eq!(name, "JÃ¼rgen MÃ¼ller");
+        assert_eq!(normalize_name(&name), "Jürgen Müller");
+    }
+
+    #[test]
+    fn replace_control_characters() {
+        assert_eq!(normalize_name("Max\tVerstappen\0 "), "Max Verstappen");
+        assert_eq!(normalize_name(" Max \r\n Verstappen"), "Max Verstappen");
+    }
+
+    #[test]
+    fn telemetry_of_cars_off_track_is_kept() {
+        let live_data = LiveData {
+            car_idx_lap_dist_pct: Some(vec![0.25, -1.0]),
+            car_idx_gear: Some(vec![3, -1]),
+            ..Default::default()
+        };
+        let mut entry = model::Entry::default();
+        update_entry_telemetry(&mut entry, &live_data, 0);
+        assert_eq!(*entry.spline_pos, 0.25);
+        assert_eq!(*entry.gear, 3);
+
+        update_entry_telemetry(&mut entry, &live_data, 1);
+        assert_eq!(*entry.spline_pos, 0.25);
+        assert_eq!(*entry.gear, 3);
+
+        // A car index beyond the arrays.
+        update_entry_telemetry(&mut entry, &live_data, 63);
+        assert_eq!(*entry.spline_pos, 0.25);
+    }
+
+    #[test]
+    fn session_state_to_phase() {
+        let no_flags = Flags::empty();
+        let table = [
+            (
+                SessionState::StateInvalid,
+                no_flags.clone(),
+                SessionPhase::Waiting,
+            ),
+            (
+                SessionState::StateGetInCar,
+                no_flags.clone(),
+                SessionPhase::Preparing,
+            ),
+            (
+                SessionState::StateWarmup,
+                no_flags.clone(),
+                SessionPhase::Preparing,
+            ),
+            (
+                SessionState::StateParadeLaps,
+                no_flags.clone(),
+                SessionPhase::Formation,
+            ),
+            (
+                SessionState::StateParadeLaps,
+                Flags::irsdk_startGo,
+                SessionPhase::Active,
+            ),
+            (
+                SessionState::StateRacing,
+                Flags::irsdk_green,
+                SessionPhase::Active,
+            ),
+            (
+                SessionState::StateRacing,
+                Flags::irsdk_checkered,
+                SessionPhase::Ending,
+            ),
+            (
+                SessionState::StateCheckered,
+                no_flags.clone(),
+                SessionPhase::Ending,
+            ),
+            (
+                SessionState::StateCoolDown,
+                no_flags.clone(),
+                SessionPhase::Finished,
+            ),
+        ];
+        for (state, flags, phase) in table {
+            assert_eq!(map_session_phase(&state, Some(&flags)), phase, "{state:?}");
+        }
+        assert_eq!(
+            map_session_phase(&SessionState::StateRacing, None),
+            SessionPhase::Active
+        );
+    }
+
+    #[test]
+    fn session_phase_does_not_regress() {
+        let mut session = model::Session {
+            phase: SessionPhase::Waiting.into(),
+            ..Default::default()
+        };
+        let mut events = VecDeque::new();
+        advance_session_phase(&mut session, SessionPhase::Active, &mut events);
+        assert_eq!(*session.phase, SessionPhase::Active);
+        // Preparing, Formation and Active.
+        assert_eq!(events.len(), 3);
+
+        advance_session_phase(&mut session, SessionPhase::Formation, &mut events);
+        assert_eq!(*session.phase, SessionPhase::Active);
+        assert_eq!(events.len(), 3);
+    }
+
+    #[test]
+    fn single_frame_checkered_flag_is_ignored() {
+        let start = Instant::now();
+        let frame = Duration::from_millis(16);
+        let mut session = model::Session {
+            phase: SessionPhase::Active.into(),
+            ..Default::default()
+        };
+        let mut phase = Debounce::new(SessionPhase::Active, Duration::from_millis(500));
+        let mut events = VecDeque::new();
+
+        update_session_phase(
+            &mut session,
+            &mut phase,
+            SessionPhase::Ending,
+            start,
+            &mut events,
+        );
+        update_session_phase(
+            &mut session,
+            &mut phase,
+            SessionPhase::Active,
+            start + frame,
+            &mut events,
+        );
+        assert_eq!(*session.phase, SessionPhase::Active);
+        assert!(events.is_empty());
+
+        // A stable change is accepted after the hold time.
+        for frame_count in 2..40 {
+            update_session_phase(
+                &mut session,
+                &mut phase,
+                SessionPhase::Ending,
+                start + frame * frame_count,
+                &mut events,
+            );
+        }
+        assert_eq!(*session.phase, SessionPhase::Ending);
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn car_class_to_category() {
+        let table = [
+            ("GT3", "GT3"),
+            ("GT3 Class", "GT3"),
+            ("gt4", "GT4"),
+            ("IMSA GTP", "GTP"),
+            ("LMP2", "LMP2"),
+            ("Dallara P217 LMP2", "LMP2"),
+            ("TCR", "TCR"),
+            ("MX5 Cup", "MX5 Cup"),
+            ("GT3R Cup", "GT3R Cup"),
+            ("", "None"),
+        ];
+        for (class_name, category) in table {
+            assert_eq!(car_category(class_name).name, category, "{class_name}");
+        }
+    }
+
+    #[test]
+    fn missing_limit_is_not_available() {
+        let limited = map_limit(&Some(MaybeUnlimited::Value(17)));
+        assert_eq!(*limited, Limit::Limited(17));
+        let unlimited = map_limit(&Some(MaybeUnlimited::Unlimited));
+        assert!(unlimited.is_avaliable());
+        assert_eq!(*unlimited, Limit::Unlimited);
+        assert!(!map_limit(&None).is_avaliable());
+    }
+
+    #[test]
+    fn valid_position_order() {
+        assert!(is_valid_order([2, 1, 3].into_iter()));
+        assert!(is_valid_order([0, 0, 0].into_iter()));
+        assert!(is_valid_order([1, 0, 2].into_iter()));
+    }
+
+    #[test]
+    fn invalid_position_order() {
+        // Two cars in the same position.
+        assert!(!is_valid_order([1, 2, 2].into_iter()));
+        // No leader.
+        assert!(!is_valid_order([2, 3, 0].into_iter()));
+    }
+
+    #[test]
+    fn inconsistent_positions_are_ignored() {
+        let mut session = model::Session {
+            id: model::SessionId(0),
+            ..Default::default()
+        };
+        for (id, position) in [(0, 1), (1, 2), (2, 3)] {
+            let entry = model::Entry {
+                id: EntryId(id),
+                position: model::Value::new(position),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        let mut model = model::Model::default();
+        model.sessions.insert(session.id, session);
+        model.current_session = Some(model::SessionId(0));
+
+        let mut processor = BaseProcessor::new(Duration::ZERO);
+        let mut update = |positions: Vec<i32>| {
+            let mut data = Data::default();
+            data.live_data.session_num = Some(0);
+            data.live_data.car_idx_position = Some(positions);
+            let mut context = IRacingProcessorContext {
+                model: &mut model,
+                events: VecDeque::new(),
+                data: &data,
+            };
+            assert!(processor.live_data(&mut context).is_ok());
+            let session = model.current_session().unwrap();
+            [0, 1, 2].map(|id| *session.entries[&EntryId(id)].position)
+        };
+
+        // Two cars in third position while the order is updated.
+        assert_eq!(update(vec![2, 3, 3]), [1, 2, 3]);
+        assert_eq!(update(vec![2, 3, 1]), [2, 3, 1]);
+    }
+
+    #[test]
+    fn overtake_from_p2p_arrays() {
+        let live_data = LiveData {
+            car_idx_p2p_status: Some(vec![false, true, false]),
+            car_idx_p2p_count: Some(vec![10, 7, 0]),
+            ..Default::default()
+        };
+        let mut push_to_pass = HashSet::new();
+        assert_eq!(
+            map_overtake(&live_data, EntryId(1), &mut push_to_pass),
+            Some(OvertakeState {
+                active: true,
+                remaining: 7
+            })
+        );
+        assert_eq!(
+            map_overtake(&live_data, EntryId(0), &mut push_to_pass),
+            Some(OvertakeState {
+                active: false,
+                remaining: 10
+            })
+        );
+        assert_eq!(
+            map_overtake(&live_data, EntryId(3), &mut push_to_pass),
+            None
+        );
+        assert_eq!(
+            map_overtake(&LiveData::default(), EntryId(1), &mut push_to_pass),
+            None
+        );
+    }
+
+    #[test]
+    fn no_overtake_without_push_to_pass() {
+        let mut push_to_pass = HashSet::new();
+        let live_data = LiveData {
+            car_idx_p2p_status: Some(vec![false, false]),
+            car_idx_p2p_count: Some(vec![0, 3]),
+            ..Default::default()
+        };
+        assert_eq!(
+            map_overtake(&live_data, EntryId(0), &mut push_to_pass),
+            None
+        );
+
+        // A car that used all of its push to pass keeps the state.
+        let used_up = LiveData {
+            car_idx_p2p_status: Some(vec![false, false]),
+            car_idx_p2p_count: Some(vec![0, 0]),
+            ..Default::default()
+        };
+        assert!(map_overtake(&live_data, EntryId(1), &mut push_to_pass).is_some());
+        assert_eq!(
+            map_overtake(&used_up, EntryId(1), &mut push_to_pass),
+            Some(OvertakeState {
+                active: false,
+                remaining: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Copies the force feedback settings of the player into the model.
+
+use crate::{
+    games::iracing::IRacingResult,
+    model::{Event, FfbInfo},
+};
+
+use super::{IRacingProcessor, IRacingProcessorContext};
+
+pub struct FfbProcessor;
+
+impl IRacingProcessor for FfbProcessor {
+    fn static_data(&mut self, _context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        Ok(())
+    }
+
+    fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        let live_data = &context.data.live_data;
+        context.model.player_ffb = live_data
+            .steering_wheel_max_force_nm
+            .zip(live_data.steering_wheel_peak_force_nm)
+            .map(|(max_force_nm, peak_force_nm)| FfbInfo {
+                max_force_nm,
+                peak_force_nm,
+                use_linear: live_data.steering_wheel_use_linear.unwrap_or_default(),
+            });
+        Ok(())
+    }
+
+    fn event(
+        &mut self,
+        _context: &mut IRacingProcessorContext,
+        _event: &Event,
+    ) -> IRacingResult<()> {
+        Ok(())
+    }
+}
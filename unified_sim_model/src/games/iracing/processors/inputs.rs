@@ -0,0 +1,160 @@
+//! Copies the driver inputs and the brake state of the player car into the model.
+//! The inputs are also used to measure the driving metrics of the laps of the player.
+
+use crate::{
+    games::{
+        common::driving_metrics::DrivingMetricsRecorder,
+        iracing::{irsdk::live_data::LiveData, IRacingResult},
+    },
+    model::{BrakeState, DrivingMetrics, EntryId, Event, Inputs},
+    Angle, Pressure, Time,
+};
+
+use super::{IRacingProcessor, IRacingProcessorContext};
+
+#[derive(Default)]
+pub struct InputsProcessor {
+    /// Collects the pedal samples of the current lap.
+    recorder: DrivingMetricsRecorder,
+    /// If the current lap is sampled from its start.
+    complete_lap: bool,
+    /// The lap count of the player in the last update.
+    lap_count: Option<i32>,
+    /// The session time of the last update.
+    session_time: Option<Time>,
+    /// The metrics of the last lap and the number of laps of the player at the time
+    /// the lap was completed. The metrics are set once the lap is added to the entry.
+    finished_lap: Option<(DrivingMetrics, usize)>,
+}
+
+impl IRacingProcessor for InputsProcessor {
+    fn static_data(&mut self, _context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        Ok(())
+    }
+
+    fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        let live_data = &context.data.live_data;
+        let Some(player_car_idx) = live_data.player_car_idx else {
+            return Ok(());
+        };
+        let Some(session) = context.model.current_session_mut() else {
+            return Ok(());
+        };
+        let Some(entry) = session.entries.get_mut(&EntryId(player_car_idx)) else {
+            return Ok(());
+        };
+
+        entry.inputs.set(Inputs {
+            throttle: live_data.throttle.unwrap_or_default(),
+            brake: live_data.brake.unwrap_or_default(),
+            clutch: live_data.clutch.unwrap_or_default(),
+            steer: Angle::from_rad(live_data.steering_wheel_angle.unwrap_or_default()),
+            gear: live_data.gear.unwrap_or_default(),
+            rpm: live_data.rpm.unwrap_or_default(),
+        });
+        entry.brakes = map_brakes(live_data);
+
+        // Measure the driving metrics.
+        let duration = match (self.session_time, live_data.session_time) {
+            (Some(last), Some(now)) => Time::from(now.ms - last.ms),
+            _ => Time::from(0),
+        };
+        self.session_time = live_data.session_time;
+        let lap_count = *entry.lap_count;
+        if self.lap_count.is_some_and(|last| last != lap_count) {
+            let metrics = self.recorder.finish();
+            if self.complete_lap {
+                self.finished_lap = metrics.map(|metrics| (metrics, entry.laps.len()));
+            }
+            self.complete_lap = true;
+        }
+        self.lap_count = Some(lap_count);
+        self.recorder.sample(
+            live_data.throttle.unwrap_or_default(),
+            live_data.brake.unwrap_or_default(),
+            duration,
+        );
+
+        // The lap is added to the entry a little after the lap count changes.
+        if let Some((metrics, laps_before)) = self.finished_lap {
+            if entry.laps.len() > laps_before {
+                if let Some(lap) = entry.laps.last_mut() {
+                    lap.driving_metrics = Some(metrics);
+                }
+                for event in context.events.iter_mut() {
+                    if let Event::LapCompleted(lap_completed) = event {
+                        if lap_completed.lap.entry_id == Some(entry.id) {
+                            lap_completed.lap.driving_metrics = Some(metrics);
+                        }
+                    }
+                }
+                self.finished_lap = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn event(
+        &mut self,
+        _context: &mut IRacingProcessorContext,
+        event: &Event,
+    ) -> IRacingResult<()> {
+        if let Event::SessionChanged(_) = event {
+            *self = Self::default();
+        }
+        Ok(())
+    }
+}
+
+/// Map the brake state of the player car.
+/// `None` if the brake line pressures are not available.
+fn map_brakes(live_data: &LiveData) -> Option<BrakeState> {
+    Some(BrakeState {
+        line_pressure: [
+            Pressure::from_bar(live_data.l_fbrake_line_press?),
+            Pressure::from_bar(live_data.r_fbrake_line_press?),
+            Pressure::from_bar(live_data.l_rbrake_line_press?),
+            Pressure::from_bar(live_data.r_rbrake_line_press?),
+        ],
+        bias: live_data.dc_brake_bias,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{games::iracing::irsdk::live_data::LiveData, Pressure};
+
+    use super::map_brakes;
+
+    #[test]
+    fn brakes_from_line_pressures() {
+        let live_data = LiveData {
+            l_fbrake_line_press: Some(40.0),
+            r_fbrake_line_press: Some(41.0),
+            l_rbrake_line_press: Some(30.0),
+            r_rbrake_line_press: Some(31.0),
+            dc_brake_bias: Some(54.5),
+            ..Default::default()
+        };
+        let brakes = map_brakes(&live_data).unwrap();
+        assert_eq!(
+            brakes.line_pressure,
+            [
+                Pressure::from_bar(40.0),
+                Pressure::from_bar(41.0),
+                Pressure::from_bar(30.0),
+                Pressure::from_bar(31.0),
+            ]
+        );
+        assert_eq!(brakes.bias, Some(54.5));
+    }
+
+    #[test]
+    fn no_brakes_without_line_pressures() {
+        let live_data = LiveData {
+            dc_brake_bias: Some(54.5),
+            ..Default::default()
+        };
+        assert_eq!(map_brakes(&live_data), None);
+    }
+}
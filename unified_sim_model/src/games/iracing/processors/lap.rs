@@ -1,25 +1,46 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use tracing::info;
 
-use crate::{games::iracing::IRacingResult, model};
+use crate::{
+    games::iracing::{car_value, irsdk::defines::Flags, IRacingResult},
+    model,
+};
 
 use super::{IRacingProcessor, IRacingProcessorContext};
 
 pub struct LapProcessor {
     laps_before: HashMap<model::EntryId, i32>,
+    /// Entries that have seen a caution during their current lap.
+    caution_on_lap: HashSet<model::EntryId>,
 }
 
 impl LapProcessor {
     pub fn new() -> Self {
         Self {
             laps_before: HashMap::new(),
+            caution_on_lap: HashSet::new(),
         }
     }
 }
 
 impl IRacingProcessor for LapProcessor {
-    fn live_data(&mut self, _context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+    fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        let is_caution = context
+            .data
+            .live_data
+            .session_flags
+            .as_ref()
+            .is_some_and(|flags| {
+                flags.intersects(Flags::irsdk_caution | Flags::irsdk_cautionWaving)
+            });
+        if !is_caution {
+            return Ok(());
+        }
+        let Some(session) = context.model.current_session() else {
+            return Ok(());
+        };
+        self.caution_on_lap.extend(session.entries.keys());
         Ok(())
     }
 
@@ -47,7 +68,7 @@ impl IRacingProcessor for LapProcessor {
                 let Some(last_lap_time) = context.data.live_data
                     .car_idx_last_lap_time
                     .as_ref()
-                    .and_then(|lap_times| lap_times.get(entry_id.0 as usize)) else {continue};
+                    .and_then(|lap_times| car_value(lap_times, entry_id.0)) else {continue};
                 if last_lap_time.ms == -1000.0 {
                     (*last_lap_time, true)
                 } else {
@@ -61,8 +82,10 @@ impl IRacingProcessor for LapProcessor {
                 time: last_lap_time.into(),
                 splits: Vec::new().into(),
                 invalid: invalid.into(),
+                caution: self.caution_on_lap.remove(entry_id).into(),
                 driver_id: Some(driver.id),
                 entry_id: Some(entry.id),
+                driving_metrics: None,
             };
             entry.laps.push(lap.clone());
 
@@ -125,6 +148,7 @@ impl IRacingProcessor for LapProcessor {
         if let model::Event::SessionChanged(_) = event {
             // clear data and initialise it again.
             self.laps_before.clear();
+            self.caution_on_lap.clear();
             self.static_data(context)?;
         }
         Ok(())
@@ -0,0 +1,125 @@
+//! Decodes the pit service of the player car into the model.
+
+use crate::{
+    games::iracing::{
+        irsdk::{
+            defines::{PitSvFlags, PitSvStatus},
+            live_data::LiveData,
+        },
+        IRacingResult,
+    },
+    model::{EntryId, Event, PitServiceState, PitServiceStatus},
+    Pressure,
+};
+
+use super::{IRacingProcessor, IRacingProcessorContext};
+
+pub struct PitServiceProcessor;
+
+impl IRacingProcessor for PitServiceProcessor {
+    fn static_data(&mut self, _context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        Ok(())
+    }
+
+    fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        let live_data = &context.data.live_data;
+        let Some(player_car_idx) = live_data.player_car_idx else {
+            return Ok(());
+        };
+        let Some(pit_service) = decode_pit_service(live_data) else {
+            return Ok(());
+        };
+        let Some(session) = context.model.current_session_mut() else {
+            return Ok(());
+        };
+        if let Some(entry) = session.entries.get_mut(&EntryId(player_car_idx)) {
+            entry.pit_service.set(pit_service);
+        }
+        Ok(())
+    }
+
+    fn event(
+        &mut self,
+        _context: &mut IRacingProcessorContext,
+        _event: &Event,
+    ) -> IRacingResult<()> {
+        Ok(())
+    }
+}
+
+/// Decode the pit service of the player car.
+/// `None` if the game does not report the pit service.
+fn decode_pit_service(live_data: &LiveData) -> Option<PitServiceStatus> {
+    let flags = live_data.pit_sv_flags.as_ref()?;
+    let state = match live_data
+        .player_car_pit_sv_status
+        .map(PitSvStatus::from)
+        .unwrap_or(PitSvStatus::PitSvNone)
+    {
+        PitSvStatus::PitSvNone => PitServiceState::None,
+        PitSvStatus::PitSvInProgress => PitServiceState::InProgress,
+        PitSvStatus::PitSvComplete => PitServiceState::Complete,
+        PitSvStatus::PitSvTooFarLeft
+        | PitSvStatus::PitSvTooFarRight
+        | PitSvStatus::PitSvTooFarForward
+        | PitSvStatus::PitSvTooFarBack
+        | PitSvStatus::PitSvBadAngle => PitServiceState::NotInPitBox,
+        PitSvStatus::PitSvCantFixThat => PitServiceState::CannotRepair,
+    };
+    let pressure = |kpa: Option<f32>| Pressure::from_kpa(kpa.unwrap_or_default());
+    Some(PitServiceStatus {
+        state,
+        fueling: flags.contains(PitSvFlags::FuelFill),
+        fuel_to_add: live_data.pit_sv_fuel,
+        changing_tires: [
+            flags.contains(PitSvFlags::LFTireChange),
+            flags.contains(PitSvFlags::RFTireChange),
+            flags.contains(PitSvFlags::LRTireChange),
+            flags.contains(PitSvFlags::RRTireChange),
+        ],
+        fast_repair: flags.contains(PitSvFlags::FastRepair),
+        tire_pressures: [
+            pressure(live_data.pit_sv_lfp),
+            pressure(live_data.pit_sv_rfp),
+            pressure(live_data.pit_sv_lrp),
+            pressure(live_data.pit_sv_rrp),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        games::iracing::irsdk::{defines::PitSvFlags, live_data::LiveData},
+        model::PitServiceState,
+        Pressure,
+    };
+
+    use super::decode_pit_service;
+
+    #[test]
+    fn decode_flags() {
+        // Front tires, fuel and fast repair.
+        let live_data = LiveData {
+            pit_sv_flags: Some(PitSvFlags::from_bits_truncate(0x0053)),
+            player_car_pit_sv_status: Some(1),
+            pit_sv_lfp: Some(172.0),
+            pit_sv_rfp: Some(175.0),
+            pit_sv_fuel: Some(42.5),
+            ..Default::default()
+        };
+        let pit_service = decode_pit_service(&live_data).unwrap();
+        assert_eq!(pit_service.state, PitServiceState::InProgress);
+        assert!(pit_service.fueling);
+        assert_eq!(pit_service.fuel_to_add, Some(42.5));
+        assert_eq!(pit_service.changing_tires, [true, true, false, false]);
+        assert!(pit_service.fast_repair);
+        assert_eq!(pit_service.tire_pressures[0], Pressure::from_kpa(172.0));
+        assert_eq!(pit_service.tire_pressures[1], Pressure::from_kpa(175.0));
+    }
+
+    #[test]
+    fn no_flags() {
+        assert_eq!(decode_pit_service(&LiveData::default()), None);
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Instant;
+
+use crate::{
+    games::{common::pit_time::PitTimer, iracing::IRacingResult},
+    model::Event,
+};
+
+use super::{IRacingProcessor, IRacingProcessorContext};
+
+/// Measures the time entries spend in the pitlane.
+#[derive(Default)]
+pub struct PitTimeProcessor {
+    timer: PitTimer,
+}
+
+impl IRacingProcessor for PitTimeProcessor {
+    fn static_data(&mut self, _context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        Ok(())
+    }
+
+    fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        if let Some(session) = context.model.current_session_mut() {
+            self.timer.update(session, Instant::now());
+        }
+        Ok(())
+    }
+
+    fn event(
+        &mut self,
+        _context: &mut IRacingProcessorContext,
+        event: &Event,
+    ) -> IRacingResult<()> {
+        if let Event::SessionChanged(_) = event {
+            self.timer.clear();
+        }
+        Ok(())
+    }
+}
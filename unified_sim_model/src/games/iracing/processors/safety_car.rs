@@ -0,0 +1,241 @@
+//! Maps the caution flags and the pacing state of iRacing to the safety car state.
+//!
+//! iRacing does not have a single value for the safety car. Instead the state is derived
+//! from the session flags and the pace mode:
+//! - The safety car is deployed while the caution or caution waving flag is shown.
+//! - The pace mode is `NotPacing` while the cars are free to race. During the start of the race
+//!   the pace mode is one of the start modes. The pace car leads the field but this is not a
+//!   safety car.
+//! - After a caution the pace mode changes to one of the restart modes while the field is lined
+//!   up behind the pace car. The safety car stays deployed until the pace mode is back to
+//!   `NotPacing`, even if the caution flags are already gone on the final lap of the caution.
+//!
+//! The car the safety car is picking up is the car in the first row and the first line of the
+//! pacing order.
+//!
+//! The caution flags can flicker for a single update. The deployment of the safety car is
+//! debounced so that short changes do not generate events. The debounce starts over with
+//! every new session.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use tracing::info;
+
+use crate::{
+    games::{
+        common::debounce::Debounce,
+        iracing::{
+            irsdk::defines::{Flags, PaceMode},
+            IRacingResult,
+        },
+    },
+    model::{EntryId, Event, SafetyCarState, Session},
+};
+
+use super::{IRacingProcessor, IRacingProcessorContext};
+
+pub struct SafetyCarProcessor {
+    deployed: Debounce<bool>,
+}
+
+impl SafetyCarProcessor {
+    /// Create a new processor.
+    /// `hold` is the time a change in the deployment must be stable before it is accepted.
+    pub fn new(hold: Duration) -> Self {
+        Self {
+            deployed: Debounce::new(false, hold),
+        }
+    }
+
+    /// Update the safety car of the session with the state read from the game.
+    fn update(
+        &mut self,
+        session: &mut Session,
+        state: SafetyCarState,
+        now: Instant,
+        events: &mut VecDeque<Event>,
+    ) {
+        if self.deployed.update(state.deployed, now) {
+            if *self.deployed.value() {
+                info!("Safety car deployed");
+                events.push_back(Event::SafetyCarDeployed(session.id));
+            } else {
+                info!("Safety car in");
+                events.push_back(Event::SafetyCarIn(session.id));
+            }
+        }
+        let deployed = *self.deployed.value();
+        session.safety_car.set(SafetyCarState {
+            deployed,
+            picking_up: state.picking_up.filter(|_| deployed),
+        });
+    }
+}
+
+impl IRacingProcessor for SafetyCarProcessor {
+    fn static_data(&mut self, _context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        Ok(())
+    }
+
+    fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
+        let live_data = &context.data.live_data;
+        let state = map_safety_car_state(
+            live_data.session_flags.as_ref(),
+            live_data.pace_mode.map(PaceMode::from),
+            live_data.car_idx_pace_row.as_deref().unwrap_or_default(),
+            live_data.car_idx_pace_line.as_deref().unwrap_or_default(),
+        );
+
+        let Some(session) = context.model.current_session_mut() else {
+            return Ok(());
+        };
+        self.update(session, state, Instant::now(), &mut context.events);
+        Ok(())
+    }
+
+    fn event(
+        &mut self,
+        _context: &mut IRacingProcessorContext,
+        event: &Event,
+    ) -> IRacingResult<()> {
+        if let Event::SessionChanged(_) = event {
+            self.deployed.reset(false);
+        }
+        Ok(())
+    }
+}
+
+/// Map the session flags and the pacing state to the state of the safety car.
+fn map_safety_car_state(
+    flags: Option<&Flags>,
+    pace_mode: Option<PaceMode>,
+    pace_rows: &[i32],
+    pace_lines: &[i32],
+) -> SafetyCarState {
+    let caution = flags
+        .is_some_and(|flags| flags.intersects(Flags::irsdk_caution | Flags::irsdk_cautionWaving));
+    let restart = matches!(
+        pace_mode,
+        Some(PaceMode::PaceModeSingleFileRestart | PaceMode::PaceModeDoubleFileRestart)
+    );
+    let deployed = caution || restart;
+
+    let picking_up = deployed
+        .then(|| {
+            pace_rows
+                .iter()
+                .zip(pace_lines.iter())
+                .enumerate()
+                .filter(|(_, (row, line))| **row >= 0 && **line >= 0)
+                .min_by_key(|(_, (row, line))| (**row, **line))
+                .map(|(car_idx, _)| EntryId(car_idx as i32))
+        })
+        .flatten();
+
+    SafetyCarState {
+        deployed,
+        picking_up,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        games::iracing::irsdk::defines::{Flags, PaceMode},
+        model::{EntryId, SafetyCarState, Session},
+    };
+
+    use super::{map_safety_car_state, SafetyCarProcessor};
+
+    #[test]
+    fn single_frame_caution_is_ignored() {
+        let start = Instant::now();
+        let frame = Duration::from_millis(16);
+        let mut processor = SafetyCarProcessor::new(Duration::from_millis(500));
+        let mut session = Session::default();
+        let mut events = VecDeque::new();
+        let caution = SafetyCarState {
+            deployed: true,
+            picking_up: None,
+        };
+
+        processor.update(&mut session, caution, start, &mut events);
+        for frame_count in 1..40 {
+            processor.update(
+                &mut session,
+                SafetyCarState::default(),
+                start + frame * frame_count,
+                &mut events,
+            );
+        }
+        assert!(events.is_empty());
+        assert!(!session.safety_car.deployed);
+    }
+
+    #[test]
+    fn green_flag_racing() {
+        let state = map_safety_car_state(
+            Some(&Flags::irsdk_green),
+            Some(PaceMode::PaceModeNotPacing),
+            &[-1, -1, -1],
+            &[-1, -1, -1],
+        );
+        assert!(!state.deployed);
+        assert_eq!(state.picking_up, None);
+    }
+
+    #[test]
+    fn pace_car_at_the_start_is_not_a_safety_car() {
+        let state = map_safety_car_state(
+            Some(&Flags::empty()),
+            Some(PaceMode::PaceModeDoubleFileStart),
+            &[1, 0, 0],
+            &[0, 1, 0],
+        );
+        assert!(!state.deployed);
+        assert_eq!(state.picking_up, None);
+    }
+
+    #[test]
+    fn caution_deploys_safety_car() {
+        // The field has not lined up yet.
+        let state = map_safety_car_state(
+            Some(&Flags::irsdk_cautionWaving),
+            Some(PaceMode::PaceModeNotPacing),
+            &[-1, -1, -1],
+            &[-1, -1, -1],
+        );
+        assert!(state.deployed);
+        assert_eq!(state.picking_up, None);
+
+        // The car with index 2 is in the first row on the first line.
+        let state = map_safety_car_state(
+            Some(&Flags::irsdk_caution),
+            Some(PaceMode::PaceModeSingleFileRestart),
+            &[1, 0, 0],
+            &[0, 1, 0],
+        );
+        assert!(state.deployed);
+        assert_eq!(state.picking_up, Some(EntryId(2)));
+    }
+
+    #[test]
+    fn safety_car_stays_out_until_restart() {
+        let state = map_safety_car_state(
+            Some(&Flags::empty()),
+            Some(PaceMode::PaceModeDoubleFileRestart),
+            &[0, 1],
+            &[0, 0],
+        );
+        assert!(state.deployed);
+        assert_eq!(state.picking_up, Some(EntryId(0)));
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use tracing::warn;
 
 use crate::{
-    games::iracing::IRacingResult,
+    games::iracing::{spline_delta, IRacingResult},
     model::{EntryId, Event},
 };
 
@@ -35,23 +35,40 @@ impl IRacingProcessor for SpeedProcessor {
     }
 
     fn live_data(&mut self, context: &mut IRacingProcessorContext) -> IRacingResult<()> {
-        let Some(track_length) = context.data.static_data.weekend_info.track_length else {
-            warn!("No track length available");
-            return Ok(());
-        };
         let Some(update_number) = context.data.live_data.session_tick else {
             warn!("No session tick available");
             return Ok(());
         };
 
         let time_between = (update_number - self.last_update_number) as f32 / 60.0;
+        self.last_update_number = update_number;
+        if time_between <= 0.0 {
+            // No time has passed so no speed can be calculated.
+            return Ok(());
+        }
+
+        // The speed of the player car is known exactly.
+        let player_speed = context
+            .data
+            .live_data
+            .player_car_idx
+            .zip(context.data.live_data.speed);
 
         context.model.current_session_mut().map(|s| {
+            let Some(track_length) = s.known_track_length() else {
+                warn!("No track length available");
+                return;
+            };
             s.entries.iter_mut().for_each(|(entry_id, entry)| {
                 let last_data = self.entries.remove(entry_id).unwrap_or_default();
+                if !*entry.connected {
+                    // A car that is not on track does not move.
+                    entry.speed.estimate(0.0);
+                    return;
+                }
 
                 let distance_driven =
-                    (entry.spline_pos.as_ref() - last_data.lap_dist) * track_length.as_meters();
+                    spline_delta(last_data.lap_dist, *entry.spline_pos) * track_length.as_meters();
                 let mut speed = distance_driven / time_between;
 
                 // 120 m/s is roughly 430 kph. No car can reasonable travel that fast.
@@ -59,7 +76,15 @@ impl IRacingProcessor for SpeedProcessor {
                     speed = last_data.speed;
                 }
 
-                entry.speed.estimate(speed);
+                match player_speed {
+                    Some((player_car_idx, player_speed)) if entry_id.0 == player_car_idx => {
+                        speed = player_speed;
+                        entry.speed.set(speed);
+                    }
+                    _ => {
+                        entry.speed.estimate(speed);
+                    }
+                };
 
                 self.entries.insert(
                     *entry_id,
@@ -71,8 +96,6 @@ impl IRacingProcessor for SpeedProcessor {
             })
         });
 
-        self.last_update_number = update_number;
-
         Ok(())
     }
 
@@ -0,0 +1,306 @@
+//! Plays back a recording of the model.
+//!
+//! The frames of the recording are written into the model with the same timing they were
+//! recorded with. The playback speed can be changed and the playback can start at a later
+//! point in the recording. The events of each frame are added to the model in the order
+//! they happened. While the recording is played back the speed can be changed with
+//! [`AdapterCommand::SetPlaybackSpeed`] and the playback can be paused and resumed.
+//!
+//! Only available with the `bincode` feature.
+
+use std::{
+    io::Read,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{
+    model::{GameKind, Model},
+    recording::{RecordingError, RecordingReader},
+    AdapterCommand, AdapterError, GameAdapter, Time, UpdateEvent,
+};
+
+/// The slowest playback speed.
+pub const MIN_SPEED: f32 = 0.1;
+/// The fastest playback speed.
+pub const MAX_SPEED: f32 = 64.0;
+
+impl From<RecordingError> for AdapterError {
+    fn from(value: RecordingError) -> Self {
+        AdapterError::Playback(value)
+    }
+}
+
+/// The adapter that plays back a recording.
+pub struct PlaybackAdapter<R: Read> {
+    /// The recording to play back.
+    reader: Option<R>,
+    /// The playback speed.
+    speed: f32,
+    /// The session time to start the playback at.
+    seek: Option<Time>,
+}
+
+impl<R: Read> PlaybackAdapter<R> {
+    /// Create a new playback adapter for a recording.
+    ///
+    /// The header of the recording is read when the adapter starts.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(reader),
+            speed: 1.0,
+            seek: None,
+        }
+    }
+
+    /// Set the playback speed.
+    ///
+    /// A speed of 2.0 plays the recording at double speed, 0.5 at half speed.
+    /// The speed is clamped to the range of [`MIN_SPEED`] to [`MAX_SPEED`].
+    /// Default is 1.0.
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = clamp_speed(speed);
+        self
+    }
+
+    /// Start the playback at a session time.
+    ///
+    /// The frames before the elapsed time of the current session reaches `time` are skipped
+    /// without waiting. Frames of sessions without an elapsed time are skipped as well.
+    /// The events of the skipped frames are not added to the model.
+    pub fn seek(mut self, time: Time) -> Self {
+        self.seek = Some(time);
+        self
+    }
+}
+
+/// Clamp a playback speed to the supported range. Not a number plays at normal speed.
+fn clamp_speed(speed: f32) -> f32 {
+    if speed.is_nan() {
+        1.0
+    } else {
+        speed.clamp(MIN_SPEED, MAX_SPEED)
+    }
+}
+
+impl<R: Read> GameAdapter for PlaybackAdapter<R> {
+    fn run(
+        &mut self,
+        model: Arc<RwLock<Model>>,
+        command_rx: mpsc::Receiver<AdapterCommand>,
+        update_event: UpdateEvent,
+    ) -> Result<(), AdapterError> {
+        let Some(reader) = self.reader.take() else {
+            return Ok(());
+        };
+        let mut reader = RecordingReader::new(reader)?;
+
+        // The time in the recording and the instant the playback started from.
+        let mut start: Option<(Duration, Instant)> = None;
+        let mut paused: Option<Duration> = None;
+        let mut next_event_id = 0;
+        let mut seek = self.seek;
+        while let Some(frame) = reader.read_frame()? {
+            if let Some(seek_time) = seek {
+                let reached = frame
+                    .model
+                    .current_session()
+                    .and_then(|session| session.time_elapsed())
+                    .is_some_and(|time| time.ms >= seek_time.ms);
+                if !reached {
+                    next_event_id = frame.model.next_event_id();
+                    continue;
+                }
+                seek = None;
+            }
+
+            // Wait until the frame is due. The time is measured from the start of the playback
+            // so that small delays do not add up over the recording.
+            loop {
+                let (start_time, start_instant) =
+                    *start.get_or_insert((frame.time, Instant::now()));
+                let command = match paused {
+                    // A paused playback only waits for the next command.
+                    Some(_) => command_rx
+                        .recv()
+                        .map_err(|_| RecvTimeoutError::Disconnected),
+                    None => {
+                        let due = start_instant
+                            + frame.time.saturating_sub(start_time).div_f32(self.speed);
+                        command_rx.recv_timeout(due.saturating_duration_since(Instant::now()))
+                    }
+                };
+                // The point in the recording that is currently played.
+                let position = || start_time + start_instant.elapsed().mul_f32(self.speed);
+                match command {
+                    Ok(AdapterCommand::Close) => return Ok(()),
+                    Ok(AdapterCommand::SetPlaybackSpeed(speed)) => {
+                        // Continue from the current point in the recording with the new speed.
+                        if paused.is_none() {
+                            start = Some((position(), Instant::now()));
+                        }
+                        self.speed = clamp_speed(speed);
+                    }
+                    Ok(AdapterCommand::Pause) => {
+                        if paused.is_none() {
+                            paused = Some(position());
+                        }
+                    }
+                    Ok(AdapterCommand::Resume) => {
+                        if let Some(position) = paused.take() {
+                            start = Some((position, Instant::now()));
+                        }
+                    }
+                    Ok(_) => (),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    // A closed channel cannot send a `Close` command anymore.
+                    Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                }
+            }
+
+            if let Ok(mut model) = model.write() {
+                let frame_next_event_id = frame.model.next_event_id();
+                model.apply_snapshot(frame.model, next_event_id);
+                model.game = GameKind::Replay;
+                model.last_update = Some(Instant::now());
+                next_event_id = frame_next_event_id;
+            }
+            update_event.frame(0);
+            update_event.trigger();
+        }
+
+        Ok(())
+    }
+
+    fn game_kind(&self) -> GameKind {
+        GameKind::Replay
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{mpsc, Arc, RwLock},
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        model::{Event, Model, Session, SessionId, Value},
+        recording::RecordingWriter,
+        AdapterCommand, GameAdapter, Time, UpdateEvent,
+    };
+
+    use super::PlaybackAdapter;
+
+    /// A recording with a frame every 100ms and one event in each frame.
+    fn recording(frame_count: i32) -> Vec<u8> {
+        let mut model = Model::default();
+        let id = model.add_session(Session {
+            session_time: Value::new(Time::from(60_000)),
+            ..Default::default()
+        });
+        model.current_session = Some(id);
+
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        for frame in 0..frame_count {
+            let session = model.current_session_mut().unwrap();
+            session.time_remaining.set(Time::from(60_000 - frame * 100));
+            model.push_event(Event::SessionChanged(SessionId(frame as usize)));
+            writer
+                .write_frame(Duration::from_millis(frame as u64 * 100), &model)
+                .unwrap();
+        }
+        writer.into_inner()
+    }
+
+    fn play(adapter: PlaybackAdapter<&'static [u8]>) -> Model {
+        play_with_commands(adapter, Vec::new())
+    }
+
+    /// Play back a recording with commands that are sent before the playback starts.
+    fn play_with_commands(
+        mut adapter: PlaybackAdapter<&'static [u8]>,
+        commands: Vec<AdapterCommand>,
+    ) -> Model {
+        let model = Arc::new(RwLock::new(Model::default()));
+        let (command_tx, command_rx) = mpsc::channel();
+        for command in commands {
+            assert!(command_tx.send(command).is_ok());
+        }
+        let result = adapter.run(model.clone(), command_rx, UpdateEvent::new());
+        assert!(result.is_ok());
+        Arc::into_inner(model).unwrap().into_inner().unwrap()
+    }
+
+    fn event_ids(model: &Model) -> Vec<usize> {
+        model
+            .recent_events(0)
+            .iter()
+            .map(|event| match event.event {
+                Event::SessionChanged(id) => id.0,
+                _ => panic!("Unexpected event"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn play_back_faster() {
+        let data: &'static [u8] = recording(5).leak();
+        let start = Instant::now();
+        let model = play(PlaybackAdapter::new(data).with_speed(4.0));
+        let elapsed = start.elapsed();
+        // 400ms of recording at four times the speed.
+        assert!(elapsed >= Duration::from_millis(100), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(400), "{elapsed:?}");
+        assert_eq!(event_ids(&model), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn change_speed_with_command() {
+        let data: &'static [u8] = recording(5).leak();
+        let start = Instant::now();
+        let model = play_with_commands(
+            PlaybackAdapter::new(data),
+            vec![AdapterCommand::SetPlaybackSpeed(8.0)],
+        );
+        let elapsed = start.elapsed();
+        // 400ms of recording at eight times the speed.
+        assert!(elapsed < Duration::from_millis(250), "{elapsed:?}");
+        assert_eq!(event_ids(&model), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let data: &'static [u8] = recording(3).leak();
+        let start = Instant::now();
+        let model = play_with_commands(
+            PlaybackAdapter::new(data),
+            vec![AdapterCommand::Pause, AdapterCommand::Resume],
+        );
+        let elapsed = start.elapsed();
+        // The playback continues where it was paused.
+        assert!(elapsed >= Duration::from_millis(200), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(400), "{elapsed:?}");
+        assert_eq!(event_ids(&model), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn speed_is_clamped() {
+        let data: &'static [u8] = recording(1).leak();
+        assert_eq!(PlaybackAdapter::new(data).with_speed(1000.0).speed, 64.0);
+        assert_eq!(PlaybackAdapter::new(data).with_speed(0.0).speed, 0.1);
+        assert_eq!(PlaybackAdapter::new(data).with_speed(f32::NAN).speed, 1.0);
+    }
+
+    #[test]
+    fn seek_skips_frames() {
+        let data: &'static [u8] = recording(5).leak();
+        let start = Instant::now();
+        let model = play(PlaybackAdapter::new(data).seek(Time::from(300)));
+        assert!(start.elapsed() < Duration::from_millis(300));
+        assert_eq!(event_ids(&model), vec![3, 4]);
+    }
+}
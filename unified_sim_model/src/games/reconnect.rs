@@ -0,0 +1,236 @@
+//! An adapter that reconnects to the game when the connection fails.
+//!
+//! The adapter wraps the adapter of a game. When the game adapter finishes without a
+//! `Close` command, for example because the game is not running yet or was closed, the
+//! adapter waits for a delay and runs the game adapter again. The delay grows with each
+//! failed attempt as configured by a [`RetryConfig`].
+//!
+//! While waiting for the game the connection state of the model is
+//! [`Connecting`](ConnectionState::Connecting).
+
+use std::{
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, RwLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+use crate::{
+    model::{ConnectionState, GameKind, Model},
+    AdapterCommand, AdapterError, GameAdapter, UpdateEvent,
+};
+
+/// How often the adapter checks if the game adapter has finished.
+const FINISHED_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The delays between the attempts to connect to the game.
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// The delay after the first failed attempt.
+    pub initial_delay: Duration,
+    /// The longest delay between two attempts.
+    pub max_delay: Duration,
+    /// The delay is multiplied by this factor after every failed attempt.
+    pub backoff: f32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            backoff: 2.0,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// The delay after an attempt that followed a delay of `delay`.
+    fn next_delay(&self, delay: Duration) -> Duration {
+        delay
+            .mul_f32(self.backoff.max(1.0))
+            .min(self.max_delay.max(self.initial_delay))
+    }
+}
+
+/// An adapter that runs a game adapter again when it finishes.
+///
+/// A game adapter that returns an error increases the delay to the next attempt.
+/// A game adapter that finishes without an error was connected to the game. In that
+/// case the next attempt waits for the initial delay again.
+/// The adapter only finishes when it receives a `Close` command. If the command arrives
+/// while the game adapter runs, the result of the game adapter is returned.
+pub struct ReconnectingAdapter<G> {
+    game: G,
+    config: RetryConfig,
+}
+
+impl<G> ReconnectingAdapter<G> {
+    /// Create a new adapter that reconnects the game adapter.
+    pub fn new(game: G, config: RetryConfig) -> Self {
+        Self { game, config }
+    }
+}
+
+/// The reason the adapter stopped waiting.
+enum Wait {
+    /// The delay has passed.
+    Elapsed,
+    /// The adapter should close.
+    Close,
+}
+
+impl<G: GameAdapter + Send> GameAdapter for ReconnectingAdapter<G> {
+    fn run(
+        &mut self,
+        model: Arc<RwLock<Model>>,
+        command_rx: mpsc::Receiver<AdapterCommand>,
+        update_event: UpdateEvent,
+    ) -> Result<(), AdapterError> {
+        if let Ok(mut model) = model.write() {
+            model.set_connection_state(ConnectionState::Connecting);
+        }
+        let mut delay = self.config.initial_delay;
+        let result = loop {
+            let (result, close) = self.run_game(&model, &command_rx, &update_event);
+            if close {
+                break result;
+            }
+            match &result {
+                Ok(()) => {
+                    info!(
+                        "Game disconnected. Reconnecting in {:?}",
+                        self.config.initial_delay
+                    );
+                    delay = self.config.initial_delay;
+                }
+                Err(e) => {
+                    warn!("Connection to the game failed: {e}. Reconnecting in {delay:?}");
+                }
+            }
+
+            if let Ok(mut model) = model.write() {
+                model.set_connection_state(ConnectionState::Connecting);
+            }
+            if let Wait::Close = wait(&command_rx, delay) {
+                break Ok(());
+            }
+            if result.is_err() {
+                delay = self.config.next_delay(delay);
+            }
+        };
+
+        if let Ok(mut model) = model.write() {
+            model.set_connection_state(ConnectionState::Disconnected);
+        }
+        result
+    }
+
+    fn game_kind(&self) -> GameKind {
+        self.game.game_kind()
+    }
+}
+
+impl<G: GameAdapter + Send> ReconnectingAdapter<G> {
+    /// Run the game adapter once.
+    ///
+    /// The game adapter runs in its own thread while the commands are forwarded to it.
+    /// Returns the result of the game adapter and if the adapter should close.
+    fn run_game(
+        &mut self,
+        model: &Arc<RwLock<Model>>,
+        command_rx: &mpsc::Receiver<AdapterCommand>,
+        update_event: &UpdateEvent,
+    ) -> (Result<(), AdapterError>, bool) {
+        let (game_tx, game_rx) = mpsc::channel();
+        let game = &mut self.game;
+        let model = model.clone();
+        let update_event = update_event.clone();
+        thread::scope(|scope| {
+            let handle = scope.spawn(move || game.run(model, game_rx, update_event));
+            let mut close = false;
+            while !handle.is_finished() {
+                match command_rx.recv_timeout(FINISHED_POLL_INTERVAL) {
+                    Ok(command) => {
+                        close |= matches!(command, AdapterCommand::Close);
+                        // The game adapter may have finished in the meantime.
+                        _ = game_tx.send(command);
+                    }
+                    Err(RecvTimeoutError::Timeout) => (),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        // A closed channel cannot send a `Close` command anymore.
+                        _ = game_tx.send(AdapterCommand::Close);
+                        close = true;
+                        break;
+                    }
+                }
+            }
+            let result = handle.join().expect("Should be able to join thread");
+            (result, close)
+        })
+    }
+}
+
+/// Wait for the delay while still reacting to commands.
+/// Other commands than `Close` have no effect without a game.
+fn wait(command_rx: &mpsc::Receiver<AdapterCommand>, delay: Duration) -> Wait {
+    let deadline = Instant::now() + delay;
+    loop {
+        match command_rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            // A closed channel cannot send a `Close` command anymore.
+            Ok(AdapterCommand::Close) | Err(RecvTimeoutError::Disconnected) => return Wait::Close,
+            Ok(_) => (),
+            Err(RecvTimeoutError::Timeout) => return Wait::Elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::mpsc,
+        time::{Duration, Instant},
+    };
+
+    use crate::AdapterCommand;
+
+    use super::{wait, RetryConfig, Wait};
+
+    #[test]
+    fn delay_grows_to_max() {
+        let config = RetryConfig {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(5),
+            backoff: 2.0,
+        };
+        assert_eq!(
+            config.next_delay(Duration::from_secs(1)),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            config.next_delay(Duration::from_secs(4)),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn close_while_waiting() {
+        let (command_tx, command_rx) = mpsc::channel();
+        assert!(command_tx.send(AdapterCommand::Pause).is_ok());
+        assert!(command_tx.send(AdapterCommand::Close).is_ok());
+        let start = Instant::now();
+        assert!(matches!(
+            wait(&command_rx, Duration::from_secs(60)),
+            Wait::Close
+        ));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(matches!(
+            wait(&command_rx, Duration::from_millis(1)),
+            Wait::Elapsed
+        ));
+    }
+}
@@ -1,16 +1,29 @@
-use games::{acc, dummy::DummyAdapter, iracing};
-use model::{Camera, EntryId};
+use games::{
+    acc,
+    auto::AutoAdapter,
+    demo::{DemoAdapter, DemoConfig},
+    dummy::DummyAdapter,
+    iracing,
+    reconnect::{ReconnectingAdapter, RetryConfig},
+};
+use model::{Camera, EntryId, GameKind, TimedEvent};
 use thiserror::Error;
-use tracing::warn;
+use tracing::{error, warn};
 
 use std::{
-    sync::{mpsc, Arc, Condvar, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering},
+        mpsc, Arc, Condvar, Mutex, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    },
     thread::{self, JoinHandle},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 pub mod games;
 pub mod model;
+#[cfg(feature = "bincode")]
+pub mod recording;
 pub mod types;
 
 use crate::model::Model;
@@ -31,6 +44,8 @@ pub trait GameAdapter {
     /// * `command_rx` The adapter must react to adapter commands published on this channel
     /// to the best of its abilities. It is not expected for an adapter to correctly react to
     /// every command since not every game provides the interface to implement every command.
+    /// An [`Adapter`] keeps this channel open while the game adapter runs, so the game adapter
+    /// is only closed by a `Close` command or when it returns by itself.
     ///
     /// * `update_tx` The adapter should publish update events on this channel to allow
     /// a user of the adapter to react to changes in the model without having to scan for changes themself.
@@ -41,6 +56,14 @@ pub trait GameAdapter {
         command_rx: mpsc::Receiver<AdapterCommand>,
         update_event: UpdateEvent,
     ) -> Result<(), AdapterError>;
+
+    /// The game this adapter connects to.
+    ///
+    /// Stored in [`Model::game`] when the adapter is created. An adapter that only
+    /// knows the game once it is connected updates the model itself.
+    fn game_kind(&self) -> GameKind {
+        GameKind::Unknown
+    }
 }
 
 /// A error with the game adapter.
@@ -50,8 +73,17 @@ pub enum AdapterError {
     ACC(acc::AccConnectionError),
     #[error("IRacing connection error: {0}")]
     IRacing(iracing::IRacingError),
+    #[cfg(feature = "bincode")]
+    #[error("Playback error: {0}")]
+    Playback(recording::RecordingError),
 }
 
+/// The number of events an event receiver can hold before new events are dropped.
+pub const EVENT_QUEUE_CAPACITY: usize = 1024;
+
+/// A receiver for the events of an adapter.
+pub type EventReceiver = mpsc::Receiver<TimedEvent>;
+
 /// The result of an adapter after it has finished.
 /// If the adapter had to finish because of an error the error
 /// is reported in the `Err` variant.
@@ -64,6 +96,19 @@ pub type AdapteResult = Result<(), AdapterError>;
 /// the game. The adapter is then the window to interact with the game connection.
 /// Should all instances of an adapter be dropped or destroyed, the game connection
 /// that is associated with that adapter is also closed.
+///
+/// ### Shutdown:
+/// When the adapter is closed, either by a `Close` command or because of an error,
+/// the shutdown happens in this order:
+/// 1. The game adapter returns from its `run` method and releases all game resources.
+/// 2. The update event is disabled. Any thread blocked in `wait_for_update` wakes up
+/// with `WaitError::EventDisabled` and every later call returns that error immediately.
+/// 3. The model stays readable. It is shared with every clone of the adapter and is only
+/// freed once all clones have been dropped.
+///
+/// When the last clone of an adapter is dropped a `Close` command is sent to the game adapter
+/// and its thread is detached. The game connection then shuts down in the background.
+/// This can be disabled with [`Adapter::set_close_on_drop`].
 #[derive(Clone)]
 pub struct Adapter {
     /// The data model that is shared with the game adapter.
@@ -74,24 +119,53 @@ pub struct Adapter {
     command_tx: mpsc::Sender<AdapterCommand>,
     /// An event that is triggered when new data is available.
     update_event: UpdateEvent,
+    /// Closes the game adapter when the last clone of this adapter is dropped.
+    close_guard: Arc<CloseGuard>,
+    /// Signals that the thread of the game adapter has finished.
+    finish_signal: Arc<FinishSignal>,
+}
+
+/// Options for an adapter that apply to every game.
+#[derive(Debug, Default, Clone)]
+pub struct AdapterOptions {
+    /// The unit system that is stored in [`Model::unit_system`].
+    ///
+    /// The typed values in the model are not changed by this option. Default is metric.
+    pub unit_system: UnitSystem,
 }
 
 impl Adapter {
     /// Create a new adapter with a game adapter.
     pub fn new(game: impl GameAdapter + Send + 'static) -> Self {
-        let model = Arc::new(RwLock::new(Model::default()));
+        Self::with_options(game, AdapterOptions::default())
+    }
+
+    /// Create a new adapter with a game adapter and options.
+    pub fn with_options(game: impl GameAdapter + Send + 'static, options: AdapterOptions) -> Self {
+        let mut model = Model::default();
+        model.unit_system = options.unit_system;
+        model.game = game.game_kind();
+        let model = Arc::new(RwLock::new(model));
         let (command_tx, command_rx) = mpsc::channel();
         let update_event = UpdateEvent::new();
+        let finish_signal = Arc::new(FinishSignal::default());
         Self {
             model: ReadOnlyModel::new(model.clone()),
             join_handle: Arc::new(RwLock::new(Some(Self::spawn(
                 game,
                 model,
+                command_tx.clone(),
                 command_rx,
                 update_event.clone(),
+                finish_signal.clone(),
             )))),
+            close_guard: Arc::new(CloseGuard {
+                command_tx: command_tx.clone(),
+                close_on_drop: AtomicBool::new(true),
+            }),
             command_tx,
             update_event,
+            finish_signal,
         }
     }
     /// Create a new dummy adapter.
@@ -100,14 +174,81 @@ impl Adapter {
         Self::new(DummyAdapter::default())
     }
 
+    /// Create a new demo adapter.
+    /// The adapter simulates a full race with generated entries without a running game.
+    pub fn new_demo(config: DemoConfig) -> Adapter {
+        Self::new(DemoAdapter::new(config))
+    }
+
     /// Create a new Assetto Corsa Competizione adapter.
     pub fn new_acc() -> Adapter {
-        Self::new(acc::AccAdapter {})
+        Self::new(acc::AccAdapter::default())
     }
 
     /// Create a new iRacing adapter.
     pub fn new_iracing() -> Adapter {
-        Self::new(iracing::IRacingAdapter {})
+        Self::new(iracing::IRacingAdapter::default())
+    }
+
+    /// Create a new Assetto Corsa Competizione adapter that reconnects to the game.
+    ///
+    /// Instead of finishing when the game is not running or disconnects, the adapter
+    /// tries to connect again after a delay. It only finishes with a `Close` command.
+    /// The state of the connection is shown in [`Model::connection_state`].
+    pub fn new_acc_reconnecting(config: RetryConfig) -> Adapter {
+        Self::new(ReconnectingAdapter::new(acc::AccAdapter::default(), config))
+    }
+
+    /// Create a new iRacing adapter that reconnects to the game.
+    ///
+    /// Behaves like [`Adapter::new_acc_reconnecting`].
+    pub fn new_iracing_reconnecting(config: RetryConfig) -> Adapter {
+        Self::new(ReconnectingAdapter::new(
+            iracing::IRacingAdapter::default(),
+            config,
+        ))
+    }
+
+    /// Create a new adapter that plays back a recording.
+    ///
+    /// Only available with the `bincode` feature.
+    #[cfg(feature = "bincode")]
+    pub fn new_playback(reader: impl std::io::Read + Send + 'static) -> Adapter {
+        Self::new(games::playback::PlaybackAdapter::new(reader))
+    }
+
+    /// Create a new adapter that plays back a recording from a file.
+    ///
+    /// Returns an error if the file cannot be opened. Errors while reading the
+    /// recording are returned when the adapter finishes.
+    /// Only available with the `bincode` feature.
+    #[cfg(feature = "bincode")]
+    pub fn new_replay(path: impl AsRef<std::path::Path>) -> std::io::Result<Adapter> {
+        let file = std::fs::File::open(path)?;
+        Ok(Self::new_playback(std::io::BufReader::new(file)))
+    }
+
+    /// Create a new adapter that connects to the first game that is available.
+    ///
+    /// The games are probed in this order:
+    /// 1. Assetto Corsa Competizione
+    /// 2. iRacing
+    ///
+    /// If no game is running the adapter keeps probing until a game is started.
+    /// While probing the connection state of the model is
+    /// [`Connecting`](model::ConnectionState::Connecting). A `Close` command is respected
+    /// while probing and closes the adapter without connecting to a game.
+    pub fn new_auto() -> Adapter {
+        Self::new(AutoAdapter::default())
+    }
+
+    /// Returns the game the adapter connects to.
+    ///
+    /// Same as [`Model::game`].
+    pub fn game_kind(&self) -> GameKind {
+        self.model
+            .read()
+            .map_or(GameKind::Unknown, |model| model.game)
     }
 
     /// Returns `true` if the adapter has finised its connection to the game
@@ -131,13 +272,61 @@ impl Adapter {
             .map(|join_handle| join_handle.join().expect("Should be able to join thread"))
     }
 
+    /// Joins the adapter thread if it finishes before the timeout expires.
+    ///
+    /// Returns `None` if the thread has not finished in time. Send a `Close` command
+    /// first to ask the game adapter to finish. Like [`Adapter::join`] the result is only
+    /// returned the first time.
+    pub fn join_timeout(&mut self, timeout: Duration) -> Option<Result<(), AdapterError>> {
+        if !self.finish_signal.wait_timeout(timeout) {
+            return None;
+        }
+        self.join()
+    }
+
+    /// Set if the game adapter is closed when the last clone of this adapter is dropped.
+    ///
+    /// This is enabled by default. When disabled, the game adapter keeps running in the
+    /// background after the last clone was dropped until the game itself disconnects.
+    /// The model can still be read through a clone of [`Adapter::model`].
+    ///
+    /// Without a clone there is no way to send a `Close` command anymore. A game adapter
+    /// that never returns by itself, like the demo and the dummy adapter, then keeps its
+    /// thread and the model alive until the process exits.
+    /// This setting is shared with all clones of this adapter.
+    pub fn set_close_on_drop(&self, close_on_drop: bool) {
+        self.close_guard
+            .close_on_drop
+            .store(close_on_drop, Ordering::Relaxed);
+    }
+
     /// Clears the current events from the model.
+    ///
+    /// Clearing the events removes them for every other reader of the model as well.
+    /// Read the events with [`Model::recent_events`] and a watermark instead.
+    #[deprecated(note = "read the events with `Model::recent_events` and a watermark instead")]
     pub fn clear_events(&mut self) -> Result<(), PoisonError<RwLockWriteGuard<'_, Model>>> {
         let mut model = self.model.model.write()?;
-        model.events.clear();
+        model.clear_events();
         Ok(())
     }
 
+    /// Subscribe to the events of the adapter.
+    ///
+    /// The receiver gets a copy of every event the adapter adds to the model. Each subscriber
+    /// receives all events independently of other subscribers and of `clear_events`.
+    ///
+    /// The queue of the receiver holds up to [`EVENT_QUEUE_CAPACITY`] events. If the receiver
+    /// does not keep up and the queue is full, new events are dropped for this receiver
+    /// until there is space in the queue again. Dropping the receiver unsubscribes it.
+    pub fn subscribe_events(
+        &self,
+    ) -> Result<EventReceiver, PoisonError<RwLockWriteGuard<'_, Model>>> {
+        let (sender, receiver) = mpsc::sync_channel(EVENT_QUEUE_CAPACITY);
+        self.model.model.write()?.add_event_subscriber(sender);
+        Ok(receiver)
+    }
+
     /// Send a adapter command to the game.
     ///
     /// There is no guarantee that a command is received by the game and that there will
@@ -154,6 +343,8 @@ impl Adapter {
     /// Block this thread until a new update is available in the model.
     ///
     /// Returns a error if the event source is closed before an event is triggered.
+    /// The event source is closed when the adapter shuts down. The model can still be read
+    /// after this error was returned.
     pub fn wait_for_update(&self) -> Result<(), WaitError> {
         self.update_event.wait()
     }
@@ -165,16 +356,55 @@ impl Adapter {
         self.update_event.wait_timeout(duration)
     }
 
+    /// Subscribe to the updates of the model.
+    ///
+    /// Every subscription waits for updates independently. Use this when multiple
+    /// threads wait for updates and none of them should miss an update.
+    pub fn subscribe_updates(&self) -> UpdateSubscription {
+        self.update_event.subscribe()
+    }
+
+    /// Returns `true` if a new update is available since the last call to this method.
+    ///
+    /// Does not block. This is useful for a render loop that runs at its own pace and only
+    /// needs to know if the model has changed since the last frame. Every clone of
+    /// the adapter keeps track of its own calls.
+    pub fn take_update(&self) -> bool {
+        self.update_event.check_and_reset()
+    }
+
+    /// Returns the current version of the model.
+    ///
+    /// The version is increased every time the game adapter publishes an update to the model.
+    /// Comparing it to a previously read version is a cheap way to check if the model
+    /// has changed without locking it or waiting for an update.
+    pub fn model_version(&self) -> u64 {
+        self.update_event.version()
+    }
+
+    /// Returns statistics about the frames and updates of the adapter.
+    ///
+    /// The statistics are kept outside of the model and reading them does not lock the model.
+    pub fn stats(&self) -> AdapterStats {
+        self.update_event.stats.read()
+    }
+
     fn spawn(
         mut game: impl GameAdapter + Send + 'static,
         model: Arc<RwLock<Model>>,
+        command_tx: mpsc::Sender<AdapterCommand>,
         command_rx: mpsc::Receiver<AdapterCommand>,
         update_event: UpdateEvent,
+        finish_signal: Arc<FinishSignal>,
     ) -> JoinHandle<Result<(), AdapterError>> {
         update_event.enable();
         thread::Builder::new()
             .name("Acc connection".into())
             .spawn(move || {
+                let _finish_guard = FinishGuard(finish_signal);
+                // Keep the command channel open so that the game adapter is only closed
+                // by a `Close` command and not by dropping the adapter.
+                let _command_tx = command_tx;
                 let result = game.run(model, command_rx, update_event.clone());
                 update_event.disable();
                 result
@@ -183,18 +413,78 @@ impl Adapter {
     }
 }
 
+/// Sends a `Close` command to the game adapter when it is dropped.
+///
+/// Every clone of an adapter shares the same guard, so the guard is only dropped
+/// together with the last clone.
+struct CloseGuard {
+    command_tx: mpsc::Sender<AdapterCommand>,
+    close_on_drop: AtomicBool,
+}
+
+impl Drop for CloseGuard {
+    fn drop(&mut self) {
+        if self.close_on_drop.load(Ordering::Relaxed) {
+            // The game adapter may have finished already.
+            _ = self.command_tx.send(AdapterCommand::Close);
+        }
+    }
+}
+
+/// Signals that the thread of a game adapter has finished.
+///
+/// A `JoinHandle` cannot be joined with a timeout. Waiting for this signal first
+/// makes sure that the join does not block.
+#[derive(Default)]
+struct FinishSignal {
+    finished: Mutex<bool>,
+    var: Condvar,
+}
+
+impl FinishSignal {
+    fn set(&self) {
+        *self.finished.lock().unwrap() = true;
+        self.var.notify_all();
+    }
+
+    /// Wait for the signal. Returns `true` if the signal was set before the timeout expired.
+    fn wait_timeout(&self, timeout: Duration) -> bool {
+        let finished = self.finished.lock().unwrap();
+        let (finished, _) = self
+            .var
+            .wait_timeout_while(finished, timeout, |finished| !*finished)
+            .unwrap();
+        *finished
+    }
+}
+
+/// Sets the finish signal when it is dropped.
+/// The signal is set even if the game adapter panics.
+struct FinishGuard(Arc<FinishSignal>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.set();
+    }
+}
+
 /// A readonly view on a model.
 /// To read the model it must first be locked. Locking follows all the same
 /// rules as a `read` method in `RwLock`.
 #[derive(Clone)]
 pub struct ReadOnlyModel {
     model: Arc<RwLock<Model>>,
+    /// Set once a poisoned lock was recovered to only log the first time.
+    poison_logged: Arc<AtomicBool>,
 }
 
 impl ReadOnlyModel {
     /// Creates a new read only model.
     fn new(model: Arc<RwLock<Model>>) -> Self {
-        Self { model }
+        Self {
+            model,
+            poison_logged: Arc::new(AtomicBool::new(false)),
+        }
     }
     /// Locks the underlying `RwLock` and returns a read only view to the model.
     pub fn read(
@@ -210,9 +500,30 @@ impl ReadOnlyModel {
             .read()
             .expect("The model should not be poisoned.")
     }
+
+    /// Locks the underlying `RwLock` and returns a read only view to the model.
+    /// Recovers the model if the `RwLock` is poisoned.
+    ///
+    /// The lock is poisoned when the game adapter panics while writing to the model.
+    /// In that case the model may be left half way through an update. For example an entry
+    /// may have completed a lap that is not yet part of its laps, or the positions of
+    /// two entries may be the same. Since the model is plain data, reading it is still
+    /// safe, but the data may not be consistent until the adapter writes the next update.
+    /// A poisoned lock is logged once. Use [`ReadOnlyModel::read`] to handle the error
+    /// instead.
+    pub fn read_recover(&self) -> RwLockReadGuard<'_, Model> {
+        self.model.read().unwrap_or_else(|poisoned| {
+            if !self.poison_logged.swap(true, Ordering::Relaxed) {
+                error!("The model lock is poisoned. The model may contain inconsistent data.");
+            }
+            poisoned.into_inner()
+        })
+    }
 }
 
 /// Commands for the adapter to execute.
+///
+/// An adapter ignores the commands it cannot execute.
 pub enum AdapterCommand {
     /// Close the adapter and return the thread.
     Close,
@@ -220,22 +531,140 @@ pub enum AdapterCommand {
     FocusOnCar(EntryId),
     /// Change the camera.
     ChangeCamera(Camera),
+    /// Change how often the adapter reads new data from the game.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available. The update interval is agreed on when the adapter connects to the game.
+    /// - **iRacing:**
+    /// The game is read at most once per interval. An interval shorter than the update rate
+    /// of the game has no effect. The interval is limited to [`MAX_POLL_RATE`].
+    /// - **Demo and dummy:**
+    /// The interval between two updates of the model. Limited to [`MAX_POLL_RATE`].
+    SetPollRate(Duration),
+    /// Change the speed of a playback.
+    /// A speed of 2.0 plays the recording twice as fast. The speed is clamped between 0.1 and 64.
+    ///
+    /// Only the playback adapter executes this command.
+    SetPlaybackSpeed(f32),
+    /// Pause a playback.
+    ///
+    /// Only the playback adapter executes this command.
+    Pause,
+    /// Resume a paused playback.
+    ///
+    /// Only the playback adapter executes this command.
+    Resume,
     /// Game specific adapter commands.
     Game(GameAdapterCommand),
 }
 
+/// The longest interval that can be set with [`AdapterCommand::SetPollRate`].
+pub const MAX_POLL_RATE: Duration = Duration::from_secs(5);
+
 /// Game specific adapter commands.
 pub enum GameAdapterCommand {
     /// Commands for the dummy adapter.
     Dummy(games::dummy::DummyCommands),
 }
 
+/// Statistics about the frames and updates of an adapter.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AdapterStats {
+    /// The number of frames of data the adapter has received from the game.
+    pub frames: u64,
+    /// The number of updates the adapter has published to the model.
+    pub updates: u64,
+    /// The rate of updates per second over the last second.
+    pub effective_hz: f32,
+    /// The number of frames the game has produced that the adapter did not receive.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Dropped frames cannot be detected.
+    pub dropped: u64,
+}
+
+/// The length of the window over which the update rate is calculated.
+const STATS_WINDOW: Duration = Duration::from_secs(1);
+
+/// The counters for the adapter statistics.
+///
+/// The counters are atomics so that they can be read without contending with the adapter.
+/// The window of update times is only used by the adapter thread.
+#[derive(Default)]
+struct StatsCounter {
+    frames: AtomicU64,
+    updates: AtomicU64,
+    dropped: AtomicU64,
+    /// The bits of the effective update rate as a `f32`.
+    effective_hz: AtomicU32,
+    window: Mutex<VecDeque<Instant>>,
+}
+
+impl StatsCounter {
+    fn record_frame(&self, dropped: u64) {
+        self.frames.fetch_add(1, Ordering::Relaxed);
+        self.dropped.fetch_add(dropped, Ordering::Relaxed);
+    }
+
+    fn record_update(&self, now: Instant) {
+        self.updates.fetch_add(1, Ordering::Relaxed);
+
+        let mut window = self.window.lock().unwrap();
+        window.push_back(now);
+        while window
+            .front()
+            .is_some_and(|first| now.duration_since(*first) > STATS_WINDOW)
+        {
+            window.pop_front();
+        }
+        let span = window
+            .front()
+            .map_or(Duration::ZERO, |first| now.duration_since(*first));
+        let effective_hz = if span.is_zero() {
+            0.0
+        } else {
+            (window.len() - 1) as f32 / span.as_secs_f32()
+        };
+        self.effective_hz
+            .store(effective_hz.to_bits(), Ordering::Relaxed);
+    }
+
+    fn read(&self) -> AdapterStats {
+        AdapterStats {
+            frames: self.frames.load(Ordering::Relaxed),
+            updates: self.updates.load(Ordering::Relaxed),
+            effective_hz: f32::from_bits(self.effective_hz.load(Ordering::Relaxed)),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
 /// An event that is triggered when the model receives an update.
 ///
 /// This is a wrapper around a convar.
-#[derive(Clone)]
 pub struct UpdateEvent {
     pair: Arc<(Mutex<EventState>, Condvar)>,
+    /// The version of the model. Kept outside of the mutex so that it can be read
+    /// without contending with the adapter.
+    version: Arc<AtomicU64>,
+    /// The statistics of the adapter.
+    stats: Arc<StatsCounter>,
+    /// The counter of the event when [`UpdateEvent::check_and_reset`] was last called.
+    /// Every clone keeps its own count.
+    checked: AtomicUsize,
+}
+
+impl Clone for UpdateEvent {
+    fn clone(&self) -> Self {
+        Self {
+            pair: self.pair.clone(),
+            version: self.version.clone(),
+            stats: self.stats.clone(),
+            checked: AtomicUsize::new(self.checked.load(Ordering::Relaxed)),
+        }
+    }
 }
 
 /// An error that can occur when waiting for an event.
@@ -262,6 +691,9 @@ impl UpdateEvent {
                 }),
                 Condvar::new(),
             )),
+            version: Arc::new(AtomicU64::new(0)),
+            stats: Arc::new(StatsCounter::default()),
+            checked: AtomicUsize::new(0),
         }
     }
 
@@ -282,10 +714,21 @@ impl UpdateEvent {
         var.notify_all();
     }
 
+    /// Record a frame of data that was received from the game.
+    ///
+    /// `dropped` is the number of frames the game has produced since the last
+    /// received frame that the adapter has missed.
+    pub fn frame(&self, dropped: u64) {
+        self.stats.record_frame(dropped);
+    }
+
     /// Trigger the event.
     ///
     /// Only triggers the event if the event is enabled.
+    /// The model version and the update statistics are increased regardless.
     pub fn trigger(&self) {
+        self.version.fetch_add(1, Ordering::Release);
+        self.stats.record_update(Instant::now());
         let (state_mutex, var) = &*self.pair;
         let mut state = state_mutex.lock().unwrap();
         if !state.enabled {
@@ -295,45 +738,272 @@ impl UpdateEvent {
         var.notify_all();
     }
 
+    /// Returns the number of times this event has been triggered.
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if the event was triggered since the last call to this method.
+    ///
+    /// Does not block. Every clone of the event keeps track of its own calls.
+    pub fn check_and_reset(&self) -> bool {
+        let (state, _) = &*self.pair;
+        let counter = state.lock().unwrap().counter;
+        self.checked.swap(counter, Ordering::Relaxed) != counter
+    }
+
+    /// Subscribe to this event.
+    ///
+    /// The subscription keeps track of the events it has seen, so that every
+    /// subscription observes every trigger independently of other listeners.
+    pub fn subscribe(&self) -> UpdateSubscription {
+        let (state, _) = &*self.pair;
+        UpdateSubscription {
+            pair: self.pair.clone(),
+            seen: state.lock().unwrap().counter,
+        }
+    }
+
     /// Block and wait for the next event.
     ///
     /// This function will error when the event source closes.
     pub fn wait(&self) -> Result<(), WaitError> {
+        self.subscribe().wait()
+    }
+
+    /// Block and wait for the next event or until the timeout expires.
+    ///
+    /// THis function will error when the event source closes or when the timeout expires.
+    pub fn wait_timeout(&self, duration: Duration) -> Result<(), WaitError> {
+        self.subscribe().wait_timeout(duration)
+    }
+}
+
+/// A listener of an [`UpdateEvent`] created with [`UpdateEvent::subscribe`].
+///
+/// Each subscription remembers the last event it has seen. A call to wait returns
+/// right away if the event was triggered since the last call returned.
+pub struct UpdateSubscription {
+    pair: Arc<(Mutex<EventState>, Condvar)>,
+    /// The counter of the event when this subscription last returned from a wait.
+    seen: usize,
+}
+
+impl UpdateSubscription {
+    /// Block and wait for the next event that this subscription has not seen.
+    ///
+    /// This function will error when the event source closes.
+    pub fn wait(&mut self) -> Result<(), WaitError> {
         let (state_mutex, var) = &*self.pair;
         let mut state = state_mutex.lock().unwrap();
-        if !state.enabled {
-            return Err(WaitError::EventDisabled);
-        }
-        let prev_event_count = state.counter;
-        while state.enabled && state.counter == prev_event_count {
+        while state.enabled && state.counter == self.seen {
             state = var.wait(state).unwrap();
         }
         if !state.enabled {
             return Err(WaitError::EventDisabled);
         }
+        self.seen = state.counter;
         Ok(())
     }
 
-    /// Block and wait for the next event or until the timeout expires.
+    /// Block and wait for the next event that this subscription has not seen or
+    /// until the timeout expires.
     ///
-    /// THis function will error when the event source closes or when the timeout expires.
-    pub fn wait_timeout(&self, duration: Duration) -> Result<(), WaitError> {
+    /// This function will error when the event source closes or when the timeout expires.
+    pub fn wait_timeout(&mut self, duration: Duration) -> Result<(), WaitError> {
         let (state_mutex, var) = &*self.pair;
+        let deadline = Instant::now() + duration;
         let mut state = state_mutex.lock().unwrap();
-        if !state.enabled {
-            return Err(WaitError::EventDisabled);
-        }
-        let prev_event_count = state.counter;
-        while state.enabled && state.counter == prev_event_count {
-            let (next_state, result) = var.wait_timeout(state, duration).unwrap();
+        while state.enabled && state.counter == self.seen {
+            let timeout = deadline.saturating_duration_since(Instant::now());
+            let (next_state, result) = var.wait_timeout(state, timeout).unwrap();
             state = next_state;
-            if result.timed_out() {
+            if result.timed_out() && state.counter == self.seen {
                 return Err(WaitError::TimeoutExpired);
             }
         }
         if !state.enabled {
             return Err(WaitError::EventDisabled);
         }
+        self.seen = state.counter;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assert that two floats are equal within a small tolerance.
+    pub(crate) fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{a} != {b}");
+    }
+
+    /// A game adapter that does nothing until it is closed.
+    struct IdleAdapter;
+
+    impl GameAdapter for IdleAdapter {
+        fn run(
+            &mut self,
+            model: Arc<RwLock<Model>>,
+            command_rx: mpsc::Receiver<AdapterCommand>,
+            _update_event: UpdateEvent,
+        ) -> Result<(), AdapterError> {
+            model.write().unwrap().event_name.set("Idle event".to_owned());
+            while let Ok(command) = command_rx.recv() {
+                if let AdapterCommand::Close = command {
+                    break;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dropping_last_clone_closes_adapter() {
+        let adapter = Adapter::new(IdleAdapter);
+        let update_event = adapter.update_event.clone();
+        let clone = adapter.clone();
+
+        drop(adapter);
+        assert!(!clone.is_finished());
+
+        drop(clone);
+        assert!(matches!(
+            update_event.wait_timeout(Duration::from_secs(1)),
+            Err(WaitError::EventDisabled)
+        ));
+    }
+
+    #[test]
+    fn waiter_wakes_up_with_disabled_event_on_close() {
+        let mut adapter = Adapter::new(IdleAdapter);
+        let waiter = {
+            let adapter = adapter.clone();
+            thread::spawn(move || adapter.wait_for_update())
+        };
+
+        adapter.send(AdapterCommand::Close);
+        assert!(adapter.join().is_some_and(|result| result.is_ok()));
+        assert!(matches!(
+            waiter.join().expect("Waiter should not panic"),
+            Err(WaitError::EventDisabled)
+        ));
+    }
+
+    #[test]
+    fn check_update_without_blocking() {
+        let update_event = UpdateEvent::new();
+        update_event.enable();
+        assert!(!update_event.check_and_reset());
+
+        update_event.trigger();
+        let clone = update_event.clone();
+        assert!(update_event.check_and_reset());
+        assert!(!update_event.check_and_reset());
+
+        // Each clone has its own count.
+        assert!(clone.check_and_reset());
+        update_event.trigger();
+        assert!(update_event.check_and_reset());
+        assert!(clone.check_and_reset());
+    }
+
+    #[test]
+    fn every_subscriber_sees_a_trigger() {
+        let update_event = UpdateEvent::new();
+        update_event.enable();
+        let subscribers: Vec<_> = (0..2)
+            .map(|_| {
+                let mut subscription = update_event.subscribe();
+                thread::spawn(move || subscription.wait_timeout(Duration::from_secs(5)).is_ok())
+            })
+            .collect();
+
+        update_event.trigger();
+        for subscriber in subscribers {
+            assert!(subscriber.join().expect("Subscriber should not panic"));
+        }
+    }
+
+    #[test]
+    fn subscription_keeps_missed_trigger() {
+        let update_event = UpdateEvent::new();
+        update_event.enable();
+        let mut subscription = update_event.subscribe();
+        update_event.trigger();
+        assert!(subscription.wait_timeout(Duration::ZERO).is_ok());
+        assert!(matches!(
+            subscription.wait_timeout(Duration::from_millis(1)),
+            Err(WaitError::TimeoutExpired)
+        ));
+        update_event.disable();
+        assert!(matches!(subscription.wait(), Err(WaitError::EventDisabled)));
+    }
+
+    #[test]
+    fn read_poisoned_model() {
+        let model = Arc::new(RwLock::new(Model::default()));
+        let read_only = ReadOnlyModel::new(model.clone());
+        let result = thread::spawn(move || {
+            let mut model = model.write().unwrap();
+            model.event_name.set("Poisoned".to_owned());
+            panic!("Poison the lock");
+        })
+        .join();
+        assert!(result.is_err());
+
+        assert!(read_only.read().is_err());
+        assert_eq!(*read_only.read_recover().event_name, "Poisoned");
+    }
+
+    #[test]
+    fn game_kind_is_set_on_creation() {
+        assert_eq!(Adapter::new(IdleAdapter).game_kind(), GameKind::Unknown);
+        let adapter = Adapter::new_dummy();
+        assert_eq!(adapter.game_kind(), GameKind::Dummy);
+        assert_eq!(adapter.model.read().unwrap().game, GameKind::Dummy);
+    }
+
+    #[test]
+    fn join_with_timeout() {
+        let mut adapter = Adapter::new(IdleAdapter);
+        assert!(adapter.join_timeout(Duration::from_millis(10)).is_none());
+
+        adapter.send(AdapterCommand::Close);
+        assert!(adapter
+            .join_timeout(Duration::from_secs(5))
+            .is_some_and(|result| result.is_ok()));
+        assert!(adapter.join_timeout(Duration::ZERO).is_none());
+    }
+
+    #[test]
+    fn effective_update_rate() {
+        let stats = StatsCounter::default();
+        let start = Instant::now();
+        for i in 0..120 {
+            stats.record_frame(if i % 10 == 0 { 1 } else { 0 });
+            stats.record_update(start + Duration::from_millis(i * 20));
+        }
+        let stats = stats.read();
+        assert_eq!(stats.frames, 120);
+        assert_eq!(stats.updates, 120);
+        assert_eq!(stats.dropped, 12);
+        // Updates every 20ms are 50 updates per second.
+        assert!((stats.effective_hz - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn model_is_readable_after_close() {
+        let mut adapter = Adapter::new(IdleAdapter);
+        adapter.send(AdapterCommand::Close);
+        adapter.join();
+
+        assert!(matches!(
+            adapter.wait_for_update(),
+            Err(WaitError::EventDisabled)
+        ));
+        let model = adapter.model.read().expect("Model should not be poisoned");
+        assert_eq!(*model.event_name, "Idle event");
+    }
+}
@@ -14,12 +14,16 @@
 //! or if a default is used. To do this, the ['Value'] object has some flags to read this information.
 
 use std::{
+    borrow::Cow,
     collections::{HashMap, HashSet},
     fmt::Display,
     ops::{Deref, DerefMut},
+    sync::mpsc::{SyncSender, TrySendError},
+    time::{Duration, Instant},
 };
 
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     games::{
@@ -27,7 +31,7 @@ use crate::{
         iracing::IRacingCamera,
     },
     types::Time,
-    Distance, Temperature,
+    Angle, Color, Distance, Pressure, Temperature, UnitSystem,
 };
 
 /// A single piece of data in the model that carries extra information about its
@@ -42,7 +46,7 @@ use crate::{
 /// to overwrite the value set by the user or set the `editable` flag to false at any time.
 ///
 /// The specific behavior of the game adapter is documented in the documentation for the value.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Value<T> {
     value: T,
     available: bool,
@@ -255,18 +259,33 @@ impl<T: Display> Display for Value<T> {
 
 /// The unified sim model.
 /// Holds all the date availabe from the game.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Model {
     /// Shows if the adapter is currently receiving data from the game.
     pub connected: bool,
+    /// The state of the connection to the game.
+    ///
+    /// Changed with [`Model::set_connection_state`] which keeps `connected` in sync.
+    pub connection_state: ConnectionState,
+    /// The game the adapter connects to.
+    ///
+    /// Set when the adapter is created. The auto adapter sets the game once it has
+    /// found a running game.
+    pub game: GameKind,
     /// List of sessions that have happend during the event.
     /// Sessions are orderd in the order they occur in the event.
     pub sessions: IndexMap<SessionId, Session>,
     /// Id of the current active session.
     /// `None` if there is no active session.
     pub current_session: Option<SessionId>,
-    /// List of events that have happened during the liftime of the adapter.
-    pub events: Vec<Event>,
+    /// The most recent events that have happened during the lifetime of the adapter.
+    ///
+    /// Holds at most [`EVENT_BUFFER_CAPACITY`] events. When the buffer is full the oldest
+    /// event is removed. Use [`Model::recent_events`] to read the events without
+    /// having to clear them.
+    pub events: Vec<TimedEvent>,
+    /// The sequence id of the first event in `events`.
+    first_event_id: u64,
     /// Name of the event.
     ///
     /// ### Availability:
@@ -285,8 +304,36 @@ pub struct Model {
     pub available_cameras: HashSet<Camera>,
     /// The currently focused car.
     pub focused_entry: Option<EntryId>,
+    /// The unit system the user of the adapter prefers.
+    ///
+    /// Set from the [`AdapterOptions`](crate::AdapterOptions) when the adapter is created.
+    /// The typed values in the model always keep their own unit. This is a convenience
+    /// for simple consumers to show plain numbers, for example with
+    /// `model.unit_system.speed(speed)`. Working with the typed values directly is preferred.
+    pub unit_system: UnitSystem,
+    /// The force feedback settings of the wheel of the player.
+    /// `None` if the game does not provide the force feedback settings.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Only available if the adapter was created with force feedback enabled.
+    pub player_ffb: Option<FfbInfo>,
+    /// The instant the adapter last wrote new data into the model.
+    /// `None` if the adapter has not written any data yet.
+    ///
+    /// This is not serialized since an instant is only meaningful in the running program.
+    #[serde(skip)]
+    pub last_update: Option<Instant>,
+    /// The senders of all event subscribers.
+    #[serde(skip)]
+    event_subscribers: Vec<SyncSender<TimedEvent>>,
 }
 
+/// The number of events the model keeps before the oldest events are removed.
+pub const EVENT_BUFFER_CAPACITY: usize = 1024;
+
 impl Model {
     /// Add a session to the model.
     /// Generates a new id for the session and adds it to the model.
@@ -310,18 +357,400 @@ impl Model {
         self.sessions.get_mut(&self.current_session?)
     }
 
+    /// Returns the id, type and phase of every session in the order the sessions happened.
+    pub fn session_tabs(&self) -> Vec<(SessionId, SessionType, SessionPhase)> {
+        self.sessions
+            .values()
+            .map(|session| (session.id, *session.session_type, *session.phase))
+            .collect()
+    }
+
+    /// Returns the sessions grouped into sessions that belong together.
+    ///
+    /// Some events split a session into multiple parts, for example a qualifying
+    /// with Q1 and Q2 or multiple heat races. Those sessions are grouped together using
+    /// the following heuristic:
+    /// - Only sessions that follow each other directly can be in the same group.
+    /// - Sessions in a group have the same session type.
+    /// - Sessions in a group have the same name after removing a trailing number.
+    /// "HEAT 1" and "HEAT 2" are grouped together, "HEAT 2" and "FEATURE" are not.
+    /// Sessions without a name are grouped by their type only.
+    pub fn session_groups(&self) -> Vec<SessionGroup> {
+        let mut groups: Vec<SessionGroup> = Vec::new();
+        for session in self.sessions.values() {
+            let name = session
+                .name
+                .get_available()
+                .map(|name| {
+                    name.trim_end_matches(|c: char| c.is_ascii_digit() || c.is_whitespace())
+                })
+                .filter(|name| !name.is_empty())
+                .map_or_else(|| format!("{:?}", *session.session_type), str::to_owned);
+            match groups.last_mut() {
+                Some(group)
+                    if group.session_type == *session.session_type && group.name == name =>
+                {
+                    group.sessions.push(session.id);
+                }
+                _ => groups.push(SessionGroup {
+                    name,
+                    session_type: *session.session_type,
+                    sessions: vec![session.id],
+                }),
+            }
+        }
+        groups
+    }
+
+    /// Check the model for inconsistent data.
+    ///
+    /// Returns a list of all problems that were found. A well formed model returns an empty list.
+    /// This is meant to find bugs in the adapters and is not required for normal use.
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        if let Some(id) = self.current_session {
+            if !self.sessions.contains_key(&id) {
+                warnings.push(ValidationWarning::CurrentSessionMissing(id));
+            }
+        }
+        for session in self.sessions.values() {
+            // Positions must be unique and without gaps.
+            let mut positions: Vec<i32> = session
+                .entries
+                .values()
+                .map(|entry| *entry.position)
+                .filter(|position| *position > 0)
+                .collect();
+            positions.sort_unstable();
+            let mut duplicates: Vec<i32> = positions
+                .windows(2)
+                .filter(|window| window[0] == window[1])
+                .map(|window| window[0])
+                .collect();
+            duplicates.dedup();
+            warnings.extend(duplicates.into_iter().map(|position| {
+                ValidationWarning::DuplicatePosition {
+                    session: session.id,
+                    position,
+                }
+            }));
+            positions.dedup();
+            for (expected, position) in (1..).zip(positions.iter()) {
+                if *position != expected {
+                    warnings.push(ValidationWarning::MissingPosition {
+                        session: session.id,
+                        position: expected,
+                    });
+                    break;
+                }
+            }
+
+            if let Some(entry_id) = session
+                .best_lap
+                .as_ref()
+                .as_ref()
+                .and_then(|lap| lap.entry_id)
+            {
+                if !session.entries.contains_key(&entry_id) {
+                    warnings.push(ValidationWarning::BestLapEntryMissing {
+                        session: session.id,
+                        entry: entry_id,
+                    });
+                }
+            }
+
+            for entry in session.entries.values() {
+                if !entry.drivers.is_empty() && !entry.drivers.contains_key(&entry.current_driver) {
+                    warnings.push(ValidationWarning::CurrentDriverMissing {
+                        session: session.id,
+                        entry: entry.id,
+                    });
+                }
+                for (lap_index, lap) in entry.laps.iter().enumerate() {
+                    let negative_split = lap.splits.iter().any(|split| split.ms < 0.0);
+                    if negative_split || !lap.splits_consistent(Time::from(1000)) {
+                        warnings.push(ValidationWarning::InvalidSplits {
+                            session: session.id,
+                            entry: entry.id,
+                            lap_index,
+                        });
+                    }
+                }
+            }
+        }
+        warnings
+    }
+
     /// Returns if the given camera is available.
     pub fn is_camera_available(&self, camera: &Camera) -> bool {
         self.available_cameras.contains(camera)
     }
+
+    /// Returns the time that has passed since the adapter last updated the model.
+    /// `None` if the model has never been updated.
+    pub fn time_since_update(&self) -> Option<Duration> {
+        self.last_update.map(|instant| instant.elapsed())
+    }
+
+    /// Returns the fastest valid lap of the event across all sessions together with the
+    /// session and the entry that drove it.
+    /// `None` if no valid lap with a known lap time has been driven yet.
+    pub fn event_best_lap(&self) -> Option<(SessionId, EntryId, &Lap)> {
+        self.sessions
+            .values()
+            .flat_map(|session| {
+                session.entries.values().flat_map(move |entry| {
+                    entry
+                        .laps
+                        .iter()
+                        .map(move |lap| (session.id, entry.id, lap))
+                })
+            })
+            .filter(|(_, _, lap)| Self::is_valid_timed_lap(lap))
+            .min_by(|(_, _, a), (_, _, b)| a.time.ms.total_cmp(&b.time.ms))
+    }
+
+    /// Returns the fastest valid lap of an entry across all sessions of the event together
+    /// with the session it was driven in.
+    /// `None` if the entry has not driven a valid lap with a known lap time yet.
+    ///
+    /// Entries are matched by their `EntryId` in each session. This assumes that the
+    /// game keeps the id of an entry stable between sessions of the same event, which is
+    /// the case for the car id in Assetto Corsa Competizione and the car index in iRacing.
+    /// If a game reassigns ids between sessions, laps of different entries may be mixed.
+    pub fn entry_event_best(&self, entry_id: EntryId) -> Option<(SessionId, &Lap)> {
+        self.sessions
+            .values()
+            .filter_map(|session| {
+                session
+                    .entries
+                    .get(&entry_id)
+                    .map(|entry| entry.laps.iter().map(move |lap| (session.id, lap)))
+            })
+            .flatten()
+            .filter(|(_, lap)| Self::is_valid_timed_lap(lap))
+            .min_by(|(_, a), (_, b)| a.time.ms.total_cmp(&b.time.ms))
+    }
+
+    /// Returns a short human readable overview of the model.
+    ///
+    /// The summary contains the event name, the track, the type and phase of the current
+    /// session, the number of entries, the leader and the time or laps remaining.
+    /// Values that are not available are left out.
+    pub fn summary(&self) -> String {
+        use std::fmt::Write;
+
+        let mut summary = String::with_capacity(128);
+        summary.push_str(&self.event_name);
+        let Some(session) = self.current_session() else {
+            summary.push_str(" | no session");
+            return summary;
+        };
+        if session.track_name.is_avaliable() {
+            _ = write!(summary, " | {}", *session.track_name);
+        }
+        _ = write!(
+            summary,
+            " | {:?} {:?} | {} entries",
+            *session.session_type,
+            *session.phase,
+            session.entries.len()
+        );
+        if let Some(leader) = session.entries.values().find(|entry| *entry.position == 1) {
+            _ = write!(summary, " | leader #{}", *leader.car_number);
+        }
+        if let Some(time_remaining) = session.time_remaining.get_available() {
+            _ = write!(summary, " | {} remaining", time_remaining.fmt_no_ms());
+        } else if let Some(laps_remaining) = session.laps_remaining.get_available() {
+            _ = write!(summary, " | {} laps remaining", laps_remaining);
+        }
+        summary
+    }
+
+    /// Change the state of the connection to the game.
+    ///
+    /// Sends a [`Event::ConnectionStateChanged`] event if the state changed.
+    pub fn set_connection_state(&mut self, state: ConnectionState) {
+        self.connected = state == ConnectionState::Connected;
+        if self.connection_state != state {
+            self.connection_state = state;
+            self.push_event(Event::ConnectionStateChanged(state));
+        }
+    }
+
+    /// Add an event to the model.
+    /// The event is stamped with the elapsed time of the current session.
+    pub fn push_event(&mut self, event: Event) {
+        let time = self
+            .current_session()
+            .and_then(|session| session.time_elapsed())
+            .map_or_else(Value::default, Value::new);
+        self.push_timed_event(TimedEvent { time, event });
+    }
+
+    /// Add an event that already has a time to the model.
+    fn push_timed_event(&mut self, timed_event: TimedEvent) {
+        // A subscriber that is lagging behind misses the event. A subscriber that
+        // has dropped its receiver is removed.
+        self.event_subscribers
+            .retain(|sender| match sender.try_send(timed_event.clone()) {
+                Ok(()) | Err(TrySendError::Full(_)) => true,
+                Err(TrySendError::Disconnected(_)) => false,
+            });
+        if self.events.len() >= EVENT_BUFFER_CAPACITY {
+            let removed = self.events.len() + 1 - EVENT_BUFFER_CAPACITY;
+            self.events.drain(..removed);
+            self.first_event_id += removed as u64;
+        }
+        self.events.push(timed_event);
+    }
+
+    /// Returns the sequence id the next event will get.
+    ///
+    /// Every event gets a sequence id that increases by one for each event. A consumer
+    /// can store this id as a watermark and later read all events since then with
+    /// [`Model::recent_events`].
+    pub fn next_event_id(&self) -> u64 {
+        self.first_event_id + self.events.len() as u64
+    }
+
+    /// Returns the events with a sequence id of at least `since`.
+    ///
+    /// This allows multiple consumers to read the events independently without clearing them:
+    /// ```
+    /// # let model = unified_sim_model::model::Model::default();
+    /// let mut watermark = 0;
+    /// for event in model.recent_events(watermark) {
+    ///     println!("{:?}", event.event);
+    /// }
+    /// watermark = model.next_event_id();
+    /// ```
+    /// If events newer than `since` were already removed from the buffer, all buffered
+    /// events are returned and the removed events are missed.
+    pub fn recent_events(&self, since: u64) -> &[TimedEvent] {
+        let start = since
+            .saturating_sub(self.first_event_id)
+            .min(self.events.len() as u64);
+        &self.events[start as usize..]
+    }
+
+    /// Remove all events from the buffer.
+    /// The sequence ids continue after the removed events.
+    pub(crate) fn clear_events(&mut self) {
+        self.first_event_id = self.next_event_id();
+        self.events.clear();
+    }
+
+    /// Replace the data of this model with a snapshot of a model.
+    ///
+    /// The event buffer and the event subscribers of this model are kept. The events of the
+    /// snapshot with a sequence id of at least `since` are added to this model in order.
+    #[cfg(feature = "bincode")]
+    pub(crate) fn apply_snapshot(&mut self, mut snapshot: Model, since: u64) {
+        let new_events = snapshot.recent_events(since).to_vec();
+        snapshot.events = std::mem::take(&mut self.events);
+        snapshot.first_event_id = self.first_event_id;
+        snapshot.event_subscribers = std::mem::take(&mut self.event_subscribers);
+        *self = snapshot;
+        for timed_event in new_events {
+            self.push_timed_event(timed_event);
+        }
+    }
+
+    /// Add a subscriber that receives a copy of every event added to the model.
+    pub(crate) fn add_event_subscriber(&mut self, sender: SyncSender<TimedEvent>) {
+        self.event_subscribers.push(sender);
+    }
+
+    /// Returns if a lap is valid and has a known time.
+    ///
+    /// Some games report a lap that was not timed with a time of `i32::MAX` milliseconds.
+    /// Those laps do not have a known time.
+    fn is_valid_timed_lap(lap: &Lap) -> bool {
+        !*lap.invalid && lap.time.is_avaliable() && lap.time.ms < i32::MAX as f64
+    }
+}
+
+/// A problem with the data in the model found by [`Model::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// The current session does not exist.
+    CurrentSessionMissing(SessionId),
+    /// Multiple entries have the same position.
+    DuplicatePosition { session: SessionId, position: i32 },
+    /// No entry has this position even though entries with a worse position exist.
+    MissingPosition { session: SessionId, position: i32 },
+    /// The entry that drove the best lap of the session does not exist.
+    BestLapEntryMissing { session: SessionId, entry: EntryId },
+    /// The current driver of an entry is not one of its drivers.
+    CurrentDriverMissing { session: SessionId, entry: EntryId },
+    /// The splits of a lap are negative or do not add up to the lap time.
+    InvalidSplits {
+        session: SessionId,
+        entry: EntryId,
+        lap_index: usize,
+    },
+}
+
+impl Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationWarning::CurrentSessionMissing(id) => {
+                write!(f, "The current session {} does not exist", id.0)
+            }
+            ValidationWarning::DuplicatePosition { session, position } => write!(
+                f,
+                "Session {}: multiple entries in position {}",
+                session.0, position
+            ),
+            ValidationWarning::MissingPosition { session, position } => {
+                write!(
+                    f,
+                    "Session {}: no entry in position {}",
+                    session.0, position
+                )
+            }
+            ValidationWarning::BestLapEntryMissing { session, entry } => write!(
+                f,
+                "Session {}: the entry {} of the best lap does not exist",
+                session.0, entry.0
+            ),
+            ValidationWarning::CurrentDriverMissing { session, entry } => write!(
+                f,
+                "Session {}: the current driver of entry {} does not exist",
+                session.0, entry.0
+            ),
+            ValidationWarning::InvalidSplits {
+                session,
+                entry,
+                lap_index,
+            } => write!(
+                f,
+                "Session {}: the splits of lap {} of entry {} are invalid",
+                session.0, lap_index, entry.0
+            ),
+        }
+    }
+}
+
+/// A group of sessions that belong together.
+/// See [`Model::session_groups`] for how sessions are grouped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionGroup {
+    /// The name of the group.
+    pub name: String,
+    /// The type of the sessions in this group.
+    pub session_type: SessionType,
+    /// The sessions in this group in the order they happened.
+    pub sessions: Vec<SessionId>,
 }
 
 /// The identifier for a session.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct SessionId(pub usize);
 
 /// A session.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Session {
     /// The session id of this session
     pub id: SessionId,
@@ -329,6 +758,14 @@ pub struct Session {
     pub entries: HashMap<EntryId, Entry>,
     /// The current session type.
     pub session_type: Value<SessionType>,
+    /// The name of the session.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// The name of the session as it is shown in the game, for example "QUALIFY" or "HEAT 1".
+    pub name: Value<String>,
     /// The current phase of the session.
     pub phase: Value<SessionPhase>,
     /// The time limit for this session.
@@ -342,6 +779,8 @@ pub struct Session {
     /// If the session is not a timed session then this will not be available.
     pub time_remaining: Value<Time>,
     /// The amount of laps required to finish this session.
+    /// This is the total length of the session, not the laps that have been driven.
+    /// Use [`Session::leader_lap`] for the laps completed by the leader.
     ///
     /// ### Availability:
     /// If the session is not a lapped session then this will not be available.
@@ -381,13 +820,723 @@ pub struct Session {
     /// - **Assetto Corsa Competizione:**
     /// After the session changes or when the adapter first connects there might be a short delay before
     /// the track length is availabe.
+    ///
+    /// Use [`Session::known_track_length`] for calculations since the track length can be
+    /// zero before the game has sent it.
     pub track_length: Value<Distance>,
+    /// The number of sectors the track is split into.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Every track has three sectors.
+    /// - **iRacing:**
+    /// Available from the split time info of the track.
+    pub sector_count: Value<usize>,
+    /// The best time of each sector in this session.
+    ///
+    /// Updated from the splits of every valid lap that is completed. The list may be shorter
+    /// than the sector count until a lap with splits for every sector is completed.
+    /// A sector with a time of zero or less has no best time yet.
+    /// [`Session::best_sectors`] and [`Session::ideal_lap`] are read from this list.
+    pub best_splits: Vec<Time>,
+    /// The pit window and mandatory stop requirements for this session.
+    ///
+    /// `None` if the session has no pit window.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// The pit window is not part of the broadcasting data and is not available.
+    /// - **iRacing:**
+    /// The pit window is not available in iRacing.
+    pub pit_window: Value<Option<PitWindow>>,
+    /// When the current phase of the session started.
+    /// `None` if the start of the phase has not been seen by the adapter.
+    ///
+    /// This is not serialized since an instant is only meaningful in the running program.
+    #[serde(skip)]
+    pub phase_start: Option<PhaseStart>,
+    /// The state of the safety car.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// The broadcasting data does not contain the safety car or full course yellow state.
+    /// Not available.
+    /// - **iRacing:**
+    /// The safety car is deployed during a full course caution. The pace car leading
+    /// the field during the start of the race is not a safety car.
+    pub safety_car: Value<SafetyCarState>,
+    /// The rules of the session.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    pub rules: Value<SessionRules>,
     /// Contains additional data that is game specific.
     pub game_data: SessionGameData,
 }
 
+impl Session {
+    /// Returns the time that has passed since the start of the session.
+    /// `None` if the session is not a timed session.
+    pub fn time_elapsed(&self) -> Option<Time> {
+        let session_time = self.session_time.get_available()?;
+        let time_remaining = self.time_remaining.get_available()?;
+        Some(Time::from(session_time.ms - time_remaining.ms))
+    }
+
+    /// Returns the time that has passed since the current phase started.
+    /// `None` if the start of the phase is not known.
+    pub fn time_in_phase(&self) -> Option<Duration> {
+        self.phase_start
+            .as_ref()
+            .map(|start| start.instant.elapsed())
+    }
+
+    /// Returns an estimate for the time until the session goes green.
+    /// `None` if the session is not in the formation phase or if there is not enough
+    /// progress to estimate the pace of the formation lap yet.
+    ///
+    /// This is a best effort estimate. It assumes that the session goes green when the leader
+    /// crosses the line after one formation lap and that the leader keeps the average pace
+    /// it had since the formation started. Games that use a rolling start or a formation
+    /// lap of different length will see inaccurate estimates.
+    pub fn estimated_time_to_green(&self) -> Option<Time> {
+        if *self.phase != SessionPhase::Formation {
+            return None;
+        }
+        let start = self.phase_start.as_ref()?;
+        let leader_distance = self
+            .entries
+            .values()
+            .map(|entry| *entry.distance_driven)
+            .max_by(|a, b| a.total_cmp(b))?;
+
+        // The grid can be slightly before or after the line. The green flag is at the
+        // line one lap after the line that is closest to the grid.
+        let green_distance = start.leader_distance.round() + 1.0;
+        let covered = leader_distance - start.leader_distance;
+        if covered < 0.01 {
+            return None;
+        }
+        let remaining = (green_distance - leader_distance).max(0.0);
+        let elapsed = start.instant.elapsed().as_secs_f64() * 1000.0;
+        Some(Time::from(elapsed * (remaining / covered) as f64))
+    }
+
+    /// Returns the length of the track if it is known.
+    /// `None` if the track length is unavailable or not a positive length.
+    pub fn known_track_length(&self) -> Option<Distance> {
+        self.track_length
+            .get_available()
+            .copied()
+            .filter(|length| length.as_meters() > 0.0 && length.as_meters().is_finite())
+    }
+
+    /// Returns the distance on track for a number of laps.
+    /// `None` if the track length is not known.
+    pub fn track_distance(&self, laps: f32) -> Option<Distance> {
+        self.known_track_length()
+            .map(|length| Distance::from_meter(length.as_meters() * laps))
+    }
+
+    /// Returns the number of laps a distance on track covers.
+    /// `None` if the track length is not known.
+    pub fn laps_for_distance(&self, distance: Distance) -> Option<f32> {
+        self.known_track_length()
+            .map(|length| distance.as_meters() / length.as_meters())
+    }
+
+    /// Returns the gap of every entry to a reference entry.
+    /// A positive gap means the entry is behind the reference, a negative gap means it is ahead.
+    ///
+    /// The gaps are calculated from the `time_behind_leader` of the entries, which makes the gaps
+    /// to the leader the same as the `time_behind_leader`. Entries without a time behind the leader
+    /// are left out. If the reference entry does not exist or has no time behind the
+    /// leader, the map is empty.
+    pub fn gaps_relative_to(&self, reference: EntryId) -> HashMap<EntryId, Time> {
+        let Some(reference_gap) = self
+            .entries
+            .get(&reference)
+            .and_then(|entry| entry.time_behind_leader.get_available())
+        else {
+            return HashMap::new();
+        };
+        self.entries
+            .values()
+            .filter_map(|entry| {
+                entry
+                    .time_behind_leader
+                    .get_available()
+                    .map(|gap| (entry.id, Time::from(gap.ms - reference_gap.ms)))
+            })
+            .collect()
+    }
+
+    /// Returns the gap of an entry to the entry in the position ahead of it.
+    ///
+    /// The gap is calculated from the `time_behind_leader` of both entries. If no entry has
+    /// the position directly ahead, for example because that car disconnected, the next
+    /// occupied position ahead is used. `None` for the leader, for entries without a position
+    /// and if either entry has no time behind the leader.
+    pub fn gap_ahead(&self, id: EntryId) -> Option<Time> {
+        let entry = self.entries.get(&id)?;
+        let position = *entry.position;
+        if position <= 0 {
+            return None;
+        }
+        let ahead = self
+            .entries
+            .values()
+            .filter(|other| *other.position > 0 && *other.position < position)
+            .max_by_key(|other| *other.position)?;
+        let ahead_gap = ahead.time_behind_leader.get_available()?;
+        let gap = entry.time_behind_leader.get_available()?;
+        Some(Time::from(gap.ms - ahead_gap.ms))
+    }
+
+    /// Returns a row for every entry to show in a leaderboard.
+    ///
+    /// The rows are ordered by position, entries without a position are at the end.
+    /// The gap of each row is relative to `reference`. See [`TimingRow`] for the
+    /// content of each row.
+    pub fn timing_rows(&self, reference: GapReference) -> Vec<TimingRow> {
+        let entries = self.entries_by_position();
+
+        let reference_id = match reference {
+            GapReference::Leader => entries.first().map(|entry| entry.id),
+            GapReference::Entry(id) => Some(id),
+        };
+        let gaps = reference_id
+            .map(|id| self.gaps_relative_to(id))
+            .unwrap_or_default();
+
+        let mut class_positions: HashMap<&str, i32> = HashMap::new();
+        let mut rows = Vec::with_capacity(entries.len());
+        let mut entry_ahead: Option<&Entry> = None;
+        for entry in entries {
+            let class = entry.car.category().name.as_ref();
+            let class_position = class_positions.entry(class).or_insert(0);
+            *class_position += 1;
+
+            let laps_down = entry.laps_down(self);
+            let gap = if Some(entry.id) == reference_id {
+                String::new()
+            } else if reference == GapReference::Leader && laps_down > 0 {
+                format!("+{laps_down} L")
+            } else {
+                gaps.get(&entry.id).map_or("-".to_owned(), format_gap)
+            };
+            let interval = match entry_ahead {
+                None => String::new(),
+                Some(ahead) if entry.is_lapped_by(ahead) => {
+                    format!("+{} L", *ahead.lap_count - *entry.lap_count)
+                }
+                Some(ahead) => ahead
+                    .time_behind_leader
+                    .get_available()
+                    .zip(entry.time_behind_leader.get_available())
+                    .map_or("-".to_owned(), |(ahead, this)| {
+                        format_gap(&Time::from(this.ms - ahead.ms))
+                    }),
+            };
+            let driver_name = entry
+                .drivers
+                .get(&entry.current_driver)
+                .map(|driver| format!("{} {}", *driver.first_name, *driver.last_name))
+                .unwrap_or_default();
+
+            rows.push(TimingRow {
+                entry_id: entry.id,
+                position: *entry.position,
+                class_position: *class_position,
+                car_number: *entry.car_number,
+                driver_name,
+                class: class.to_owned(),
+                best_lap: entry
+                    .best_lap
+                    .as_ref()
+                    .as_ref()
+                    .and_then(|lap| lap.time.get_available().copied()),
+                last_lap: entry
+                    .laps
+                    .last()
+                    .and_then(|lap| lap.time.get_available().copied()),
+                gap,
+                interval,
+                in_pits: *entry.in_pits,
+                color: entry.display_color(),
+            });
+            entry_ahead = Some(entry);
+        }
+        rows
+    }
+
+    /// Returns the classification of this session as it is now.
+    ///
+    /// The results are a copy of the current state and do not change when the session
+    /// is updated. Take them when the session is finished to keep the final result.
+    /// The results are ordered like `entries_by_position`.
+    pub fn results(&self) -> SessionResults {
+        let results = self
+            .entries_by_position()
+            .into_iter()
+            .map(|entry| SessionResult {
+                position: *entry.position,
+                entry_id: entry.id,
+                best_lap: entry
+                    .best_lap
+                    .as_ref()
+                    .as_ref()
+                    .and_then(|lap| lap.time.get_available().copied()),
+                laps_completed: *entry.lap_count,
+                time_behind_leader: entry.time_behind_leader.get_available().copied(),
+            })
+            .collect();
+        SessionResults {
+            session_id: self.id,
+            session_type: *self.session_type,
+            results,
+        }
+    }
+
+    /// Returns if the last completed lap of an entry has the best time of the session
+    /// in a sector.
+    ///
+    /// `false` if the entry has no completed lap or the lap has no time for the sector.
+    /// The splits of a lap do not need to have an item for every sector.
+    pub fn is_sector_best(&self, entry: EntryId, sector: usize) -> bool {
+        let Some(split) = self
+            .entries
+            .get(&entry)
+            .and_then(|entry| entry.laps.last())
+            .filter(|lap| !*lap.invalid)
+            .and_then(|lap| lap.splits.get_available())
+            .and_then(|splits| splits.get(sector))
+        else {
+            return false;
+        };
+        let Some(best) = self.best_splits.get(sector) else {
+            return false;
+        };
+        split.ms > 0.0 && split.ms <= best.ms
+    }
+
+    /// Returns the entries that are currently in the pitlane.
+    ///
+    /// The entries are ordered by the time they spent in the pitlane, the entry that entered
+    /// the pitlane first comes first. Entries without a pit time are at the end.
+    /// Entries that are not connected are left out. A car that is in its garage is not
+    /// connected to the session and is not part of the pitlane.
+    pub fn entries_in_pits(&self) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self
+            .entries
+            .values()
+            .filter(|entry| *entry.in_pits && *entry.connected)
+            .collect();
+        entries.sort_by(|e1, e2| {
+            let t1 = e1.pit_time.get_available().map(|t| t.ms);
+            let t2 = e2.pit_time.get_available().map(|t| t.ms);
+            t2.partial_cmp(&t1).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        entries
+    }
+
+    /// Returns the number of entries that are currently in the pitlane.
+    /// Counts the same entries that are returned by `entries_in_pits`.
+    pub fn pit_lane_count(&self) -> usize {
+        self.entries
+            .values()
+            .filter(|entry| *entry.in_pits && *entry.connected)
+            .count()
+    }
+
+    /// Returns an iterator over all drivers of all entries in this session
+    /// together with the entry they belong to.
+    pub fn all_drivers(&self) -> impl Iterator<Item = (&Entry, &Driver)> {
+        self.entries
+            .values()
+            .flat_map(|entry| entry.drivers.values().map(move |driver| (entry, driver)))
+    }
+
+    /// Find a driver by name.
+    /// The name is compared case-insensitive with the full name, the first name
+    /// and the last name of each driver. Returns the first driver that matches.
+    pub fn find_driver(&self, name: &str) -> Option<(&Entry, &Driver)> {
+        let name = name.trim().to_lowercase();
+        self.all_drivers().find(|(_, driver)| {
+            let first_name = driver.first_name.to_lowercase();
+            let last_name = driver.last_name.to_lowercase();
+            name == first_name
+                || name == last_name
+                || name == format!("{} {}", first_name, last_name)
+        })
+    }
+
+    /// Returns the entries ordered by their position.
+    ///
+    /// Entries that are not classified yet, with a position of zero or less, are at the end.
+    /// Entries with the same position are ordered by their id.
+    pub fn entries_by_position(&self) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.values().collect();
+        entries.sort_by_key(|entry| (*entry.position <= 0, *entry.position, entry.id.0));
+        entries
+    }
+
+    /// Returns the entries grouped by their class and ordered by position in each class.
+    ///
+    /// The class with the best placed entry comes first. The entries of a class are in the
+    /// same order as in `entries_by_position`.
+    pub fn entries_by_class_position(&self) -> Vec<&Entry> {
+        let mut entries = self.entries_by_position();
+        let mut class_order: Vec<&str> = Vec::new();
+        for entry in entries.iter() {
+            let class = entry.car.category().name.as_ref();
+            if !class_order.contains(&class) {
+                class_order.push(class);
+            }
+        }
+        entries.sort_by_key(|entry| {
+            let class = entry.car.category().name.as_ref();
+            class_order.iter().position(|c| *c == class)
+        });
+        entries
+    }
+
+    /// Find an entry by its car number.
+    ///
+    /// Car numbers are not guaranteed to be unique, for example in different classes.
+    /// If multiple entries have the same number the entry with the lowest id wins
+    /// so that the result is the same every time.
+    pub fn entry_by_car_number(&self, number: i32) -> Option<&Entry> {
+        self.entries
+            .values()
+            .filter(|entry| *entry.car_number == number)
+            .min_by_key(|entry| entry.id.0)
+    }
+
+    /// Find an entry by its car number to change it.
+    /// Finds the same entry as `entry_by_car_number`.
+    pub fn entry_by_car_number_mut(&mut self, number: i32) -> Option<&mut Entry> {
+        let id = self.entry_by_car_number(number)?.id;
+        self.entries.get_mut(&id)
+    }
+
+    /// Returns the longest stint of any entry in this session.
+    /// Stints that are currently driven are included with their length so far.
+    /// `None` if no entry has driven a stint, which is always the case for games that do not
+    /// record stints. See [`Entry::stints`] for the availability.
+    pub fn longest_stint(&self) -> Option<(EntryId, Time)> {
+        self.entries
+            .values()
+            .flat_map(|entry| {
+                let current = entry.has_stint_in_progress().then_some(*entry.stint_time);
+                entry
+                    .stints
+                    .iter()
+                    .map(|stint| stint.time)
+                    .chain(current)
+                    .map(move |time| (entry.id, time))
+            })
+            .max_by(|(_, a), (_, b)| a.ms.total_cmp(&b.ms))
+    }
+
+    /// Returns the number of laps completed by the leader of this session.
+    ///
+    /// This is the highest lap count of any entry, which is the lap the leader
+    /// is on minus one. 0 if there are no entries.
+    pub fn leader_lap(&self) -> i32 {
+        self.entries
+            .values()
+            .map(|entry| *entry.lap_count)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Returns the number of laps completed by the leader of this session that were
+    /// not driven under caution.
+    ///
+    /// This is [`Session::leader_lap`] minus the laps of the leader that have the
+    /// `caution` flag set. The leader is the entry in first position, or the entry with
+    /// the most completed laps if positions are not available. Laps where it is not known
+    /// if they were driven under caution are counted as green laps. In games that do not
+    /// tag laps with the flag state this is the same as the leader lap.
+    pub fn green_laps(&self) -> i32 {
+        let leader = self
+            .entries
+            .values()
+            .find(|entry| entry.position.is_avaliable() && *entry.position == 1)
+            .or_else(|| self.entries.values().max_by_key(|entry| *entry.lap_count));
+        let caution_laps = leader.map_or(0, |leader| {
+            leader
+                .laps
+                .iter()
+                .filter(|lap| lap.caution.get_available().is_some_and(|caution| *caution))
+                .count() as i32
+        });
+        (self.leader_lap() - caution_laps).max(0)
+    }
+
+    /// Returns the fastest lap of this session that was driven by a car of a class.
+    ///
+    /// Classes are matched by the name of their category. Only valid laps with a known time
+    /// are considered.
+    pub fn best_lap_for_class(&self, category: &CarCategory) -> Option<&Lap> {
+        self.entries
+            .values()
+            .filter(|entry| entry.car.category().name == category.name)
+            .flat_map(|entry| entry.laps.iter())
+            .filter(|lap| Model::is_valid_timed_lap(lap))
+            .min_by(|a, b| a.time.ms.total_cmp(&b.time.ms))
+    }
+
+    /// Returns the best time of each sector in this session, regardless of which entry set it.
+    ///
+    /// The times are read from the [best splits](Session::best_splits) of the session.
+    /// The list has an item for every sector of the session, or for every sector with a
+    /// recorded split if the sector count is not available.
+    /// A sector is `None` if no valid time was recorded for it.
+    pub fn best_sectors(&self) -> Vec<Option<Time>> {
+        let sector_count = self
+            .sector_count
+            .get_available()
+            .map_or(0, |count| *count)
+            .max(self.best_splits.len());
+        (0..sector_count)
+            .map(|sector| {
+                self.best_splits
+                    .get(sector)
+                    .filter(|split| split.ms > 0.0)
+                    .copied()
+            })
+            .collect()
+    }
+
+    /// Returns the ideal lap time of this session.
+    ///
+    /// The ideal lap is the sum of the [best sectors](Session::best_sectors) of the session.
+    /// `None` until every sector has a recorded time.
+    pub fn ideal_lap(&self) -> Option<Time> {
+        let best_sectors = self.best_sectors();
+        if best_sectors.is_empty() {
+            return None;
+        }
+        best_sectors
+            .into_iter()
+            .try_fold(0.0, |sum, sector| Some(sum + sector?.ms))
+            .map(Time::from)
+    }
+
+    /// Returns the average lap time of all valid laps in this session that were driven
+    /// entirely under green.
+    /// `None` if no such lap has been completed yet.
+    ///
+    /// This relies on the `caution` flag of each lap. Laps where it is not known if they were
+    /// driven under caution are not counted, which means this is only available in games that
+    /// tag laps with the flag state.
+    pub fn average_green_lap(&self) -> Option<Time> {
+        let (sum, count) = self
+            .entries
+            .values()
+            .flat_map(|entry| entry.laps.iter())
+            .filter(|lap| {
+                !*lap.invalid
+                    && lap.time.is_avaliable()
+                    && lap.caution.get_available().is_some_and(|caution| !caution)
+            })
+            .fold((0.0, 0), |(sum, count), lap| (sum + lap.time.ms, count + 1));
+        (count > 0).then(|| Time::from(sum / count as f64))
+    }
+}
+
+/// The entry the gaps of the timing rows are relative to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum GapReference {
+    /// The gaps are relative to the leader of the session.
+    /// Lapped entries show the number of laps they are down instead of a time.
+    #[default]
+    Leader,
+    /// The gaps are relative to an entry, for example the focused car.
+    Entry(EntryId),
+}
+
+/// A row of a leaderboard created by [`Session::timing_rows`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingRow {
+    /// The id of the entry of this row.
+    pub entry_id: EntryId,
+    /// The position of the entry in the session.
+    pub position: i32,
+    /// The position of the entry among the entries of the same class.
+    pub class_position: i32,
+    /// The car number of the entry.
+    pub car_number: i32,
+    /// The full name of the current driver.
+    /// Empty if the current driver is not known.
+    pub driver_name: String,
+    /// The name of the class of the car.
+    pub class: String,
+    /// The best lap time of the entry.
+    pub best_lap: Option<Time>,
+    /// The time of the last lap of the entry.
+    pub last_lap: Option<Time>,
+    /// The gap to the reference entry, formatted as `+1.234`.
+    /// Empty for the reference entry itself and `-` if the gap is not known.
+    pub gap: String,
+    /// The gap to the entry one position ahead, formatted like the gap.
+    /// Empty for the first row.
+    pub interval: String,
+    /// If the entry is in the pitlane.
+    pub in_pits: bool,
+    /// The color to show the entry with.
+    pub color: Color,
+}
+
+/// The classification of a session created by [`Session::results`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionResults {
+    /// The id of the session.
+    pub session_id: SessionId,
+    /// The type of the session.
+    pub session_type: SessionType,
+    /// The result of every entry ordered by position.
+    pub results: Vec<SessionResult>,
+}
+
+/// The result of a single entry in a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionResult {
+    /// The position of the entry.
+    /// Zero or less if the entry was not classified.
+    pub position: i32,
+    /// The id of the entry.
+    pub entry_id: EntryId,
+    /// The best lap time of the entry.
+    pub best_lap: Option<Time>,
+    /// The number of laps the entry completed.
+    pub laps_completed: i32,
+    /// The time the entry is behind the leader.
+    pub time_behind_leader: Option<Time>,
+}
+
+/// Format a gap in seconds with a sign.
+fn format_gap(gap: &Time) -> String {
+    let sign = if gap.ms < 0.0 { "-" } else { "+" };
+    format!("{sign}{:.3}", gap.ms.abs() / 1000.0)
+}
+
+/// The rules of a session.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionRules {
+    /// The number of incidents a driver can have before being disqualified.
+    ///
+    /// ### Availability:
+    /// - **iRacing:**
+    /// Not available when the weekend options do not contain the limit.
+    pub incident_limit: Value<Limit<i32>>,
+    /// The number of fast repairs an entry can use.
+    ///
+    /// ### Availability:
+    /// - **iRacing:**
+    /// Not available when the weekend options do not contain the limit.
+    pub fast_repairs: Value<Limit<i32>>,
+    /// The number of joker laps an entry must drive.
+    pub joker_laps: i32,
+}
+
+/// The state of the connection of an adapter to the game.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionState {
+    /// The adapter waits for the game to start.
+    Connecting,
+    /// The adapter is connected to the game.
+    Connected,
+    /// The adapter is not connected to the game.
+    #[default]
+    Disconnected,
+}
+
+/// The game an adapter connects to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameKind {
+    /// The game is not known yet.
+    #[default]
+    Unknown,
+    /// Assetto Corsa Competizione.
+    Acc,
+    /// iRacing.
+    IRacing,
+    /// The dummy adapter.
+    Dummy,
+    /// The demo adapter.
+    Demo,
+    /// The playback of a recording.
+    Replay,
+}
+
+impl Display for GameKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameKind::Unknown => write!(f, "Unknown"),
+            GameKind::Acc => write!(f, "Assetto Corsa Competizione"),
+            GameKind::IRacing => write!(f, "iRacing"),
+            GameKind::Dummy => write!(f, "Dummy"),
+            GameKind::Demo => write!(f, "Demo"),
+            GameKind::Replay => write!(f, "Replay"),
+        }
+    }
+}
+
+/// A limit that may be unlimited.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Limit<T> {
+    /// There is no limit.
+    #[default]
+    Unlimited,
+    /// The limit is reached at this value.
+    Limited(T),
+}
+
+impl<T: Display> Display for Limit<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Limit::Unlimited => write!(f, "unlimited"),
+            Limit::Limited(limit) => limit.fmt(f),
+        }
+    }
+}
+
+/// The state of the safety car.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafetyCarState {
+    /// If the safety car is deployed.
+    pub deployed: bool,
+    /// The entry directly behind the safety car that the safety car is picking up.
+    /// `None` if the safety car is not deployed or the field has not been lined up yet.
+    pub picking_up: Option<EntryId>,
+}
+
+/// The start of a session phase.
+#[derive(Debug, Clone)]
+pub struct PhaseStart {
+    /// The instant the phase started.
+    pub instant: Instant,
+    /// The distance driven of the leader when the phase started.
+    pub leader_distance: f32,
+}
+
+/// The pit window of a session.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct PitWindow {
+    /// The first lap on which a mandatory stop counts.
+    pub open_lap: i32,
+    /// The last lap on which a mandatory stop counts.
+    pub close_lap: i32,
+    /// The number of stops every entry must make during the session.
+    pub mandatory_stops: i32,
+}
+
 /// Game specific session data.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub enum SessionGameData {
     #[default]
     None,
@@ -395,11 +1544,11 @@ pub enum SessionGameData {
 }
 
 /// The identifier for an entry.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct EntryId(pub i32);
 
 /// A team entry in the session.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Entry {
     /// The id for this entry.
     pub id: EntryId,
@@ -425,6 +1574,14 @@ pub struct Entry {
     pub car: Value<Car>,
     /// The car number for this entry.
     pub car_number: Value<i32>,
+    /// The primary color of the livery of this entry.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Livery colors are not available.
+    /// - **iRacing:**
+    /// Taken from the car design of the entry.
+    pub livery_color: Value<Color>,
     /// The nationality of the entry as a whole.
     ///
     /// ### Availability:
@@ -455,7 +1612,30 @@ pub struct Entry {
     pub orientation: Value<[f32; 3]>,
     /// The classification position of this entry.
     pub position: Value<i32>,
+    /// The position this entry started the race from.
+    ///
+    /// ### Availability:
+    /// Only available in a race session. The grid position is recorded when the race
+    /// goes green and is not changed afterwards. If the adapter connects after the start
+    /// of the race the grid position is not known.
+    pub grid_position: Value<i32>,
+    /// The projected position of this entry at the next timing line.
+    ///
+    /// This is always an estimate. It extrapolates the progress of every entry using the
+    /// sector times of its last lap and may differ from `position` while entries with different
+    /// pace are in the middle of a sector.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Only available once the leader has completed a lap with split times.
+    /// - **iRacing:**
+    /// Split times are not available in iRacing. Not available.
+    pub projected_position: Value<i32>,
     /// The spline position around the track from 0 to 1.
+    ///
+    /// ### Availability:
+    /// - **iRacing:**
+    /// Keeps the last known position while the car is not on track.
     pub spline_pos: Value<f32>,
     /// The ammount of laps completed by this entry.
     pub lap_count: Value<i32>,
@@ -506,14 +1686,91 @@ pub struct Entry {
     pub time_behind_position_ahead: Value<Time>,
     /// If the entry is currently in the pitlane or not.
     pub in_pits: Value<bool>,
+    /// The number of pit stops this entry has completed in the current session.
+    ///
+    /// A pit stop is counted once per visit to the pitlane when the entry stops.
+    /// Driving through the pitlane is not a pit stop.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// A stop is counted when the speed of the car is zero in the pitlane.
+    /// - **iRacing:**
+    /// A stop is counted when the car is in its pit stall.
+    pub pit_stops_completed: Value<i32>,
+    /// The time the entry has spent in the pitlane during the current pit stop.
+    ///
+    /// ### Availability:
+    /// Only available while the entry is in the pitlane.
+    /// The time is measured by the adapter from the moment it sees the entry enter the pitlane.
+    pub pit_time: Value<Time>,
+    /// The time the entry spent in the pitlane during its last pit stop.
+    ///
+    /// ### Availability:
+    /// Only available after the entry has left the pitlane at least once in the session.
+    /// The time is measured by the adapter, from the moment it sees the entry enter the pitlane
+    /// until it sees the entry leave the pitlane. It includes the time to drive through the pitlane.
+    pub last_pit_duration: Value<Time>,
+    /// The number of mandatory pit stops this entry still has to make.
+    ///
+    /// `None` if the session has no pit window.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available, the pit window is not part of the broadcasting data.
+    /// - **iRacing:**
+    /// Not available.
+    pub mandatory_stops_remaining: Value<Option<i32>>,
     /// The gear of the entry.
+    ///
+    /// ### Availability:
+    /// - **iRacing:**
+    /// Keeps the last known gear while the car is not on track.
     pub gear: Value<i32>,
     /// The current speed of the entry in m/s.
     ///
     /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Available.
     /// - **iRacing:**
-    /// The car speed is not implemented yet in iRacing.
+    /// The speed of the player car is available. For every other car the speed is
+    /// estimated from the change in spline position.
     pub speed: Value<f32>,
+    /// The inputs of the driver.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Only available for the car of the player and only if the adapter
+    /// was created with inputs enabled. iRacing does not provide inputs for other cars.
+    pub inputs: Value<Inputs>,
+    /// The state of the overtake aid of the car, like push to pass or boost.
+    /// `None` if the car does not have an overtake aid.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Available for series with push to pass.
+    pub overtake: Option<OvertakeState>,
+    /// The state of the brakes of the car.
+    /// `None` if the game does not provide the brake state for this car.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Only available for the car of the player and only if the adapter
+    /// was created with inputs enabled.
+    pub brakes: Option<BrakeState>,
+    /// The pit service that is requested or carried out for this car.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Only available for the car of the player.
+    pub pit_service: Value<PitServiceStatus>,
     /// If the entry is currently connected to the session.
     ///
     /// ### Availability:
@@ -529,6 +1786,16 @@ pub struct Entry {
     /// - **iRacing:**
     /// Stint time is not implemented for iRacing yet.
     pub stint_time: Value<Time>,
+    /// The completed stints of this entry in the order they were driven.
+    /// The stint that is currently driven is not part of this list. Its length is
+    /// available in `stint_time`.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available. Stints are not recorded for ACC yet and this list is always empty.
+    /// - **iRacing:**
+    /// Not available. Stints are not recorded for iRacing yet and this list is always empty.
+    pub stints: Vec<Stint>,
     /// The logical distance driven by this entry in laps.
     /// This is simply the lap count + the current lap progress from the spline position and
     /// can be used to sort entries into a realtime position.
@@ -552,8 +1819,207 @@ pub struct Entry {
     pub game_data: EntryGameData,
 }
 
+impl Entry {
+    /// Returns the number of positions this entry has gained since the start of the race.
+    /// A negative number means the entry has lost positions.
+    /// `None` if the grid position is not known.
+    pub fn positions_gained(&self) -> Option<i32> {
+        self.grid_position
+            .get_available()
+            .map(|grid_position| grid_position - *self.position)
+    }
+
+    /// Returns a color to represent this entry in graphs and tables.
+    ///
+    /// Uses the livery color if it is available. Otherwise a color from a fallback palette is
+    /// picked by the car number. The color only depends on the entry itself and does not
+    /// change between frames or sessions as long as the livery or car number does not change.
+    pub fn display_color(&self) -> Color {
+        match self.livery_color.get_available() {
+            Some(color) => *color,
+            None => Color::from_palette(*self.car_number),
+        }
+    }
+
+    /// Returns the number of laps this entry is behind the leader of the session.
+    ///
+    /// The leader is the entry in first position. If positions are not available the
+    /// entry with the most completed laps is used instead.
+    pub fn laps_down(&self, session: &Session) -> i32 {
+        let leader_laps = session
+            .entries
+            .values()
+            .find(|entry| entry.position.is_avaliable() && *entry.position == 1)
+            .or_else(|| {
+                session
+                    .entries
+                    .values()
+                    .max_by_key(|entry| *entry.lap_count)
+            })
+            .map_or(*self.lap_count, |leader| *leader.lap_count);
+        (leader_laps - *self.lap_count).max(0)
+    }
+
+    /// Returns if this entry has completed as many laps as the leader of the session.
+    pub fn is_on_lead_lap(&self, session: &Session) -> bool {
+        self.laps_down(session) == 0
+    }
+
+    /// Returns if this entry is lapping the other entry.
+    /// An entry is lapping another entry if it has driven at least one full lap more.
+    pub fn is_lapping(&self, other: &Entry) -> bool {
+        *self.distance_driven - *other.distance_driven >= 1.0
+    }
+
+    /// Returns if this entry is lapped by the other entry.
+    /// An entry is lapped by another entry if it has driven at least one full lap less.
+    pub fn is_lapped_by(&self, other: &Entry) -> bool {
+        other.is_lapping(self)
+    }
+
+    /// Returns the number of stints this entry has driven, including the current stint.
+    ///
+    /// Stints are not recorded by every game, see [`Entry::stints`] and [`Entry::stint_time`].
+    pub fn stint_count(&self) -> usize {
+        self.stints.len() + usize::from(self.has_stint_in_progress())
+    }
+
+    /// Returns the average length of the completed stints of this entry.
+    /// The current stint is not counted since it has not finished yet.
+    /// `None` if no stint has been completed or the game does not record stints.
+    pub fn average_stint_length(&self) -> Option<Time> {
+        if self.stints.is_empty() {
+            return None;
+        }
+        let total: f64 = self.stints.iter().map(|stint| stint.time.ms).sum();
+        Some(Time::from(total / self.stints.len() as f64))
+    }
+
+    /// Returns the average time of the last `last_n` valid laps of this entry.
+    ///
+    /// Invalid laps and laps without a time are skipped and do not count towards `last_n`.
+    /// If fewer than `last_n` valid laps exist the average of all valid laps is returned.
+    /// `None` if there is no valid lap.
+    pub fn average_lap_time(&self, last_n: usize) -> Option<Time> {
+        let (sum, count) = self
+            .laps
+            .iter()
+            .rev()
+            .filter(|lap| Model::is_valid_timed_lap(lap))
+            .take(last_n)
+            .fold((0.0, 0), |(sum, count), lap| (sum + lap.time.ms, count + 1));
+        (count > 0).then(|| Time::from(sum / count as f64))
+    }
+
+    /// Returns if this entry is currently in a stint.
+    fn has_stint_in_progress(&self) -> bool {
+        self.stint_time
+            .get_available()
+            .is_some_and(|time| time.ms > 0.0)
+    }
+}
+
+/// The force feedback settings of a steering wheel.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FfbInfo {
+    /// The strength of the force feedback as the torque in Nm at full force.
+    pub max_force_nm: f32,
+    /// The peak torque of the wheel in Nm.
+    pub peak_force_nm: f32,
+    /// If the force feedback uses a linear mapping of the torque.
+    pub use_linear: bool,
+}
+
+/// The state of the overtake aid of a car.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OvertakeState {
+    /// If the overtake aid is currently active.
+    pub active: bool,
+    /// The number of activations that are left.
+    ///
+    /// ### Availability:
+    /// - **iRacing:**
+    /// Outside of races this is the number of times push to pass was used.
+    pub remaining: i32,
+}
+
+/// The state of the brakes of a car.
+///
+/// The brakes are ordered front left, front right, rear left, rear right.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BrakeState {
+    /// The pressure in the brake line of each brake.
+    pub line_pressure: [Pressure; 4],
+    /// The share of the brake force on the front axle in percent.
+    /// `None` if the car does not have an adjustable brake bias.
+    pub bias: Option<f32>,
+}
+
+/// The state of the pit service of a car.
+///
+/// The tires are ordered front left, front right, rear left, rear right.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PitServiceStatus {
+    /// The progress of the pit service.
+    pub state: PitServiceState,
+    /// If the car is refueled.
+    pub fueling: bool,
+    /// The amount of fuel that is added in liters.
+    /// For an electric car this is the energy in kWh.
+    /// `None` if the amount is not known.
+    pub fuel_to_add: Option<f32>,
+    /// If the tire is changed.
+    pub changing_tires: [bool; 4],
+    /// If a fast repair is used.
+    pub fast_repair: bool,
+    /// The pressure the new tires are set to.
+    pub tire_pressures: [Pressure; 4],
+}
+
+/// The progress of the pit service.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PitServiceState {
+    /// No service is carried out.
+    #[default]
+    None,
+    /// The car is being serviced.
+    InProgress,
+    /// The service is complete.
+    Complete,
+    /// The car is not parked correctly in the pit box and cannot be serviced.
+    NotInPitBox,
+    /// The damage of the car cannot be repaired.
+    CannotRepair,
+}
+
+/// The inputs of a driver.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct Inputs {
+    /// Throttle input from 0 (off) to 1 (full throttle).
+    pub throttle: f32,
+    /// Brake input from 0 (released) to 1 (full pedal force).
+    pub brake: f32,
+    /// Clutch input from 0 (disengaged) to 1 (fully engaged).
+    pub clutch: f32,
+    /// The angle of the steering wheel.
+    pub steer: Angle,
+    /// The selected gear. -1 is reverse and 0 is neutral.
+    pub gear: i32,
+    /// The engine rpm.
+    pub rpm: f32,
+}
+
+/// A completed stint of an entry.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Stint {
+    /// Id of the driver that drove this stint.
+    pub driver_id: DriverId,
+    /// The length of the stint.
+    pub time: Time,
+}
+
 /// Game specific entry data.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub enum EntryGameData {
     #[default]
     None,
@@ -561,11 +2027,11 @@ pub enum EntryGameData {
 }
 
 /// An iddentifier for a driver.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct DriverId(pub i32);
 
 /// A Driver in a entry.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Driver {
     /// The id of the driver.
     pub id: DriverId,
@@ -599,7 +2065,7 @@ pub struct Driver {
 }
 
 /// Data about a single lap.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Lap {
     /// The lap time of this lap.
     ///
@@ -624,19 +2090,69 @@ pub struct Lap {
     /// For the current lap this value is not know and all current laps are valid
     /// as a default.
     pub invalid: Value<bool>,
+    /// If any part of this lap was driven under a full course caution.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Only available for completed laps. The flag is sampled while the lap is driven,
+    /// a caution that is thrown shortly before the lap is completed may be missed.
+    pub caution: Value<bool>,
     /// Id of the driver that drove this lap.
     pub driver_id: Option<DriverId>,
     /// Id of the entry that drove this lap.
     pub entry_id: Option<EntryId>,
+    /// Metrics about how the pedals were used during this lap.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Not available.
+    /// - **iRacing:**
+    /// Only available for the laps of the player in `Entry::laps` and in the
+    /// `LapCompleted` event, and only if the adapter was created with inputs enabled.
+    /// The first lap after the adapter connects is not measured completely and has no metrics.
+    pub driving_metrics: Option<DrivingMetrics>,
+}
+
+/// Metrics about how the pedals were used during a lap.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DrivingMetrics {
+    /// The time where neither the throttle nor the brake was pressed.
+    pub coasting_time: Time,
+    /// The share of the lap in percent where the throttle and the brake were pressed
+    /// at the same time.
+    pub trail_braking: f32,
+    /// The share of the lap in percent where the throttle was fully pressed.
+    pub full_throttle: f32,
+}
+
+impl Lap {
+    /// Returns `true` if the sum of the splits matches the lap time within the tolerance.
+    ///
+    /// Laps without a lap time or without splits have nothing to compare and are
+    /// considered consistent.
+    pub fn splits_consistent(&self, tolerance: Time) -> bool {
+        let (Some(time), Some(splits)) = (self.time.get_available(), self.splits.get_available())
+        else {
+            return true;
+        };
+        if splits.is_empty() {
+            return true;
+        }
+        let sum: f64 = splits.iter().map(|split| split.ms).sum();
+        (sum - time.ms).abs() <= tolerance.ms
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct CarCategory {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
 }
 
 /// The type of the session.
-#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum SessionType {
     /// A practice session scored by best lap time.
     Practice,
@@ -682,7 +2198,7 @@ pub enum ScoringType {
 }
 
 /// The phase of the current session.
-#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Deserialize)]
 pub enum SessionPhase {
     /// The session phase is unknown or unavailable
     #[default]
@@ -723,7 +2239,19 @@ impl SessionPhase {
     }
 }
 
-#[derive(Debug)]
+/// An event together with the time it happened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedEvent {
+    /// The elapsed session time when the event happened.
+    ///
+    /// ### Availability:
+    /// Only available if the current session is a timed session.
+    pub time: Value<Time>,
+    /// The event.
+    pub event: Event,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Event {
     /// When an entry joins the session.
     EntryConnected {
@@ -750,9 +2278,39 @@ pub enum Event {
     /// This delay can cause multiple 'LapCompleted' events to be send out at the same time and in
     /// the wrong order.
     LapCompleted(LapCompleted),
+    /// When the focused entry changes.
+    ///
+    /// This is send for every change of focus, regardless of if the change was requested
+    /// by an adapter command or by the user directly in the game.
+    FocusChanged {
+        /// Id of the entry that is now focused.
+        /// `None` if no entry is focused.
+        entry: Option<EntryId>,
+    },
+    /// When the safety car is deployed in a session.
+    SafetyCarDeployed(SessionId),
+    /// When the safety car comes in and the session is about to go green again.
+    SafetyCarIn(SessionId),
+    /// When the driver of an entry changes.
+    ///
+    /// ### Availability:
+    /// - **Assetto Corsa Competizione:**
+    /// Available.
+    /// - **iRacing:**
+    /// Not available. Each car only has a single driver.
+    DriverChange {
+        /// Id of the entry that changed drivers.
+        entry: EntryId,
+        /// The driver that drove the car before the change.
+        from: DriverId,
+        /// The driver that drives the car now.
+        to: DriverId,
+    },
+    /// When the state of the connection to the game changes.
+    ConnectionStateChanged(ConnectionState),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LapCompleted {
     pub lap: Lap,
     pub is_session_best: bool,
@@ -761,7 +2319,7 @@ pub struct LapCompleted {
 }
 
 /// Describes the day a session takes part in.
-#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum Day {
     Monday,
     Thuesday,
@@ -776,17 +2334,26 @@ pub enum Day {
 /// Describes the category of a car.
 impl CarCategory {
     pub const fn new(name: &'static str) -> Self {
-        Self { name }
+        Self {
+            name: Cow::Borrowed(name),
+        }
+    }
+
+    /// Create a category with a name that is only known at runtime.
+    pub fn new_owned(name: String) -> Self {
+        Self {
+            name: Cow::Owned(name),
+        }
     }
 }
 
 /// A car model.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Car {
     /// A car model where the data is known at compile time.
     Static {
-        name: &'static str,
-        manufacturer: &'static str,
+        name: Cow<'static, str>,
+        manufacturer: Cow<'static, str>,
         category: CarCategory,
     },
     /// A car model where the data is only knonw at runtime.
@@ -814,8 +2381,8 @@ impl Car {
         category: CarCategory,
     ) -> Self {
         Self::Static {
-            name,
-            manufacturer,
+            name: Cow::Borrowed(name),
+            manufacturer: Cow::Borrowed(manufacturer),
             category,
         }
     }
@@ -855,14 +2422,17 @@ impl Car {
 }
 
 /// Nationality.
-#[derive(Debug, Default, PartialEq, Eq, Clone)]
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Nationality {
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
 }
 
 impl Nationality {
     const fn new(name: &'static str) -> Self {
-        Self { name }
+        Self {
+            name: Cow::Borrowed(name),
+        }
     }
 
     pub const NONE: Self = Self::new("None");
@@ -1072,7 +2642,7 @@ impl Nationality {
 }
 
 /// Set of possible camera views.
-#[derive(Debug, Default, Clone, Hash, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Camera {
     /// No camera is active.
     #[default]
@@ -1108,7 +2678,7 @@ impl Display for Camera {
 }
 
 /// Game specific camera options.
-#[derive(Debug, Default, Clone, Hash, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum GameCamera {
     #[default]
     None,
@@ -1125,3 +2695,889 @@ impl Display for GameCamera {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lap(ms: i32, caution: Value<bool>) -> Lap {
+        Lap {
+            time: Value::new(Time::from(ms)),
+            invalid: Value::new(false),
+            caution,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn summary_of_model() {
+        let mut model = Model {
+            event_name: Value::new("Test event".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(model.summary(), "Test event | no session");
+
+        let mut session = Session {
+            session_type: Value::new(SessionType::Race),
+            phase: Value::new(SessionPhase::Active),
+            track_name: Value::new("Spa".to_owned()),
+            time_remaining: Value::new(Time::from(754_000)),
+            ..Default::default()
+        };
+        for (id, position, car_number) in [(0, 2, 7), (1, 1, 42)] {
+            let entry = Entry {
+                id: EntryId(id),
+                position: Value::new(position),
+                car_number: Value::new(car_number),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        let id = model.add_session(session);
+        model.current_session = Some(id);
+        assert_eq!(
+            model.summary(),
+            "Test event | Spa | Race Active | 2 entries | leader #42 | 12:34 remaining"
+        );
+    }
+
+    #[test]
+    fn estimated_time_to_green_from_leader_pace() {
+        let mut session = Session {
+            phase: Value::new(SessionPhase::Formation),
+            // The leader started the formation lap on the grid just before the line
+            // and has covered a quarter of the lap in 30s.
+            phase_start: Some(PhaseStart {
+                instant: Instant::now() - Duration::from_secs(30),
+                leader_distance: 0.95,
+            }),
+            ..Default::default()
+        };
+        let leader = Entry {
+            distance_driven: Value::new(1.2),
+            ..Default::default()
+        };
+        session.entries.insert(leader.id, leader);
+
+        let estimate = session.estimated_time_to_green().unwrap();
+        assert!((estimate.ms - 96_000.0).abs() < 500.0, "{}", estimate.ms);
+
+        session.phase.set(SessionPhase::Active);
+        assert_eq!(session.estimated_time_to_green(), None);
+    }
+
+    #[test]
+    fn events_round_trip() {
+        let mut model = Model::default();
+        let id = model.add_session(Session {
+            session_time: Value::new(Time::from(600_000)),
+            time_remaining: Value::new(Time::from(450_000)),
+            ..Default::default()
+        });
+        model.current_session = Some(id);
+
+        let events = vec![
+            Event::EntryConnected {
+                id: EntryId(3),
+                reconnect: true,
+            },
+            Event::EntryDisconnected(EntryId(4)),
+            Event::SessionChanged(id),
+            Event::SessionPhaseChanged(id, SessionPhase::Formation),
+            Event::LapCompleted(LapCompleted {
+                lap: lap(91_234, Value::new(false)),
+                is_session_best: true,
+                is_entry_best: true,
+                is_driver_best: false,
+            }),
+            Event::FocusChanged {
+                entry: Some(EntryId(5)),
+            },
+            Event::FocusChanged { entry: None },
+            Event::SafetyCarDeployed(id),
+            Event::SafetyCarIn(id),
+            Event::DriverChange {
+                entry: EntryId(5),
+                from: DriverId(0),
+                to: DriverId(1),
+            },
+            Event::ConnectionStateChanged(ConnectionState::Connected),
+        ];
+        for event in events {
+            model.push_event(event);
+        }
+
+        for event in model.events.iter() {
+            assert_eq!(*event.time, Time::from(150_000));
+            let data = serde_yaml::to_string(event).unwrap();
+            let read: TimedEvent = serde_yaml::from_str(&data).unwrap();
+            assert_eq!(format!("{:?}", read), format!("{:?}", event));
+        }
+    }
+
+    #[test]
+    fn connection_state_changes() {
+        let mut model = Model::default();
+        model.set_connection_state(ConnectionState::Connecting);
+        assert!(!model.connected);
+        model.set_connection_state(ConnectionState::Connected);
+        model.set_connection_state(ConnectionState::Connected);
+        assert!(model.connected);
+        model.set_connection_state(ConnectionState::Disconnected);
+        assert!(!model.connected);
+
+        let states: Vec<_> = model
+            .recent_events(0)
+            .iter()
+            .map(|event| match event.event {
+                Event::ConnectionStateChanged(state) => state,
+                _ => panic!("Unexpected event"),
+            })
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                ConnectionState::Connecting,
+                ConnectionState::Connected,
+                ConnectionState::Disconnected
+            ]
+        );
+    }
+
+    #[test]
+    fn session_tabs_in_order() {
+        let mut model = Model::default();
+        for (session_type, phase) in [
+            (SessionType::Practice, SessionPhase::Finished),
+            (SessionType::Qualifying, SessionPhase::Finished),
+            (SessionType::Race, SessionPhase::Active),
+        ] {
+            model.add_session(Session {
+                session_type: Value::new(session_type),
+                phase: Value::new(phase),
+                ..Default::default()
+            });
+        }
+        assert_eq!(
+            model.session_tabs(),
+            vec![
+                (SessionId(0), SessionType::Practice, SessionPhase::Finished),
+                (
+                    SessionId(1),
+                    SessionType::Qualifying,
+                    SessionPhase::Finished
+                ),
+                (SessionId(2), SessionType::Race, SessionPhase::Active),
+            ]
+        );
+    }
+
+    #[test]
+    fn unknown_track_length() {
+        let mut session = Session::default();
+        assert_eq!(session.known_track_length(), None);
+        assert_eq!(session.track_distance(0.5), None);
+
+        // A track length of zero is reported before the track data was loaded.
+        session.track_length.set(Distance::from_meter(0.0));
+        assert_eq!(session.known_track_length(), None);
+        assert_eq!(session.track_distance(0.5), None);
+        assert_eq!(session.laps_for_distance(Distance::from_meter(100.0)), None);
+
+        session.track_length.set(Distance::from_meter(4_000.0));
+        assert_eq!(
+            session.track_distance(0.5),
+            Some(Distance::from_meter(2_000.0))
+        );
+        assert_eq!(
+            session.laps_for_distance(Distance::from_meter(1_000.0)),
+            Some(0.25)
+        );
+    }
+
+    fn gap_session() -> Session {
+        let mut session = Session::default();
+        for (id, gap) in [(0, Some(0)), (1, Some(1_500)), (2, Some(4_000)), (3, None)] {
+            let entry = Entry {
+                id: EntryId(id),
+                time_behind_leader: gap
+                    .map_or_else(Value::default, |gap| Value::new(Time::from(gap))),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        session
+    }
+
+    #[test]
+    fn gaps_relative_to_leader() {
+        let gaps = gap_session().gaps_relative_to(EntryId(0));
+        assert_eq!(gaps.len(), 3);
+        assert_eq!(gaps[&EntryId(0)], Time::from(0));
+        assert_eq!(gaps[&EntryId(1)], Time::from(1_500));
+        assert_eq!(gaps[&EntryId(2)], Time::from(4_000));
+    }
+
+    #[test]
+    fn gaps_relative_to_player() {
+        let session = gap_session();
+        let gaps = session.gaps_relative_to(EntryId(1));
+        assert_eq!(gaps[&EntryId(0)], Time::from(-1_500));
+        assert_eq!(gaps[&EntryId(1)], Time::from(0));
+        assert_eq!(gaps[&EntryId(2)], Time::from(2_500));
+        assert!(!gaps.contains_key(&EntryId(3)));
+
+        assert!(session.gaps_relative_to(EntryId(3)).is_empty());
+    }
+
+    #[test]
+    fn average_green_lap_excludes_caution_laps() {
+        let mut session = Session::default();
+        let entry = Entry {
+            laps: vec![
+                lap(90_000, Value::new(false)),
+                lap(150_000, Value::new(true)),
+                lap(92_000, Value::new(false)),
+                lap(91_000, Value::default()),
+            ],
+            ..Default::default()
+        };
+        session.entries.insert(entry.id, entry);
+        assert_eq!(session.average_green_lap(), Some(Time::from(91_000)));
+    }
+
+    #[test]
+    fn average_green_lap_without_green_laps() {
+        let mut session = Session::default();
+        let entry = Entry {
+            laps: vec![lap(150_000, Value::new(true))],
+            ..Default::default()
+        };
+        session.entries.insert(entry.id, entry);
+        assert_eq!(session.average_green_lap(), None);
+    }
+
+    #[test]
+    fn display_color_is_stable_for_entry() {
+        let entry = Entry {
+            id: EntryId(7),
+            car_number: Value::new(42),
+            ..Default::default()
+        };
+        let same_entry = entry.clone();
+        assert_eq!(entry.display_color(), same_entry.display_color());
+        assert_eq!(entry.display_color(), Color::from_palette(42));
+    }
+
+    #[test]
+    fn display_color_prefers_livery() {
+        let livery = Color::from_rgb(0x12, 0x34, 0x56);
+        let entry = Entry {
+            car_number: Value::new(42),
+            livery_color: Value::new(livery),
+            ..Default::default()
+        };
+        assert_eq!(entry.display_color(), livery);
+    }
+
+    fn race_entry(id: i32, position: i32, laps: i32, spline_pos: f32) -> Entry {
+        Entry {
+            id: EntryId(id),
+            position: Value::new(position),
+            lap_count: Value::new(laps),
+            distance_driven: Value::new(laps as f32 + spline_pos),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn laps_down_to_leader() {
+        let mut session = Session::default();
+        for entry in [
+            race_entry(1, 1, 50, 0.3),
+            race_entry(2, 2, 49, 0.2),
+            race_entry(3, 3, 48, 0.5),
+        ] {
+            session.entries.insert(entry.id, entry);
+        }
+        let leader = &session.entries[&EntryId(1)];
+        let one_down = &session.entries[&EntryId(2)];
+        let two_down = &session.entries[&EntryId(3)];
+
+        assert_eq!(leader.laps_down(&session), 0);
+        assert_eq!(one_down.laps_down(&session), 1);
+        assert_eq!(two_down.laps_down(&session), 2);
+        assert!(leader.is_on_lead_lap(&session));
+        assert!(!one_down.is_on_lead_lap(&session));
+        assert!(!two_down.is_on_lead_lap(&session));
+    }
+
+    #[test]
+    fn leader_and_green_laps() {
+        let mut session = Session::default();
+        assert_eq!(session.leader_lap(), 0);
+        assert_eq!(session.green_laps(), 0);
+
+        let mut leader = race_entry(1, 1, 4, 0.3);
+        leader.laps = vec![
+            lap(90_000, Value::new(false)),
+            lap(150_000, Value::new(true)),
+            lap(151_000, Value::new(true)),
+            lap(91_000, Value::default()),
+        ];
+        let mut lapped = race_entry(2, 2, 3, 0.8);
+        lapped.laps = vec![
+            lap(95_000, Value::new(true)),
+            lap(150_000, Value::new(true)),
+            lap(151_000, Value::new(true)),
+        ];
+        for entry in [leader, lapped, race_entry(3, 3, 4, 0.1)] {
+            session.entries.insert(entry.id, entry);
+        }
+        assert_eq!(session.leader_lap(), 4);
+        assert_eq!(session.green_laps(), 2);
+    }
+
+    #[test]
+    fn entries_in_position_order() {
+        let mut session = Session::default();
+        for (id, position, class) in [
+            (1, 0, "GT3"),
+            (2, 3, "GT3"),
+            (3, 1, "GT4"),
+            (4, 2, "GT3"),
+            (5, 4, "GT4"),
+        ] {
+            let mut entry = race_entry(id, position, 10, 0.0);
+            entry.car = Value::new(Car::new_static(
+                "Car",
+                "Manufacturer",
+                CarCategory::new(class),
+            ));
+            session.entries.insert(entry.id, entry);
+        }
+        let ids = |entries: Vec<&Entry>| entries.iter().map(|e| e.id.0).collect::<Vec<_>>();
+        assert_eq!(ids(session.entries_by_position()), vec![3, 4, 2, 5, 1]);
+        assert_eq!(
+            ids(session.entries_by_class_position()),
+            vec![3, 5, 4, 2, 1]
+        );
+    }
+
+    #[test]
+    fn best_lap_of_class() {
+        let mut session = Session::default();
+        let mut invalid_lap = lap(80_000, Value::new(false));
+        invalid_lap.invalid = Value::new(true);
+        for (id, class, laps) in [
+            (1, "GT3", vec![lap(92_000, Value::new(false)), invalid_lap]),
+            (
+                2,
+                "GT3",
+                vec![
+                    lap(i32::MAX, Value::new(false)),
+                    lap(91_000, Value::new(false)),
+                ],
+            ),
+            (3, "GT4", vec![lap(99_000, Value::new(false))]),
+        ] {
+            let mut entry = race_entry(id, id, 2, 0.0);
+            entry.car = Value::new(Car::new_static(
+                "Car",
+                "Manufacturer",
+                CarCategory::new(class),
+            ));
+            entry.laps = laps;
+            session.entries.insert(entry.id, entry);
+        }
+        let best = |class| {
+            session
+                .best_lap_for_class(&CarCategory::new(class))
+                .map(|lap| *lap.time)
+        };
+        assert_eq!(best("GT3"), Some(Time::from(91_000)));
+        assert_eq!(best("GT4"), Some(Time::from(99_000)));
+        assert_eq!(best("TCR"), None);
+    }
+
+    #[test]
+    fn average_lap_time_of_entry() {
+        let mut invalid_lap = lap(80_000, Value::new(false));
+        invalid_lap.invalid = Value::new(true);
+        let mut entry = Entry {
+            laps: vec![
+                lap(100_000, Value::new(false)),
+                lap(90_000, Value::new(false)),
+                invalid_lap.clone(),
+                lap(i32::MAX, Value::new(false)),
+                lap(92_000, Value::new(false)),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(entry.average_lap_time(2), Some(Time::from(91_000)));
+        // Fewer valid laps than requested.
+        assert_eq!(entry.average_lap_time(5), Some(Time::from(94_000)));
+        assert_eq!(entry.average_lap_time(0), None);
+
+        entry.laps = vec![invalid_lap.clone(), invalid_lap];
+        assert_eq!(entry.average_lap_time(3), None);
+    }
+
+    #[test]
+    fn results_of_session() {
+        let mut session = Session::default();
+        for (id, position, laps, gap) in [(1, 2, 20, 4_000), (2, 1, 20, 0), (3, 0, 12, 0)] {
+            let mut entry = race_entry(id, position, laps, 0.0);
+            entry.best_lap = Value::new(Some(lap(90_000 + id, Value::new(false))));
+            if position > 0 {
+                entry.time_behind_leader = Value::new(Time::from(gap));
+            }
+            session.entries.insert(entry.id, entry);
+        }
+        let results = session.results();
+        session.entries.get_mut(&EntryId(1)).unwrap().position = Value::new(1);
+
+        let ids: Vec<i32> = results.results.iter().map(|r| r.entry_id.0).collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+        let second = &results.results[1];
+        assert_eq!(second.position, 2);
+        assert_eq!(second.best_lap, Some(Time::from(90_001)));
+        assert_eq!(second.laps_completed, 20);
+        assert_eq!(second.time_behind_leader, Some(Time::from(4_000)));
+        assert_eq!(results.results[2].time_behind_leader, None);
+    }
+
+    #[test]
+    fn gap_to_car_ahead() {
+        let mut session = Session::default();
+        // The car in second position has disconnected.
+        for (id, position, gap) in [(1, 1, 0), (2, 3, 2_500), (3, 4, 3_750)] {
+            let mut entry = race_entry(id, position, 10, 0.0);
+            entry.time_behind_leader = Value::new(Time::from(gap));
+            session.entries.insert(entry.id, entry);
+        }
+        assert_eq!(session.gap_ahead(EntryId(1)), None);
+        assert_eq!(session.gap_ahead(EntryId(2)), Some(Time::from(2_500)));
+        assert_eq!(session.gap_ahead(EntryId(3)), Some(Time::from(1_250)));
+        assert_eq!(session.gap_ahead(EntryId(4)), None);
+    }
+
+    #[test]
+    fn entry_by_car_number() {
+        let mut session = Session::default();
+        for (id, car_number) in [(3, 7), (1, 42), (2, 7)] {
+            let entry = Entry {
+                id: EntryId(id),
+                car_number: Value::new(car_number),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        assert_eq!(
+            session.entry_by_car_number(42).map(|e| e.id),
+            Some(EntryId(1))
+        );
+        // The lowest id wins when numbers collide.
+        assert_eq!(
+            session.entry_by_car_number(7).map(|e| e.id),
+            Some(EntryId(2))
+        );
+        assert!(session.entry_by_car_number(1).is_none());
+
+        session.entry_by_car_number_mut(7).unwrap().team_name = Value::new("Team".to_owned());
+        assert_eq!(*session.entries[&EntryId(2)].team_name, "Team");
+    }
+
+    #[test]
+    fn timing_rows_of_session() {
+        let mut session = Session::default();
+        for (id, position, laps, gap, class) in [
+            (1, 3, 49, 95_000, "GT3"),
+            (2, 2, 50, 5_500, "GT4"),
+            (3, 1, 50, 0, "GT3"),
+        ] {
+            let mut entry = race_entry(id, position, laps, 0.5);
+            entry.car_number = Value::new(id * 10);
+            entry.time_behind_leader = Value::new(Time::from(gap));
+            entry.car = Value::new(Car::new_static(
+                "Car",
+                "Manufacturer",
+                CarCategory::new(class),
+            ));
+            let driver = Driver {
+                id: DriverId(id),
+                first_name: Value::new("Max".to_owned()),
+                last_name: Value::new(format!("Driver{id}")),
+                ..Default::default()
+            };
+            entry.current_driver = driver.id;
+            entry.drivers.insert(driver.id, driver);
+            entry.laps = vec![lap(90_000 + id * 100, Value::default())];
+            session.entries.insert(entry.id, entry);
+        }
+
+        let rows = session.timing_rows(GapReference::Leader);
+        let summary: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                (
+                    row.entry_id.0,
+                    row.class_position,
+                    row.gap.as_str(),
+                    row.interval.as_str(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (3, 1, "", ""),
+                (2, 1, "+5.500", "+5.500"),
+                (1, 2, "+1 L", "+1 L")
+            ]
+        );
+        assert_eq!(rows[0].driver_name, "Max Driver3");
+        assert_eq!(rows[0].car_number, 30);
+        assert_eq!(rows[0].class, "GT3");
+        assert_eq!(rows[0].last_lap, Some(Time::from(90_300)));
+
+        let rows = session.timing_rows(GapReference::Entry(EntryId(2)));
+        let gaps: Vec<_> = rows.iter().map(|row| row.gap.as_str()).collect();
+        assert_eq!(gaps, vec!["-5.500", "", "+89.500"]);
+    }
+
+    #[test]
+    fn lapping_relationship() {
+        let leader = race_entry(1, 1, 50, 0.3);
+        let one_down = race_entry(2, 2, 49, 0.2);
+        let two_down = race_entry(3, 3, 48, 0.5);
+
+        assert!(leader.is_lapping(&one_down));
+        assert!(one_down.is_lapped_by(&leader));
+        assert!(leader.is_lapping(&two_down));
+        // Less than a full lap apart on the road.
+        assert!(!one_down.is_lapping(&two_down));
+        assert!(!two_down.is_lapped_by(&one_down));
+        assert!(!one_down.is_lapping(&leader));
+    }
+
+    fn stint(minutes: i32) -> Stint {
+        Stint {
+            driver_id: DriverId(0),
+            time: Time::from(minutes * 60_000),
+        }
+    }
+
+    #[test]
+    fn stint_aggregations() {
+        let entry = Entry {
+            id: EntryId(1),
+            stints: vec![stint(40), stint(50)],
+            stint_time: Value::new(Time::from(10 * 60_000)),
+            ..Default::default()
+        };
+        assert_eq!(entry.stint_count(), 3);
+        // The stint in progress is not part of the average.
+        assert_eq!(entry.average_stint_length(), Some(Time::from(45 * 60_000)));
+
+        let mut session = Session::default();
+        session.entries.insert(entry.id, entry);
+        assert_eq!(
+            session.longest_stint(),
+            Some((EntryId(1), Time::from(50 * 60_000)))
+        );
+
+        // A stint in progress that is longer than any completed stint.
+        let other = Entry {
+            id: EntryId(2),
+            stint_time: Value::new(Time::from(55 * 60_000)),
+            ..Default::default()
+        };
+        assert_eq!(other.stint_count(), 1);
+        assert_eq!(other.average_stint_length(), None);
+        session.entries.insert(other.id, other);
+        assert_eq!(
+            session.longest_stint(),
+            Some((EntryId(2), Time::from(55 * 60_000)))
+        );
+    }
+
+    #[test]
+    fn find_driver_by_name() {
+        let mut session = Session::default();
+        for (entry_id, names) in [
+            (0, vec!["Max Power"]),
+            (1, vec!["Lenny Lounge", "Anna Power"]),
+        ] {
+            let mut entry = Entry {
+                id: EntryId(entry_id),
+                ..Default::default()
+            };
+            for (driver_id, name) in names.into_iter().enumerate() {
+                let (first_name, last_name) = name.split_once(' ').unwrap();
+                let driver = Driver {
+                    id: DriverId(driver_id as i32),
+                    first_name: Value::new(first_name.to_owned()),
+                    last_name: Value::new(last_name.to_owned()),
+                    ..Default::default()
+                };
+                entry.drivers.insert(driver.id, driver);
+            }
+            session.entries.insert(entry.id, entry);
+        }
+        assert_eq!(session.all_drivers().count(), 3);
+
+        let (entry, driver) = session.find_driver("anna POWER").unwrap();
+        assert_eq!(entry.id, EntryId(1));
+        assert_eq!(driver.id, DriverId(1));
+        let (entry, _) = session.find_driver("lounge").unwrap();
+        assert_eq!(entry.id, EntryId(1));
+        assert!(session.find_driver("Nobody").is_none());
+    }
+
+    #[test]
+    fn entries_in_pits_ordered_by_pit_time() {
+        let mut session = Session::default();
+        for (id, in_pits, connected, pit_time) in [
+            (0, true, true, Some(5_000)),
+            (1, false, true, None),
+            (2, true, true, Some(20_000)),
+            (3, true, true, None),
+            // In the garage.
+            (4, true, false, None),
+        ] {
+            let entry = Entry {
+                id: EntryId(id),
+                in_pits: Value::new(in_pits),
+                connected: Value::new(connected),
+                pit_time: pit_time.map(Time::from).map(Value::new).unwrap_or_default(),
+                ..Default::default()
+            };
+            session.entries.insert(entry.id, entry);
+        }
+        let in_pits: Vec<EntryId> = session
+            .entries_in_pits()
+            .iter()
+            .map(|entry| entry.id)
+            .collect();
+        assert_eq!(in_pits, vec![EntryId(2), EntryId(0), EntryId(3)]);
+        assert_eq!(session.pit_lane_count(), 3);
+    }
+
+    #[test]
+    fn splits_consistent_with_lap_time() {
+        let lap = |time: i32, splits: &[i32]| Lap {
+            time: Value::new(Time::from(time)),
+            splits: Value::new(splits.iter().map(|ms| Time::from(*ms)).collect()),
+            ..Default::default()
+        };
+        assert!(lap(90_000, &[30_000, 35_000, 25_000]).splits_consistent(Time::from(0)));
+        assert!(lap(90_002, &[30_000, 35_000, 25_000]).splits_consistent(Time::from(5)));
+        // A missed sector.
+        assert!(!lap(90_000, &[30_000, 35_000]).splits_consistent(Time::from(5)));
+        assert!(!lap(90_000, &[30_000, 35_000, 30_000]).splits_consistent(Time::from(5)));
+        // Nothing to compare.
+        assert!(lap(90_000, &[]).splits_consistent(Time::from(0)));
+        assert!(Lap::default().splits_consistent(Time::from(0)));
+    }
+
+    #[test]
+    fn ideal_lap_from_best_sectors() {
+        let mut session = Session::default();
+        assert_eq!(session.ideal_lap(), None);
+
+        session.best_splits = [30_000, 35_000, 25_000].map(Time::from).into();
+        assert_eq!(
+            session.best_sectors(),
+            vec![
+                Some(Time::from(30_000)),
+                Some(Time::from(35_000)),
+                Some(Time::from(25_000))
+            ]
+        );
+        assert_eq!(session.ideal_lap(), Some(Time::from(90_000)));
+    }
+
+    #[test]
+    fn ideal_lap_missing_sector() {
+        let mut session = Session {
+            best_splits: vec![Time::from(30_000), Time::from(-1)],
+            ..Default::default()
+        };
+        assert_eq!(session.best_sectors(), vec![Some(Time::from(30_000)), None]);
+        assert_eq!(session.ideal_lap(), None);
+
+        // A sector without a split yet.
+        session.best_splits = vec![Time::from(30_000), Time::from(35_000)];
+        session.sector_count = Value::new(3);
+        assert_eq!(
+            session.best_sectors(),
+            vec![Some(Time::from(30_000)), Some(Time::from(35_000)), None]
+        );
+        assert_eq!(session.ideal_lap(), None);
+    }
+
+    #[test]
+    fn group_split_sessions() {
+        let mut model = Model::default();
+        for (session_type, name) in [
+            (SessionType::Practice, None),
+            (SessionType::Qualifying, Some("Q1")),
+            (SessionType::Qualifying, Some("Q2")),
+            (SessionType::Race, Some("HEAT 1")),
+            (SessionType::Race, Some("HEAT 2")),
+            (SessionType::Race, Some("FEATURE")),
+        ] {
+            model.add_session(Session {
+                session_type: Value::new(session_type),
+                name: name.map(str::to_owned).map(Value::new).unwrap_or_default(),
+                ..Default::default()
+            });
+        }
+        let groups: Vec<(String, Vec<SessionId>)> = model
+            .session_groups()
+            .into_iter()
+            .map(|group| (group.name, group.sessions))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("Practice".to_owned(), vec![SessionId(0)]),
+                ("Q".to_owned(), vec![SessionId(1), SessionId(2)]),
+                ("HEAT".to_owned(), vec![SessionId(3), SessionId(4)]),
+                ("FEATURE".to_owned(), vec![SessionId(5)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn recent_events_by_watermark() {
+        let mut model = Model::default();
+        model.push_event(Event::SessionChanged(SessionId(0)));
+        let watermark = model.next_event_id();
+        assert_eq!(watermark, 1);
+
+        model.push_event(Event::SessionChanged(SessionId(1)));
+        let recent = model.recent_events(watermark);
+        assert_eq!(recent.len(), 1);
+        assert!(matches!(
+            recent[0].event,
+            Event::SessionChanged(SessionId(1))
+        ));
+        assert_eq!(model.recent_events(model.next_event_id()).len(), 0);
+
+        // Clearing does not change the sequence ids.
+        model.clear_events();
+        assert_eq!(model.next_event_id(), 2);
+        assert_eq!(model.recent_events(watermark).len(), 0);
+    }
+
+    #[test]
+    fn event_buffer_is_bounded() {
+        let mut model = Model::default();
+        for id in 0..EVENT_BUFFER_CAPACITY + 10 {
+            model.push_event(Event::SessionChanged(SessionId(id)));
+        }
+        assert_eq!(model.events.len(), EVENT_BUFFER_CAPACITY);
+        assert_eq!(model.next_event_id(), EVENT_BUFFER_CAPACITY as u64 + 10);
+
+        // The first ten events were removed and are missed.
+        let recent = model.recent_events(0);
+        assert_eq!(recent.len(), EVENT_BUFFER_CAPACITY);
+        assert!(matches!(
+            recent[0].event,
+            Event::SessionChanged(SessionId(10))
+        ));
+        let recent = model.recent_events(EVENT_BUFFER_CAPACITY as u64 + 8);
+        assert!(matches!(
+            recent,
+            [TimedEvent {
+                event: Event::SessionChanged(SessionId(id)),
+                ..
+            }, _] if *id == EVENT_BUFFER_CAPACITY + 8
+        ));
+    }
+
+    #[test]
+    fn event_subscriber_receives_events() {
+        let mut model = Model::default();
+        let (sender, receiver) = std::sync::mpsc::sync_channel(2);
+        model.add_event_subscriber(sender);
+
+        for id in 0..3 {
+            model.push_event(Event::SessionChanged(SessionId(id)));
+        }
+        model.events.clear();
+
+        // The third event did not fit in the queue and was dropped.
+        let received: Vec<_> = receiver.try_iter().map(|event| event.event).collect();
+        assert!(matches!(
+            received.as_slice(),
+            [
+                Event::SessionChanged(SessionId(0)),
+                Event::SessionChanged(SessionId(1))
+            ]
+        ));
+
+        // A subscriber that has dropped its receiver is removed.
+        drop(receiver);
+        model.push_event(Event::SessionChanged(SessionId(3)));
+        assert!(model.event_subscribers.is_empty());
+    }
+
+    #[test]
+    fn validate_model() {
+        let mut model = Model::default();
+        let mut session = Session::default();
+        for (id, position) in [(0, 1), (1, 2), (2, 3)] {
+            let mut entry = Entry {
+                id: EntryId(id),
+                position: Value::new(position),
+                current_driver: DriverId(0),
+                ..Default::default()
+            };
+            entry.drivers.insert(DriverId(0), Driver::default());
+            session.entries.insert(entry.id, entry);
+        }
+        let id = model.add_session(session);
+        model.current_session = Some(id);
+        assert_eq!(model.validate(), Vec::new());
+
+        let session = model.current_session_mut().unwrap();
+        session
+            .entries
+            .get_mut(&EntryId(1))
+            .unwrap()
+            .position
+            .set(3);
+        let entry = session.entries.get_mut(&EntryId(2)).unwrap();
+        entry.current_driver = DriverId(5);
+        entry.laps.push(Lap {
+            time: Value::new(Time::from(60_000)),
+            splits: Value::new(vec![Time::from(20_000), Time::from(-5_000)]),
+            ..Default::default()
+        });
+        model.current_session = Some(SessionId(4));
+
+        let warnings = model.validate();
+        assert_eq!(warnings.len(), 5);
+        assert!(warnings.contains(&ValidationWarning::CurrentSessionMissing(SessionId(4))));
+        assert!(warnings.contains(&ValidationWarning::DuplicatePosition {
+            session: id,
+            position: 3
+        }));
+        assert!(warnings.contains(&ValidationWarning::MissingPosition {
+            session: id,
+            position: 2
+        }));
+        assert!(warnings.contains(&ValidationWarning::CurrentDriverMissing {
+            session: id,
+            entry: EntryId(2)
+        }));
+        assert!(warnings.contains(&ValidationWarning::InvalidSplits {
+            session: id,
+            entry: EntryId(2),
+            lap_index: 0
+        }));
+    }
+}
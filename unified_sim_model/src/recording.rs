@@ -0,0 +1,252 @@
+//! A compact binary format to record snapshots of the model.
+//!
+//! A recording starts with a header made of the [`MAGIC`] bytes and the [`FORMAT_VERSION`]
+//! as a little endian `u16`. The header is followed by any number of frames.
+//! Each frame contains:
+//! - The time of the frame since the start of the recording in microseconds as a little endian `u64`.
+//! - The length of the model data in bytes as a little endian `u32`.
+//! - The model encoded with `bincode`.
+//!
+//! Only available with the `bincode` feature.
+
+use std::{
+    io::{self, ErrorKind, Read, Write},
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::model::Model;
+
+/// The magic bytes at the start of every recording.
+pub const MAGIC: [u8; 4] = *b"USMR";
+
+/// The version of the recording format.
+///
+/// Must be increased every time the layout of the model changes since older recordings
+/// cannot be decoded anymore.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// An error when reading or writing a recording.
+#[derive(Debug, Error)]
+pub enum RecordingError {
+    #[error("Io error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Cannot encode or decode model: {0}")]
+    Encoding(#[from] bincode::Error),
+    #[error("Data is not a recording")]
+    InvalidHeader,
+    #[error("Recording has version {0} but only version {FORMAT_VERSION} is supported")]
+    UnsupportedVersion(u16),
+    #[error("Frame is too large to be recorded")]
+    FrameTooLarge,
+}
+
+/// A single frame of a recording.
+#[derive(Debug)]
+pub struct Frame {
+    /// The time of this frame since the start of the recording.
+    pub time: Duration,
+    /// The model at the time of this frame.
+    pub model: Model,
+}
+
+/// Writes frames of a recording.
+pub struct RecordingWriter<W: Write> {
+    writer: W,
+}
+
+impl<W: Write> RecordingWriter<W> {
+    /// Create a new recording writer and write the header of the recording.
+    pub fn new(mut writer: W) -> Result<Self, RecordingError> {
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+        Ok(Self { writer })
+    }
+
+    /// Write a frame of the model.
+    /// `time` is the time of the frame since the start of the recording.
+    pub fn write_frame(&mut self, time: Duration, model: &Model) -> Result<(), RecordingError> {
+        let data = bincode::serialize(model)?;
+        let len = u32::try_from(data.len()).map_err(|_| RecordingError::FrameTooLarge)?;
+        self.writer
+            .write_all(&(time.as_micros() as u64).to_le_bytes())?;
+        self.writer.write_all(&len.to_le_bytes())?;
+        self.writer.write_all(&data)?;
+        Ok(())
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&mut self) -> Result<(), RecordingError> {
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads frames of a recording.
+pub struct RecordingReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> RecordingReader<R> {
+    /// Create a new recording reader.
+    ///
+    /// Returns an error if the header of the recording is invalid or
+    /// the recording has an incompatible version.
+    pub fn new(mut reader: R) -> Result<Self, RecordingError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => RecordingError::InvalidHeader,
+            _ => e.into(),
+        })?;
+        if magic != MAGIC {
+            return Err(RecordingError::InvalidHeader);
+        }
+        let mut version = [0; 2];
+        reader.read_exact(&mut version)?;
+        let version = u16::from_le_bytes(version);
+        if version != FORMAT_VERSION {
+            return Err(RecordingError::UnsupportedVersion(version));
+        }
+        Ok(Self { reader })
+    }
+
+    /// Read the next frame of the recording.
+    ///
+    /// Returns `None` when the end of the recording is reached.
+    /// A frame that was cut off while it was written is treated as the end of the recording.
+    pub fn read_frame(&mut self) -> Result<Option<Frame>, RecordingError> {
+        let mut time = [0; 8];
+        let mut len = [0; 4];
+        let header = self
+            .reader
+            .read_exact(&mut time)
+            .and_then(|_| self.reader.read_exact(&mut len));
+        match header {
+            Ok(()) => (),
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+
+        // Read through `take` so that a corrupt length cannot allocate more memory
+        // than there is data in the recording.
+        let len = u32::from_le_bytes(len) as usize;
+        let mut data = Vec::new();
+        self.reader
+            .by_ref()
+            .take(len as u64)
+            .read_to_end(&mut data)?;
+        if data.len() < len {
+            return Ok(None);
+        }
+
+        Ok(Some(Frame {
+            time: Duration::from_micros(u64::from_le_bytes(time)),
+            model: bincode::deserialize(&data)?,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        model::{Car, CarCategory, Entry, EntryId, Model, Nationality, Session, Value},
+        Time,
+    };
+
+    use super::*;
+
+    fn model(frame: i32) -> Model {
+        let mut model = Model::default();
+        model.event_name.set(format!("Frame {frame}"));
+        let mut session = Session::default();
+        session.time_remaining.set(Time::from(60_000 - frame * 16));
+        let entry = Entry {
+            id: EntryId(frame),
+            car: Value::new(Car::new_static(
+                "Car",
+                "Manufacturer",
+                CarCategory::new("GT3"),
+            )),
+            nationality: Value::new(Nationality::GERMANY),
+            ..Default::default()
+        };
+        session.entries.insert(entry.id, entry);
+        let id = model.add_session(session);
+        model.current_session = Some(id);
+        model
+    }
+
+    #[test]
+    fn round_trip_multiple_frames() {
+        let times = [
+            Duration::ZERO,
+            Duration::from_micros(16_667),
+            Duration::from_micros(33_334),
+        ];
+        let mut writer = RecordingWriter::new(Vec::new()).unwrap();
+        for (frame, time) in times.iter().enumerate() {
+            writer.write_frame(*time, &model(frame as i32)).unwrap();
+        }
+        let data = writer.into_inner();
+
+        let mut reader = RecordingReader::new(data.as_slice()).unwrap();
+        for (frame, time) in times.iter().enumerate() {
+            let Frame {
+                time: read_time,
+                model,
+            } = reader.read_frame().unwrap().unwrap();
+            assert_eq!(read_time, *time);
+            assert_eq!(*model.event_name, format!("Frame {frame}"));
+            let session = model.current_session().unwrap();
+            assert_eq!(
+                *session.time_remaining,
+                Time::from(60_000 - frame as i32 * 16)
+            );
+            let entry = &session.entries[&EntryId(frame as i32)];
+            assert_eq!(entry.car.name(), "Car");
+            assert_eq!(entry.car.category(), &CarCategory::new("GT3"));
+            assert_eq!(*entry.nationality, Nationality::GERMANY);
+        }
+        assert!(reader.read_frame().unwrap().is_none());
+    }
+
+    #[test]
+    fn reject_invalid_header() {
+        assert!(matches!(
+            RecordingReader::new(b"JSON".as_slice()),
+            Err(RecordingError::InvalidHeader)
+        ));
+        assert!(matches!(
+            RecordingReader::new(b"US".as_slice()),
+            Err(RecordingError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn reject_incompatible_version() {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            RecordingReader::new(data.as_slice()),
+            Err(RecordingError::UnsupportedVersion(version)) if version == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn truncated_frame_ends_recording() {
+        let mut data = RecordingWriter::new(Vec::new()).unwrap().into_inner();
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&u32::MAX.to_le_bytes());
+        data.extend_from_slice(&[0; 16]);
+        let mut reader = RecordingReader::new(data.as_slice()).unwrap();
+        assert!(reader.read_frame().unwrap().is_none());
+    }
+}
@@ -1,15 +1,19 @@
 mod angle;
+mod color;
 mod distance;
 mod pressure;
 mod speed;
 mod temperature;
 mod time;
+mod unit_system;
 mod weight;
 
 pub use angle::Angle;
+pub use color::Color;
 pub use distance::Distance;
 pub use pressure::Pressure;
 pub use speed::Speed;
 pub use temperature::Temperature;
-pub use time::Time;
+pub use time::{ParseTimeError, Time};
+pub use unit_system::UnitSystem;
 pub use weight::Weight;
@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 pub const RAD_TO_DEGREE: f32 = 57.2958;
 
 /// An angle value.
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy, Serialize, Deserialize)]
 pub struct Angle {
     /// The angle in radians
     pub rad: f32,
@@ -0,0 +1,80 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// A RGB color value.
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub struct Color {
+    /// The red component.
+    pub r: u8,
+    /// The green component.
+    pub g: u8,
+    /// The blue component.
+    pub b: u8,
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+impl Color {
+    /// A palette of clearly distinguishable colors to fall back on when no
+    /// other color is available.
+    pub const PALETTE: [Color; 12] = [
+        Color::from_rgb(0xe6, 0x19, 0x4b),
+        Color::from_rgb(0x3c, 0xb4, 0x4b),
+        Color::from_rgb(0xff, 0xe1, 0x19),
+        Color::from_rgb(0x43, 0x63, 0xd8),
+        Color::from_rgb(0xf5, 0x82, 0x31),
+        Color::from_rgb(0x91, 0x1e, 0xb4),
+        Color::from_rgb(0x42, 0xd4, 0xf4),
+        Color::from_rgb(0xf0, 0x32, 0xe6),
+        Color::from_rgb(0xbf, 0xef, 0x45),
+        Color::from_rgb(0xfa, 0xbe, 0xd4),
+        Color::from_rgb(0x46, 0x99, 0x90),
+        Color::from_rgb(0x9a, 0x63, 0x24),
+    ];
+
+    /// Create a color from its red, green and blue components.
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Create a color from a hex string like `ff8800` or `#ff8800`.
+    /// `None` if the string is not a valid color.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 {
+            return None;
+        }
+        let component = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        Some(Self::from_rgb(component(0)?, component(2)?, component(4)?))
+    }
+
+    /// Returns a color from the fallback palette.
+    /// The palette is cycled for indices larger than the palette.
+    pub fn from_palette(index: i32) -> Self {
+        Self::PALETTE[index.rem_euclid(Self::PALETTE.len() as i32) as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Color;
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(
+            Color::from_hex("ff8800"),
+            Some(Color::from_rgb(255, 136, 0))
+        );
+        assert_eq!(
+            Color::from_hex("#0a0B0c"),
+            Some(Color::from_rgb(10, 11, 12))
+        );
+        assert_eq!(Color::from_hex("ff88"), None);
+        assert_eq!(Color::from_hex("gg8800"), None);
+    }
+}
@@ -1,11 +1,14 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
+pub const METER_TO_MILLIMETER: f32 = 1000.0;
 pub const METER_TO_KILOMETER: f32 = 0.001;
 pub const METER_TO_MILE: f32 = 0.000621371;
 pub const METER_TO_FEET: f32 = 3.28084;
 
 /// A distance value
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy, Serialize, Deserialize)]
 pub struct Distance {
     /// The distance in meter.
     pub meter: f32,
@@ -24,6 +27,14 @@ impl Distance {
         Self { meter: v }
     }
 
+    /// Create a distance from millimeters.
+    #[allow(dead_code)]
+    pub fn from_millimeters(v: f32) -> Self {
+        Self {
+            meter: v / METER_TO_MILLIMETER,
+        }
+    }
+
     /// Create a distance from kilometers.
     #[allow(dead_code)]
     pub fn from_kilometers(v: f32) -> Self {
@@ -52,6 +63,12 @@ impl Distance {
         self.meter
     }
 
+    /// Return the distance in millimeter.
+    #[allow(dead_code)]
+    pub fn as_millimeters(&self) -> f32 {
+        self.meter * METER_TO_MILLIMETER
+    }
+
     /// Return the distance in kilometer.
     #[allow(dead_code)]
     pub fn as_kilometers(&self) -> f32 {
@@ -69,4 +86,28 @@ impl Distance {
     pub fn as_feet(&self) -> f32 {
         self.meter * METER_TO_FEET
     }
+
+    /// Format the distance for a car setup value like a ride height or toe.
+    /// Setup values are shown in millimeter with one decimal.
+    pub fn format_setup(&self) -> String {
+        format!("{:.1} mm", self.as_millimeters())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Distance;
+
+    #[test]
+    fn millimeter_round_trip() {
+        let distance = Distance::from_millimeters(52.5);
+        assert!((distance.as_meters() - 0.0525).abs() < 1e-6);
+        assert!((distance.as_millimeters() - 52.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn format_setup_value() {
+        assert_eq!(Distance::from_millimeters(52.34).format_setup(), "52.3 mm");
+        assert_eq!(Distance::from_millimeters(-1.0).format_setup(), "-1.0 mm");
+    }
 }
@@ -1,7 +1,11 @@
+use serde::{Deserialize, Serialize};
+
 pub const KPA_TO_INCHES_HG_AT_ZERO_C: f32 = 0.2953005;
+pub const KPA_TO_PSI: f32 = 0.1450377;
+pub const KPA_TO_BAR: f32 = 0.01;
 
 /// A pressure value
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy, Serialize, Deserialize)]
 pub struct Pressure {
     /// The pressure in kilo pacal
     pub kpa: f32,
@@ -22,6 +26,20 @@ impl Pressure {
         }
     }
 
+    /// Create a pressure from pounds per square inch.
+    pub fn from_psi(v: f32) -> Self {
+        Self {
+            kpa: v / KPA_TO_PSI,
+        }
+    }
+
+    /// Create a pressure from bar.
+    pub fn from_bar(v: f32) -> Self {
+        Self {
+            kpa: v / KPA_TO_BAR,
+        }
+    }
+
     /// Return the pressure in kilo pascal.
     pub fn as_kpa(&self) -> f32 {
         self.kpa
@@ -33,4 +51,14 @@ impl Pressure {
     pub fn as_inches_hg(&self) -> f32 {
         self.kpa * KPA_TO_INCHES_HG_AT_ZERO_C
     }
+
+    /// Return the pressure in bar.
+    pub fn as_bar(&self) -> f32 {
+        self.kpa * KPA_TO_BAR
+    }
+
+    /// Return the pressure in pounds per square inch.
+    pub fn as_psi(&self) -> f32 {
+        self.kpa * KPA_TO_PSI
+    }
 }
@@ -1,10 +1,12 @@
 use std::fmt::Display;
 
+use serde::{Deserialize, Serialize};
+
 pub const MS_TO_KMH: f32 = 3.6;
 pub const MS_TO_MPH: f32 = 2.23694;
 
 /// A speed value.
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy, Serialize, Deserialize)]
 pub struct Speed {
     /// The speed in meter per second.
     pub ms: f32,
@@ -1,7 +1,10 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 /// A Time value. Represented in milliseconds.
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy, Serialize, Deserialize)]
 pub struct Time {
     /// The time value as milliseconds.
     pub ms: f64,
@@ -28,12 +31,115 @@ impl From<f64> for Time {
     }
 }
 
+impl From<Duration> for Time {
+    /// Convert a duration to Time.
+    fn from(value: Duration) -> Self {
+        Self {
+            ms: value.as_secs_f64() * 1000.0,
+        }
+    }
+}
+
+impl From<Time> for Duration {
+    /// Convert a time to a duration.
+    ///
+    /// A duration cannot be negative. Negative times and NaN saturate to zero.
+    /// Times too large to be represented saturate to `Duration::MAX`.
+    fn from(value: Time) -> Self {
+        if value.ms.is_nan() || value.ms <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::try_from_secs_f64(value.ms / 1000.0).unwrap_or(Duration::MAX)
+    }
+}
+
 impl Display for Time {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.format())
     }
 }
 
+/// An error when parsing a time from a string.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ParseTimeError {
+    #[error("Time is empty")]
+    Empty,
+    #[error("Time has too many components")]
+    TooManyComponents,
+    #[error("Invalid number in time: {0:?}")]
+    InvalidNumber(String),
+    #[error("Value out of range in time: {0:?}")]
+    OutOfRange(String),
+}
+
+impl FromStr for Time {
+    type Err = ParseTimeError;
+
+    /// Parse a time from a string in the format hh:mm:ss.ms.
+    /// This is the inverse of `format`.
+    ///
+    /// Leading components can be left out. Minutes and seconds must be below 60 if a larger
+    /// component is present. The milliseconds are optional and can have up to three digits.
+    /// ```
+    /// use unified_sim_model::Time;
+    /// assert_eq!("1:23.456".parse::<Time>(), Ok(Time::from(83_456)));
+    /// assert_eq!("83.456".parse::<Time>(), Ok(Time::from(83_456)));
+    /// assert_eq!("1:23".parse::<Time>(), Ok(Time::from(83_000)));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (sign, s) = match s.strip_prefix('-') {
+            Some(s) => (-1.0, s),
+            None => (1.0, s),
+        };
+        if s.is_empty() {
+            return Err(ParseTimeError::Empty);
+        }
+
+        let (hms, ms) = match s.split_once('.') {
+            Some((hms, ms)) => (hms, Some(ms)),
+            None => (s, None),
+        };
+
+        let ms = match ms {
+            Some(ms) if (1..=3).contains(&ms.len()) => {
+                // Pad to three digits so that ".4" is read as 400 milliseconds.
+                parse_number(&format!("{ms:0<3}"))?
+            }
+            Some(ms) => return Err(ParseTimeError::InvalidNumber(ms.to_owned())),
+            None => 0,
+        };
+
+        let components = hms
+            .split(':')
+            .map(parse_number)
+            .collect::<Result<Vec<_>, _>>()?;
+        if components.len() > 3 {
+            return Err(ParseTimeError::TooManyComponents);
+        }
+        // Every component except the first one is limited to 60.
+        if let Some(component) = components.iter().skip(1).find(|c| **c >= 60) {
+            return Err(ParseTimeError::OutOfRange(component.to_string()));
+        }
+        let ms = components
+            .iter()
+            .try_fold(0i64, |secs, c| secs.checked_mul(60)?.checked_add(*c))
+            .and_then(|secs| secs.checked_mul(1000)?.checked_add(ms))
+            .ok_or_else(|| ParseTimeError::OutOfRange(s.to_owned()))?;
+
+        Ok(Time::from(sign * ms as f64))
+    }
+}
+
+/// Parse a component of a time. Only accepts ascii digits.
+fn parse_number(s: &str) -> Result<i64, ParseTimeError> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(ParseTimeError::InvalidNumber(s.to_owned()));
+    }
+    s.parse()
+        .map_err(|_| ParseTimeError::OutOfRange(s.to_owned()))
+}
+
 impl Time {
     /// Create a time from seconds.
     pub fn from_secs(secs: impl Into<f64>) -> Self {
@@ -151,4 +257,89 @@ mod tests {
         let time = crate::types::Time::from(-3_661_001);
         assert_eq!(time.format(), "-1:01:01.001");
     }
+
+    #[test]
+    fn convert_duration() {
+        use crate::types::Time;
+        use std::time::Duration;
+        assert_eq!(
+            Time::from(Duration::from_millis(83_456)),
+            Time::from(83_456)
+        );
+        assert_eq!(
+            Duration::from(Time::from(83_456)),
+            Duration::from_millis(83_456)
+        );
+        assert_eq!(
+            Duration::from(Time::from(1.5_f64)),
+            Duration::from_micros(1_500)
+        );
+    }
+
+    #[test]
+    fn convert_duration_saturates() {
+        use crate::types::Time;
+        use std::time::Duration;
+        // A negative gap has no duration.
+        assert_eq!(Duration::from(Time::from(-1_000)), Duration::ZERO);
+        assert_eq!(Duration::from(Time::from(f64::NAN)), Duration::ZERO);
+        assert_eq!(Duration::from(Time::from(f64::INFINITY)), Duration::MAX);
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        for s in [
+            "0.123",
+            "9.000",
+            "59.999",
+            "1:23.456",
+            "12:34:56.789",
+            "-1:02.003",
+        ] {
+            let time: crate::types::Time = s.parse().unwrap();
+            assert_eq!(time.format(), s);
+        }
+    }
+
+    #[test]
+    fn parse_partial() {
+        use crate::types::Time;
+        assert_eq!("83.456".parse(), Ok(Time::from(83_456)));
+        assert_eq!("1:23".parse(), Ok(Time::from(83_000)));
+        assert_eq!("1:23.4".parse(), Ok(Time::from(83_400)));
+        assert_eq!("1:00:00".parse(), Ok(Time::from(3_600_000)));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        use crate::types::{ParseTimeError, Time};
+        assert_eq!("".parse::<Time>(), Err(ParseTimeError::Empty));
+        assert_eq!("-".parse::<Time>(), Err(ParseTimeError::Empty));
+        assert_eq!(
+            "abc".parse::<Time>(),
+            Err(ParseTimeError::InvalidNumber("abc".to_owned()))
+        );
+        assert_eq!(
+            "1:2x.3".parse::<Time>(),
+            Err(ParseTimeError::InvalidNumber("2x".to_owned()))
+        );
+        assert_eq!(
+            "1.2345".parse::<Time>(),
+            Err(ParseTimeError::InvalidNumber("2345".to_owned()))
+        );
+        assert_eq!(
+            "1:60.000".parse::<Time>(),
+            Err(ParseTimeError::OutOfRange("60".to_owned()))
+        );
+        assert_eq!(
+            "1:1:1:1".parse::<Time>(),
+            Err(ParseTimeError::TooManyComponents)
+        );
+        assert_eq!(
+            "9223372036854775807:00".parse::<Time>(),
+            Err(ParseTimeError::OutOfRange(
+                "9223372036854775807:00".to_owned()
+            ))
+        );
+    }
 }
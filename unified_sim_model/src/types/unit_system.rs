@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Pressure, Speed, Temperature};
+
+/// A system of units to show values in.
+///
+/// The typed values like [`Temperature`] or [`Speed`] always store their value in one
+/// unit and can be converted into any other unit. A unit system is a convenience for
+/// simple consumers that want plain numbers in a preferred unit without converting
+/// every value themself.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnitSystem {
+    /// Celcius, kilometers per hour and kilo pascal.
+    #[default]
+    Metric,
+    /// Fahrenheit, miles per hour and pounds per square inch.
+    Imperial,
+}
+
+impl UnitSystem {
+    /// Returns the temperature in this unit system.
+    pub fn temperature(&self, temperature: Temperature) -> f32 {
+        match self {
+            UnitSystem::Metric => temperature.as_celcius(),
+            UnitSystem::Imperial => temperature.as_fahrenheit(),
+        }
+    }
+
+    /// Returns the speed in this unit system.
+    pub fn speed(&self, speed: Speed) -> f32 {
+        match self {
+            UnitSystem::Metric => speed.as_kmh(),
+            UnitSystem::Imperial => speed.as_mph(),
+        }
+    }
+
+    /// Returns the pressure in this unit system.
+    pub fn pressure(&self, pressure: Pressure) -> f32 {
+        match self {
+            UnitSystem::Metric => pressure.as_kpa(),
+            UnitSystem::Imperial => pressure.as_psi(),
+        }
+    }
+
+    /// Returns the symbol of the temperature unit.
+    pub fn temperature_unit(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "°C",
+            UnitSystem::Imperial => "°F",
+        }
+    }
+
+    /// Returns the symbol of the speed unit.
+    pub fn speed_unit(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "km/h",
+            UnitSystem::Imperial => "mph",
+        }
+    }
+
+    /// Returns the symbol of the pressure unit.
+    pub fn pressure_unit(&self) -> &'static str {
+        match self {
+            UnitSystem::Metric => "kPa",
+            UnitSystem::Imperial => "psi",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tests::assert_close, Pressure, Speed, Temperature};
+
+    use super::UnitSystem;
+
+    #[test]
+    fn convert_to_unit_system() {
+        let temperature = Temperature::from_celcius(100.0);
+        let speed = Speed::from_kmh(160.0);
+        let pressure = Pressure::from_kpa(172.0);
+
+        assert_close(UnitSystem::Metric.temperature(temperature), 100.0);
+        assert_close(UnitSystem::Imperial.temperature(temperature), 212.0);
+        assert_close(UnitSystem::Metric.speed(speed), 160.0);
+        assert_close(UnitSystem::Imperial.speed(speed), 99.419);
+        assert_close(UnitSystem::Metric.pressure(pressure), 172.0);
+        assert_close(UnitSystem::Imperial.pressure(pressure), 24.946);
+    }
+}
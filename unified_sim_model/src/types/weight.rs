@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 pub const KG_TO_LBS: f32 = 2.20462;
 
 /// A weight value.
-#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy)]
+#[derive(Debug, Default, PartialEq, PartialOrd, Clone, Copy, Serialize, Deserialize)]
 pub struct Weight {
     /// The weight in kg.
     pub kg: f32,